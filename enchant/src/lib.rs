@@ -0,0 +1,296 @@
+//! An [Enchant](https://abiword.github.io/enchant/) provider, so anything
+//! using Enchant (GTK apps, LibreOffice, ...) can check spelling with this
+//! crate.
+//!
+//! Build it with `cargo build --release -p spelling-enchant` and copy
+//! `libenchant_spelling.so` into Enchant's module directory as
+//! `enchant_spelling.so`. Word lists are newline separated files named after
+//! the language tag (`en_US.txt`, or `en.txt` for every `en_*` tag) in the
+//! directories listed in `SPELLING_DICT_PATH`, separated by `:`, or
+//! `/usr/share/spelling` when it isn't set.
+//!
+//! The struct layouts follow `enchant-provider.h` from Enchant 2.2.
+
+use std::collections::HashSet;
+use std::ffi::{c_void, CStr};
+use std::os::raw::{c_char, c_int};
+use std::path::PathBuf;
+
+use spelling::{Dictionary, SpellChecker};
+
+/// How many edits away suggestions can be.
+const SUGGESTION_DISTANCE: usize = 2;
+/// The most suggestions returned for one word.
+const MAX_SUGGESTIONS: usize = 15;
+
+/// `EnchantDict`
+#[repr(C)]
+pub struct EnchantDict {
+    user_data: *mut c_void,
+    enchant_private_data: *mut c_void,
+    check: Option<unsafe extern "C" fn(*mut EnchantDict, *const c_char, usize) -> c_int>,
+    suggest: Option<unsafe extern "C" fn(*mut EnchantDict, *const c_char, usize, *mut usize) -> *mut *mut c_char>,
+    add_to_personal: Option<unsafe extern "C" fn(*mut EnchantDict, *const c_char, usize)>,
+    add_to_session: Option<unsafe extern "C" fn(*mut EnchantDict, *const c_char, usize)>,
+    store_replacement: Option<unsafe extern "C" fn(*mut EnchantDict, *const c_char, usize, *const c_char, usize)>,
+    add_to_exclude: Option<unsafe extern "C" fn(*mut EnchantDict, *const c_char, usize)>,
+    get_extra_word_characters: Option<unsafe extern "C" fn(*mut EnchantDict) -> *const c_char>,
+    is_word_character: Option<unsafe extern "C" fn(*mut EnchantDict, u32, usize) -> c_int>,
+    _reserved: [*mut c_void; 3],
+}
+
+/// `EnchantProvider`
+#[repr(C)]
+pub struct EnchantProvider {
+    user_data: *mut c_void,
+    enchant_private_data: *mut c_void,
+    owner: *mut c_void,
+    dispose: Option<unsafe extern "C" fn(*mut EnchantProvider)>,
+    request_dict: Option<unsafe extern "C" fn(*mut EnchantProvider, *const c_char) -> *mut EnchantDict>,
+    dispose_dict: Option<unsafe extern "C" fn(*mut EnchantProvider, *mut EnchantDict)>,
+    dictionary_exists: Option<unsafe extern "C" fn(*mut EnchantProvider, *const c_char) -> c_int>,
+    identify: Option<unsafe extern "C" fn(*mut EnchantProvider) -> *const c_char>,
+    describe: Option<unsafe extern "C" fn(*mut EnchantProvider) -> *const c_char>,
+    list_dicts: Option<unsafe extern "C" fn(*mut EnchantProvider, *mut usize) -> *mut *mut c_char>,
+    _reserved: [*mut c_void; 5],
+}
+
+/// What an `EnchantDict`'s `user_data` points to.
+struct Checker {
+    checker: SpellChecker,
+    words: HashSet<String>,
+    session: HashSet<String>,
+}
+
+extern "C" {
+    // Enchant frees string lists with g_strfreev, which uses the system
+    // allocator, so they can't come from Rust's
+    fn malloc(size: usize) -> *mut c_void;
+}
+
+/// The provider's entry point, called by Enchant when it loads the module.
+#[no_mangle]
+pub extern "C" fn init_enchant_provider() -> *mut EnchantProvider {
+    Box::into_raw(Box::new(EnchantProvider {
+        user_data: std::ptr::null_mut(),
+        enchant_private_data: std::ptr::null_mut(),
+        owner: std::ptr::null_mut(),
+        dispose: Some(dispose),
+        request_dict: Some(request_dict),
+        dispose_dict: Some(dispose_dict),
+        dictionary_exists: Some(dictionary_exists),
+        identify: Some(identify),
+        describe: Some(describe),
+        list_dicts: Some(list_dicts),
+        _reserved: [std::ptr::null_mut(); 5],
+    }))
+}
+
+unsafe extern "C" fn dispose(provider: *mut EnchantProvider) {
+    drop(Box::from_raw(provider));
+}
+
+unsafe extern "C" fn identify(_provider: *mut EnchantProvider) -> *const c_char {
+    b"spelling\0".as_ptr().cast()
+}
+
+unsafe extern "C" fn describe(_provider: *mut EnchantProvider) -> *const c_char {
+    b"spelling Provider\0".as_ptr().cast()
+}
+
+/// The directories word lists are looked up in.
+fn dictionary_dirs() -> Vec<PathBuf> {
+    match std::env::var_os("SPELLING_DICT_PATH") {
+        Some(paths) => std::env::split_paths(&paths).collect(),
+        None => vec![PathBuf::from("/usr/share/spelling")],
+    }
+}
+
+/// The word list for `tag`, trying the language on its own (`en` for
+/// `en_US`) if there's nothing for the full tag.
+fn find_dictionary(tag: &str) -> Option<PathBuf> {
+    let language = tag.split(['_', '-']).next().unwrap_or(tag);
+    [tag, language].iter().find_map(|name| {
+        dictionary_dirs()
+            .into_iter()
+            .map(|dir| dir.join(format!("{}.txt", name)))
+            .find(|path| path.is_file())
+    })
+}
+
+unsafe extern "C" fn dictionary_exists(_provider: *mut EnchantProvider, tag: *const c_char) -> c_int {
+    match CStr::from_ptr(tag).to_str() {
+        Ok(tag) => find_dictionary(tag).is_some() as c_int,
+        Err(_) => 0,
+    }
+}
+
+unsafe extern "C" fn request_dict(_provider: *mut EnchantProvider, tag: *const c_char) -> *mut EnchantDict {
+    let path = match CStr::from_ptr(tag).to_str().ok().and_then(find_dictionary) {
+        Some(path) => path,
+        None => return std::ptr::null_mut(),
+    };
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    let dictionary = Dictionary::from_newline_separated(&contents);
+    let checker = Checker {
+        words: dictionary.iter().map(str::to_owned).collect(),
+        checker: SpellChecker::from_dictionary(dictionary),
+        session: HashSet::new(),
+    };
+    Box::into_raw(Box::new(EnchantDict {
+        user_data: Box::into_raw(Box::new(checker)).cast(),
+        enchant_private_data: std::ptr::null_mut(),
+        check: Some(check),
+        suggest: Some(suggest),
+        add_to_personal: None,
+        add_to_session: Some(add_to_session),
+        store_replacement: None,
+        add_to_exclude: None,
+        get_extra_word_characters: None,
+        is_word_character: None,
+        _reserved: [std::ptr::null_mut(); 3],
+    }))
+}
+
+unsafe extern "C" fn dispose_dict(_provider: *mut EnchantProvider, dict: *mut EnchantDict) {
+    let dict = Box::from_raw(dict);
+    drop(Box::from_raw(dict.user_data.cast::<Checker>()));
+}
+
+/// The word Enchant passed in, which isn't nul terminated.
+unsafe fn word<'a>(word: *const c_char, len: usize) -> Option<&'a str> {
+    std::str::from_utf8(std::slice::from_raw_parts(word.cast(), len)).ok()
+}
+
+unsafe fn checker<'a>(dict: *mut EnchantDict) -> &'a mut Checker {
+    &mut *(*dict).user_data.cast::<Checker>()
+}
+
+/// 0 if the word is correct, 1 if it isn't, -1 on error.
+unsafe extern "C" fn check(dict: *mut EnchantDict, text: *const c_char, len: usize) -> c_int {
+    let checker = checker(dict);
+    match word(text, len) {
+        Some(text) => (!checker.words.contains(text) && !checker.session.contains(text)) as c_int,
+        None => -1,
+    }
+}
+
+unsafe extern "C" fn suggest(
+    dict: *mut EnchantDict,
+    text: *const c_char,
+    len: usize,
+    out_n_suggs: *mut usize,
+) -> *mut *mut c_char {
+    let checker = checker(dict);
+    let suggestions = match word(text, len) {
+        Some(text) => checker.checker.suggest(text, SUGGESTION_DISTANCE),
+        None => Vec::new(),
+    };
+    let suggestions = &suggestions[..suggestions.len().min(MAX_SUGGESTIONS)];
+    string_list(suggestions, out_n_suggs)
+}
+
+unsafe extern "C" fn add_to_session(dict: *mut EnchantDict, text: *const c_char, len: usize) {
+    if let Some(text) = word(text, len) {
+        checker(dict).session.insert(text.to_owned());
+    }
+}
+
+unsafe extern "C" fn list_dicts(_provider: *mut EnchantProvider, out_n_dicts: *mut usize) -> *mut *mut c_char {
+    let mut tags: Vec<String> = dictionary_dirs()
+        .into_iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            match path.extension() {
+                Some(extension) if extension == "txt" => Some(path.file_stem()?.to_str()?.to_owned()),
+                _ => None,
+            }
+        })
+        .collect();
+    tags.sort_unstable();
+    tags.dedup();
+    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    string_list(&tags, out_n_dicts)
+}
+
+/// A `NULL` terminated list of `malloc`ed C strings, or `NULL` when empty.
+/// Strings with a nul in them are left out.
+unsafe fn string_list(strings: &[&str], out_n: *mut usize) -> *mut *mut c_char {
+    let strings: Vec<&str> = strings.iter().copied().filter(|string| !string.contains('\0')).collect();
+    *out_n = strings.len();
+    if strings.is_empty() {
+        return std::ptr::null_mut();
+    }
+    let list = malloc((strings.len() + 1) * std::mem::size_of::<*mut c_char>()).cast::<*mut c_char>();
+    if list.is_null() {
+        *out_n = 0;
+        return list;
+    }
+    for (index, string) in strings.iter().enumerate() {
+        let copy = malloc(string.len() + 1).cast::<u8>();
+        if !copy.is_null() {
+            std::ptr::copy_nonoverlapping(string.as_ptr(), copy, string.len());
+            *copy.add(string.len()) = 0;
+        }
+        *list.add(index) = copy.cast();
+    }
+    *list.add(strings.len()) = std::ptr::null_mut();
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    extern "C" {
+        fn free(pointer: *mut std::ffi::c_void);
+    }
+
+    unsafe fn take_list(list: *mut *mut std::os::raw::c_char, n: usize) -> Vec<String> {
+        let mut out = Vec::new();
+        for index in 0..n {
+            let string = *list.add(index);
+            out.push(std::ffi::CStr::from_ptr(string).to_str().unwrap().to_owned());
+            free(string.cast());
+        }
+        assert!((*list.add(n)).is_null());
+        free(list.cast());
+        out
+    }
+
+    #[test]
+    fn provider() {
+        let dir = std::env::temp_dir().join(format!("spelling-enchant-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("en.txt"), "thin\nthing\n").unwrap();
+        std::env::set_var("SPELLING_DICT_PATH", &dir);
+        unsafe {
+            let provider = super::init_enchant_provider();
+            let p = &*provider;
+            let mut n = 0;
+            let tags = take_list((p.list_dicts.unwrap())(provider, &mut n), n);
+            assert_eq!(tags, vec!["en"]);
+            assert_eq!((p.dictionary_exists.unwrap())(provider, b"en_GB\0".as_ptr().cast()), 1);
+            assert_eq!((p.dictionary_exists.unwrap())(provider, b"fr\0".as_ptr().cast()), 0);
+
+            let dict = (p.request_dict.unwrap())(provider, b"en_US\0".as_ptr().cast());
+            let d = &*dict;
+            assert_eq!((d.check.unwrap())(dict, b"thing".as_ptr().cast(), 5), 0);
+            assert_eq!((d.check.unwrap())(dict, b"thng!".as_ptr().cast(), 4), 1);
+            assert_eq!((d.check.unwrap())(dict, b"\xff".as_ptr().cast(), 1), -1);
+            let suggestions = (d.suggest.unwrap())(dict, b"thng".as_ptr().cast(), 4, &mut n);
+            assert_eq!(take_list(suggestions, n), vec!["thing", "thin"]);
+            (d.add_to_session.unwrap())(dict, b"thng".as_ptr().cast(), 4);
+            assert_eq!((d.check.unwrap())(dict, b"thng".as_ptr().cast(), 4), 0);
+            assert!((d.suggest.unwrap())(dict, b"qqqqqq".as_ptr().cast(), 6, &mut n).is_null());
+            assert_eq!(n, 0);
+
+            (p.dispose_dict.unwrap())(provider, dict);
+            (p.dispose.unwrap())(provider);
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use spelling::levenshtein_distance;
+
+fuzz_target!(|input: (String, String, String)| {
+    let (a, b, c) = input;
+    let a_len = a.chars().count();
+    let b_len = b.chars().count();
+    let distance = levenshtein_distance(&a, &b);
+
+    // metric properties
+    assert_eq!(levenshtein_distance(&a, &a), 0);
+    assert_eq!(distance == 0, a == b);
+    assert_eq!(distance, levenshtein_distance(&b, &a));
+    assert!(distance <= levenshtein_distance(&a, &c) + levenshtein_distance(&c, &b));
+
+    // bounds
+    assert!(distance >= a_len.max(b_len) - a_len.min(b_len));
+    assert!(distance <= a_len.max(b_len));
+});
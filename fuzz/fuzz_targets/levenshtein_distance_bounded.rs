@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use spelling::{levenshtein_distance, levenshtein_distance_bounded, Bounded};
+
+fuzz_target!(|input: (String, String, usize)| {
+    let (a, b, max) = input;
+    let bounded = levenshtein_distance_bounded(&a, &b, max);
+    assert_eq!(bounded, levenshtein_distance_bounded(&b, &a, max));
+
+    let distance = levenshtein_distance(&a, &b);
+    if distance <= max {
+        assert_eq!(bounded, Bounded::Within(distance));
+    } else {
+        assert_eq!(bounded, Bounded::Exceeded);
+    }
+});
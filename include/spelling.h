@@ -0,0 +1,51 @@
+#ifndef SPELLING_H
+#define SPELLING_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit by hand. */
+
+#include <stddef.h>
+
+// A spellchecker, see [`SpellChecker`](crate::SpellChecker). Only ever
+// handled through a pointer.
+typedef struct SpellingChecker SpellingChecker;
+
+// Suggestions returned by [`spelling_suggest`], freed with
+// [`spelling_free_results`].
+typedef struct SpellingResults {
+  // `len` nul terminated UTF-8 words, best first, or `NULL` when `len` is 0.
+  char **words;
+  size_t len;
+} SpellingResults;
+
+// Creates a checker from a nul terminated, newline separated word list.
+// Returns `NULL` if `dictionary` is `NULL` or isn't UTF-8.
+//
+// # Safety
+// `dictionary` has to be `NULL` or point to a nul terminated string.
+SpellingChecker *spelling_create_checker(const char *dictionary);
+
+// Frees a checker made by [`spelling_create_checker`]. Does nothing for
+// `NULL`.
+//
+// # Safety
+// `checker` has to be `NULL` or come from [`spelling_create_checker`], and
+// can't be used afterwards.
+void spelling_free_checker(SpellingChecker *checker);
+
+// The words within `max_distance` of the nul terminated `word`, sorted like
+// [`SpellChecker::suggest`](crate::SpellChecker::suggest). Empty if either
+// pointer is `NULL` or `word` isn't UTF-8.
+//
+// # Safety
+// `checker` has to be `NULL` or come from [`spelling_create_checker`], and
+// `word` has to be `NULL` or point to a nul terminated string.
+SpellingResults spelling_suggest(const SpellingChecker *checker, const char *word, size_t max_distance);
+
+// Frees the words returned by [`spelling_suggest`].
+//
+// # Safety
+// `results` has to come from [`spelling_suggest`], and can't be used
+// afterwards.
+void spelling_free_results(SpellingResults results);
+
+#endif /* SPELLING_H */
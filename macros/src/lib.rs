@@ -0,0 +1,79 @@
+//! Procedural macros for [spelling](https://crates.io/crates/spelling). Use
+//! them through spelling's `macros` feature rather than depending on this
+//! crate directly.
+
+use proc_macro::{TokenStream, TokenTree};
+use std::path::Path;
+
+/// Reads a newline separated word list at compile time and expands to a
+/// `spelling::StaticDictionary`, sorted and bucketed by length, so nothing is
+/// parsed at runtime. The path is relative to the crate's `Cargo.toml`.
+///
+/// Words are cleaned up the same way as `Dictionary::from_newline_separated`:
+/// whitespace is trimmed and blank lines are skipped.
+#[proc_macro]
+pub fn include_dict(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(message) => format!("compile_error!({:?})", message)
+            .parse()
+            .expect("compile_error! invocation parses"),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let mut tokens = input.into_iter();
+    let path = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => string_literal(&literal.to_string())?,
+        _ => return Err("include_dict! takes a single string literal path".to_owned()),
+    };
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "include_dict! needs CARGO_MANIFEST_DIR to be set".to_owned())?;
+    let path = Path::new(&root).join(path);
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|error| format!("couldn't read {}: {}", path.display(), error))?;
+
+    let mut words: Vec<(usize, &str)> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(|word| (word.chars().count(), word))
+        .collect();
+    words.sort_unstable();
+
+    // buckets[n] is the index of the first word with n chars
+    let longest = words.last().map_or(0, |&(length, _)| length);
+    let mut buckets = vec![0; longest + 2];
+    for &(length, _) in &words {
+        buckets[length + 1] += 1;
+    }
+    for index in 1..buckets.len() {
+        buckets[index] += buckets[index - 1];
+    }
+
+    let words: Vec<String> = words.iter().map(|(_, word)| format!("{:?}", word)).collect();
+    let buckets: Vec<String> = buckets.iter().map(usize::to_string).collect();
+    // the include_str! makes cargo rebuild when the word list changes
+    format!(
+        "{{ const _: &str = include_str!({:?}); ::spelling::StaticDictionary::__from_parts(&[{}], &[{}]) }}",
+        path.display().to_string(),
+        words.join(", "),
+        buckets.join(", "),
+    )
+    .parse()
+    .map_err(|error| format!("include_dict! generated invalid code: {:?}", error))
+}
+
+/// The value of a string literal without escapes, raw or not.
+fn string_literal(literal: &str) -> Result<String, String> {
+    let raw = literal.strip_prefix('r').map(|rest| rest.trim_matches('#'));
+    let contents = raw
+        .unwrap_or(literal)
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a string literal path, found {}", literal))?;
+    if raw.is_none() && contents.contains('\\') {
+        return Err("include_dict! doesn't support escapes in the path, use a raw string".to_owned());
+    }
+    Ok(contents.to_owned())
+}
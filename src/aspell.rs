@@ -0,0 +1,131 @@
+//! The Ispell/Aspell pipe protocol (`aspell -a`), which Emacs' flyspell, Vim
+//! and other editors use to talk to a spellchecker.
+
+use crate::check::SpellChecker;
+use crate::distance::DistanceMetric;
+
+/// The first line of output, editors check it to know they're talking to
+/// something Ispell compatible.
+pub const PIPE_BANNER: &str = concat!(
+    "@(#) International Ispell Version 3.1.20 (but really spelling ",
+    env!("CARGO_PKG_VERSION"),
+    ")"
+);
+
+/// One editor session in pipe mode. Words accepted with `@` or added with
+/// `*` are remembered for the rest of the session.
+/// ```
+/// use spelling::{PipeSession, SpellChecker};
+/// let checker = SpellChecker::new("thin\nthing");
+/// let mut output = Vec::new();
+/// PipeSession::new(&checker, 1).run("thin thng\n".as_bytes(), &mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert_eq!(output.lines().skip(1).collect::<Vec<_>>(), vec!["*", "& thng 1 5: thing", ""]);
+/// ```
+#[derive(Debug)]
+pub struct PipeSession<'a, M> {
+    checker: &'a SpellChecker<M>,
+    distance: usize,
+    accepted: std::collections::HashSet<String>,
+    terse: bool,
+}
+
+impl<'a, M: DistanceMetric> PipeSession<'a, M> {
+    /// Starts a session suggesting words within `distance`.
+    pub fn new(checker: &'a SpellChecker<M>, distance: usize) -> Self {
+        PipeSession {
+            checker,
+            distance,
+            accepted: std::collections::HashSet::new(),
+            terse: false,
+        }
+    }
+
+    /// Writes the banner and then answers every line of `input` until it
+    /// ends.
+    pub fn run(
+        &mut self,
+        input: impl std::io::BufRead,
+        mut output: impl std::io::Write,
+    ) -> std::io::Result<()> {
+        writeln!(output, "{}", PIPE_BANNER)?;
+        for line in input.lines() {
+            self.handle_line(&line?, &mut output)?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Answers one line of input. Lines starting with one of `*&@#!%^+-~` are
+    /// commands, anything else is checked:
+    ///
+    /// - `*` for a correct word, left out in terse mode (`!`, `%` turns it off)
+    /// - `& word count offset: suggestion, ...` for a misspelling
+    /// - `# word offset` for a misspelling without suggestions
+    ///
+    /// followed by an empty line. Offsets are 0 based `char` offsets into the
+    /// line without its `^` prefix.
+    pub fn handle_line(&mut self, line: &str, output: &mut impl std::io::Write) -> std::io::Result<()> {
+        let mut chars = line.chars();
+        let text = match chars.next() {
+            Some('*') | Some('&') | Some('@') => {
+                self.accepted.insert(chars.as_str().trim().to_owned());
+                return Ok(());
+            }
+            Some('!') => {
+                self.terse = true;
+                return Ok(());
+            }
+            Some('%') => {
+                self.terse = false;
+                return Ok(());
+            }
+            // saving the personal dictionary, TeX mode and formatter
+            // selection don't apply
+            Some('#') | Some('+') | Some('-') | Some('~') => return Ok(()),
+            Some('^') => chars.as_str(),
+            _ => line,
+        };
+
+        let mut candidates = Vec::new();
+        for (start, word) in crate::tokenize::words(text) {
+            if !word.chars().any(char::is_alphabetic) || self.accepted.contains(word) {
+                continue;
+            }
+            let offset = text[..start].chars().count();
+            match self.checker.check_word(start, word, self.distance, &mut candidates) {
+                None if self.terse => {}
+                None => writeln!(output, "*")?,
+                Some(misspelling) if misspelling.suggestions.is_empty() => {
+                    writeln!(output, "# {} {}", word, offset)?
+                }
+                Some(misspelling) => writeln!(
+                    output,
+                    "& {} {} {}: {}",
+                    word,
+                    misspelling.suggestions.len(),
+                    offset,
+                    misspelling.suggestions.join(", ")
+                )?,
+            }
+        }
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn pipe_session() {
+        let checker = crate::SpellChecker::new("thin\nthing\nnaïve");
+        let mut session = crate::PipeSession::new(&checker, 1);
+        let mut output = Vec::new();
+        for line in &["naïve thng 42 qqqq", "!", "^thin thng", "@thng", "thng", "%", "thin"] {
+            session.handle_line(line, &mut output).unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "*\n& thng 1 6: thing\n# qqqq 14\n\n& thng 1 5: thing\n\n\n*\n\n"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! [Levenshtein automata](https://en.wikipedia.org/wiki/Levenshtein_automaton),
+//! which find every dictionary word within a distance by walking a
+//! [`Dawg`] of the dictionary instead of measuring each word.
+
+use crate::dawg::{Dawg, DawgNode};
+use crate::dict::Dictionary;
+
+/// Accepts the strings within `max_distance` Levenshtein edits of a query,
+/// fed one `char` at a time. A state is the query's row of the distance
+/// table, capped at `max_distance + 1`, so strings sharing a prefix share
+/// the work and a prefix that can't lead to a match is dropped as soon as
+/// it's seen.
+/// ```
+/// use spelling::LevenshteinAutomaton;
+/// let automaton = LevenshteinAutomaton::new("thing", 1);
+/// let state = "thin".chars().fold(automaton.start(), |state, c| automaton.step(&state, c));
+/// assert_eq!(automaton.distance(&state), Some(1));
+/// let state = automaton.step(&automaton.step(&automaton.start(), 'x'), 'x');
+/// assert!(!automaton.can_match(&state));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LevenshteinAutomaton {
+    query: Vec<char>,
+    max_distance: usize,
+}
+
+/// A state of a [`LevenshteinAutomaton`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AutomatonState(pub(crate) Vec<usize>);
+
+impl LevenshteinAutomaton {
+    /// An automaton for the strings within `max_distance` of `query`.
+    pub fn new(query: &str, max_distance: usize) -> Self {
+        LevenshteinAutomaton {
+            query: query.chars().collect(),
+            max_distance,
+        }
+    }
+
+    /// The state before any input.
+    pub fn start(&self) -> AutomatonState {
+        let cap = self.max_distance.saturating_add(1);
+        AutomatonState((0..=self.query.len()).map(|distance| distance.min(cap)).collect())
+    }
+
+    /// The state after reading `c` in `state`.
+    pub fn step(&self, state: &AutomatonState, c: char) -> AutomatonState {
+        let mut row = vec![0; state.0.len()];
+        self.step_into(&state.0, c, &mut row);
+        AutomatonState(row)
+    }
+
+    /// [`step`](LevenshteinAutomaton::step) on raw rows, writing into `row`.
+    pub(crate) fn step_into(&self, previous: &[usize], c: char, row: &mut [usize]) {
+        let cap = self.max_distance.saturating_add(1);
+        row[0] = (previous[0] + 1).min(cap);
+        for (index, &query_char) in self.query.iter().enumerate() {
+            let substitution = previous[index] + (query_char != c) as usize;
+            let deletion = row[index] + 1;
+            let insertion = previous[index + 1] + 1;
+            row[index + 1] = substitution.min(deletion).min(insertion).min(cap);
+        }
+    }
+
+    /// The distance from the query to the input read to reach `state`, if
+    /// it's within `max_distance`.
+    pub fn distance(&self, state: &AutomatonState) -> Option<usize> {
+        self.row_distance(&state.0)
+    }
+
+    pub(crate) fn row_distance(&self, row: &[usize]) -> Option<usize> {
+        row.last().copied().filter(|&distance| distance <= self.max_distance)
+    }
+
+    /// Whether more input could still reach a match from `state`.
+    pub fn can_match(&self, state: &AutomatonState) -> bool {
+        self.row_can_match(&state.0)
+    }
+
+    pub(crate) fn row_can_match(&self, row: &[usize]) -> bool {
+        row.iter().any(|&distance| distance <= self.max_distance)
+    }
+}
+
+/// A dictionary stored as a [`Dawg`], searched with a
+/// [`LevenshteinAutomaton`]. Only the branches the automaton can still match
+/// from are visited, so queries with small distances touch a small part of a
+/// large dictionary.
+///
+/// Repeated words are stored once, and suggestions come back sorted by
+/// distance and then alphabetically rather than in dictionary order.
+/// ```
+/// use spelling::DawgDictionary;
+/// let dictionary: DawgDictionary = "thin\nthing\nthings\nhint".lines().collect();
+/// assert_eq!(dictionary.suggest("thinga", 1), vec!["thing", "things"]);
+/// assert!(dictionary.contains("hint"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DawgDictionary {
+    dawg: Dawg,
+}
+
+impl DawgDictionary {
+    /// Builds a dictionary out of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+
+    /// The words.
+    pub fn dawg(&self) -> &Dawg {
+        &self.dawg
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.dawg.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.dawg.is_empty()
+    }
+
+    /// Whether `word` is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.dawg.contains(word)
+    }
+
+    /// Returns the words within `k` edits of `word`, sorted by distance and
+    /// then alphabetically.
+    pub fn suggest(&self, word: &str, k: usize) -> Vec<String> {
+        let automaton = LevenshteinAutomaton::new(word, k);
+        let mut search = Search {
+            automaton: &automaton,
+            nodes: &self.dawg.nodes,
+            rows: automaton.start().0,
+            prefix: String::new(),
+            found: Vec::new(),
+        };
+        search.walk(self.dawg.root, 0);
+        let mut found = search.found;
+        found.sort_unstable();
+        found.into_iter().map(|(_, word)| word).collect()
+    }
+}
+
+impl From<Dawg> for DawgDictionary {
+    fn from(dawg: Dawg) -> Self {
+        DawgDictionary { dawg }
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does.
+impl<'a> std::iter::FromIterator<&'a str> for DawgDictionary {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        DawgDictionary {
+            dawg: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A depth first walk of a DAWG, with one automaton row per depth so nothing
+/// is allocated per node. Shared subtrees are walked once per path to them,
+/// since the prefix, and so the automaton state, differs.
+struct Search<'a> {
+    automaton: &'a LevenshteinAutomaton,
+    nodes: &'a [DawgNode],
+    /// The rows for the path to the current node, end to end.
+    rows: Vec<usize>,
+    prefix: String,
+    found: Vec<(usize, String)>,
+}
+
+impl Search<'_> {
+    fn walk(&mut self, current: usize, depth: usize) {
+        let width = self.automaton.query.len() + 1;
+        let row = depth * width..(depth + 1) * width;
+        let node = &self.nodes[current];
+        if node.is_final {
+            if let Some(distance) = self.automaton.row_distance(&self.rows[row.clone()]) {
+                self.found.push((distance, self.prefix.clone()));
+            }
+        }
+        if self.rows.len() < row.end + width {
+            self.rows.resize(row.end + width, 0);
+        }
+        for &(c, child) in &node.edges {
+            let (previous, next) = self.rows.split_at_mut(row.end);
+            self.automaton.step_into(&previous[row.clone()], c, &mut next[..width]);
+            if self.automaton.row_can_match(&next[..width]) {
+                self.prefix.push(c);
+                self.walk(child, depth + 1);
+                self.prefix.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn automaton() {
+        let automaton = crate::LevenshteinAutomaton::new("abc", 1);
+        let run = |input: &str| input.chars().fold(automaton.start(), |state, c| automaton.step(&state, c));
+        assert_eq!(automaton.distance(&automaton.start()), None);
+        assert_eq!(automaton.distance(&run("abc")), Some(0));
+        assert_eq!(automaton.distance(&run("abd")), Some(1));
+        assert_eq!(automaton.distance(&run("ab")), Some(1));
+        assert_eq!(automaton.distance(&run("abcd")), Some(1));
+        assert_eq!(automaton.distance(&run("ac")), Some(1));
+        assert_eq!(automaton.distance(&run("ca")), None);
+        assert!(automaton.can_match(&run("xa")));
+        assert!(!automaton.can_match(&run("xy")));
+        let empty = crate::LevenshteinAutomaton::new("", 0);
+        assert_eq!(empty.distance(&empty.start()), Some(0));
+    }
+
+    #[test]
+    fn dawg_dictionary() {
+        let dictionary = crate::DawgDictionary::from_dictionary(&crate::Dictionary::from_newline_separated(
+            "thin\nthing\nthin\nnaïve\n\nthings",
+        ));
+        assert_eq!(dictionary.len(), 4);
+        assert!(dictionary.contains("naïve"));
+        assert!(!dictionary.contains("thi"));
+        assert!(!dictionary.contains(""));
+        assert_eq!(dictionary.suggest("thin", 0), vec!["thin"]);
+        assert_eq!(dictionary.suggest("naive", 1), vec!["naïve"]);
+        assert_eq!(dictionary.suggest("thinga", 2), vec!["thing", "things", "thin"]);
+        assert!(crate::DawgDictionary::default().suggest("a", 5).is_empty());
+        let dawg: crate::Dawg = "thin\nthing".lines().collect();
+        assert_eq!(crate::DawgDictionary::from(dawg).suggest("thinga", 1), vec!["thing"]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn automaton_matches_levenshtein_distance(query in "[abc]{0,6}", input in "[abc]{0,8}", max in 0usize..4) {
+            let automaton = crate::LevenshteinAutomaton::new(&query, max);
+            let state = input.chars().fold(automaton.start(), |state, c| automaton.step(&state, c));
+            let distance = crate::levenshtein_distance(&query, &input);
+            proptest::prop_assert_eq!(automaton.distance(&state), Some(distance).filter(|&d| d <= max));
+        }
+
+        #[test]
+        fn dawg_dictionary_matches_a_scan(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            word in "[abc]{0,6}",
+            k in 0usize..4,
+        ) {
+            let dictionary: crate::DawgDictionary = words.iter().map(String::as_str).collect();
+            let mut expected: Vec<(usize, &str)> = words
+                .iter()
+                .map(|candidate| (crate::levenshtein_distance(&word, candidate), candidate.as_str()))
+                .filter(|&(distance, _)| distance <= k)
+                .collect();
+            expected.sort_unstable();
+            expected.dedup();
+            let expected: Vec<&str> = expected.into_iter().map(|(_, word)| word).collect();
+            proptest::prop_assert_eq!(dictionary.suggest(&word, k), expected);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! A [BK-tree](https://en.wikipedia.org/wiki/BK-tree) index, which answers
+//! queries without measuring the distance to every word.
+
+use crate::dict::Dictionary;
+use crate::distance::{DistanceMetric, Levenshtein};
+use crate::index_file::{Reader, Writer};
+
+const MAGIC: &[u8; 8] = b"SPELLBKT";
+
+/// A BK-tree over a word list. Each query only measures the distance to the
+/// words the triangle inequality can't rule out, which for small distances is
+/// a fraction of the dictionary.
+///
+/// The metric has to be a true metric, see [`DistanceMetric`]; the Levenshtein
+/// and Damerau-Levenshtein distances are.
+/// ```
+/// # #[cfg(feature = "check")] {
+/// use spelling::{spellcheck, BkTree};
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// let tree: BkTree = dictionary_string.lines().collect();
+/// assert_eq!(tree.suggest("restaraunt", 3), spellcheck(dictionary_string, "restaraunt", 3));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BkTree<M = Levenshtein> {
+    metric: M,
+    nodes: Vec<Node>,
+    /// How many words have been inserted, repeats included.
+    inserted: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Node {
+    word: String,
+    /// Where the word appears in the word list, more than once for repeats.
+    indices: Vec<usize>,
+    /// `(distance, node)` for each child.
+    children: Vec<(usize, usize)>,
+}
+
+impl<M: Default> Default for BkTree<M> {
+    fn default() -> Self {
+        BkTree {
+            metric: M::default(),
+            nodes: Vec::new(),
+            inserted: 0,
+        }
+    }
+}
+
+impl BkTree {
+    /// Builds a tree out of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+}
+
+impl<M: DistanceMetric> BkTree<M> {
+    /// Builds an empty tree measuring distance with `metric`.
+    pub fn with_metric(metric: M) -> Self {
+        BkTree {
+            metric,
+            nodes: Vec::new(),
+            inserted: 0,
+        }
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds `word` after every word already in the tree.
+    pub fn insert(&mut self, word: &str) {
+        let index = self.inserted;
+        self.inserted += 1;
+        if self.nodes.is_empty() {
+            self.nodes.push(Node {
+                word: word.to_owned(),
+                indices: vec![index],
+                children: Vec::new(),
+            });
+            return;
+        }
+        let mut current = 0;
+        loop {
+            let distance = self.metric.distance(word, &self.nodes[current].word);
+            if distance == 0 {
+                self.nodes[current].indices.push(index);
+                return;
+            }
+            match self.nodes[current].children.iter().find(|&&(edge, _)| edge == distance) {
+                Some(&(_, child)) => current = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes[current].children.push((distance, child));
+                    self.nodes.push(Node {
+                        word: word.to_owned(),
+                        indices: vec![index],
+                        children: Vec::new(),
+                    });
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns the words within `distance` of `word`, sorted by distance with
+    /// ties in insertion order, the same as [`spellcheck`](crate::spellcheck)
+    /// over the same words.
+    pub fn suggest(&self, word: &str, distance: usize) -> Vec<&str> {
+        let mut found: Vec<(usize, usize, &str)> = Vec::new();
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(current) = stack.pop() {
+            let node = &self.nodes[current];
+            let node_distance = self.metric.distance(word, &node.word);
+            if node_distance <= distance {
+                found.extend(node.indices.iter().map(|&index| (node_distance, index, node.word.as_str())));
+            }
+            // by the triangle inequality, a child `edge` away from this node
+            // is at least |node_distance - edge| away from `word`
+            let low = node_distance.saturating_sub(distance);
+            let high = node_distance.saturating_add(distance);
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|&&(edge, _)| low <= edge && edge <= high)
+                    .map(|&(_, child)| child),
+            );
+        }
+        found.sort_unstable_by_key(|&(distance, index, _)| (distance, index));
+        found.into_iter().map(|(_, _, word)| word).collect()
+    }
+
+    /// Saves the tree in a compact binary format, so it can be built once
+    /// and [`load`](BkTree::load)ed at startup. The file records the metric's
+    /// [`id`](DistanceMetric::id).
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let mut writer = Writer::new(MAGIC);
+        writer.str(&self.metric.id())?;
+        writer.usize(self.inserted)?;
+        writer.usize(self.nodes.len())?;
+        for node in &self.nodes {
+            writer.str(&node.word)?;
+            writer.usize(node.indices.len())?;
+            for &index in &node.indices {
+                writer.usize(index)?;
+            }
+            writer.usize(node.children.len())?;
+            for &(distance, child) in &node.children {
+                writer.u64(distance as u64);
+                writer.usize(child)?;
+            }
+        }
+        writer.save(path.as_ref())
+    }
+
+    /// Loads a tree saved by [`save`](BkTree::save), measuring distance with
+    /// `metric`. Fails with [`std::io::ErrorKind::InvalidData`] if the file
+    /// is corrupt or was saved with a metric with a different
+    /// [`id`](DistanceMetric::id).
+    pub fn load_with_metric(path: impl AsRef<std::path::Path>, metric: M) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let mut reader = Reader::new(&bytes, MAGIC)?;
+        if reader.str()? != metric.id().as_ref() {
+            return Err(crate::index_file::invalid("index file was saved with a different metric"));
+        }
+        let inserted = reader.usize()?;
+        let node_count = reader.count(12)?;
+        let mut nodes = Vec::with_capacity(node_count);
+        for parent in 0..node_count {
+            let word = reader.str()?.to_owned();
+            let mut indices = Vec::with_capacity(reader.count(4)?);
+            for _ in 0..indices.capacity() {
+                indices.push(reader.index(inserted)?);
+            }
+            let mut children = Vec::with_capacity(reader.count(12)?);
+            for _ in 0..children.capacity() {
+                let distance = reader.u64()? as usize;
+                let child = reader.index(node_count)?;
+                // `insert` always adds children after their parent, anything
+                // else could be a cycle that `suggest` never gets out of
+                if child <= parent {
+                    return Err(crate::index_file::invalid("index file has a cycle"));
+                }
+                children.push((distance, child));
+            }
+            nodes.push(Node { word, indices, children });
+        }
+        reader.finish()?;
+        Ok(BkTree {
+            metric,
+            nodes,
+            inserted,
+        })
+    }
+}
+
+impl<M: DistanceMetric + Default> BkTree<M> {
+    /// Loads a tree saved by [`save`](BkTree::save), see
+    /// [`load_with_metric`](BkTree::load_with_metric).
+    /// ```no_run
+    /// use spelling::BkTree;
+    /// let tree: BkTree = BkTree::load("words.bktree")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        BkTree::load_with_metric(path, M::default())
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does.
+impl<'a, M: DistanceMetric + Default> std::iter::FromIterator<&'a str> for BkTree<M> {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut tree = BkTree::default();
+        for word in iter.into_iter().filter(|word| !word.is_empty()) {
+            tree.insert(word);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bk_tree() {
+        let tree = crate::BkTree::from_dictionary(&crate::Dictionary::from_newline_separated(
+            "thin\nthing\nthin\nthings\nhint\n",
+        ));
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.suggest("thin", 1), vec!["thin", "thin", "thing"]);
+        assert_eq!(tree.suggest("thinga", 2), vec!["thing", "things", "thin", "thin"]);
+        assert!(crate::BkTree::<crate::Levenshtein>::default().suggest("a", 5).is_empty());
+
+        let mut tree = crate::BkTree::with_metric(crate::DamerauLevenshtein);
+        tree.insert("the");
+        tree.insert("ten");
+        assert_eq!(tree.suggest("teh", 1), vec!["the", "ten"]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("spelling-bk-tree-{}", std::process::id()));
+        let tree: crate::BkTree = "thin\nthing\nthin\nthings\nhint".lines().collect();
+        tree.save(&path).unwrap();
+        let loaded: crate::BkTree = crate::BkTree::load(&path).unwrap();
+        assert_eq!(loaded.suggest("thinga", 2), tree.suggest("thinga", 2));
+        // the metric is matched by id, not type
+        let boxed: Box<dyn crate::DistanceMetric> = Box::new(crate::Levenshtein);
+        assert!(crate::BkTree::load_with_metric(&path, boxed).is_ok());
+        let error = crate::BkTree::<crate::DamerauLevenshtein>::load(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // and so are its settings
+        let weighted = crate::WeightedLevenshtein::new().insertion_cost(2).pair_cost('1', 'l', 1);
+        let mut tree = crate::BkTree::with_metric(weighted.clone());
+        tree.insert("thin");
+        tree.save(&path).unwrap();
+        assert!(crate::BkTree::load_with_metric(&path, weighted.clone()).is_ok());
+        for other in [weighted.clone().insertion_cost(1), weighted.pair_cost('1', 'l', 2)] {
+            let error = crate::BkTree::load_with_metric(&path, other).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn load_rejects_cycles() {
+        use crate::index_file::Writer;
+        let path = std::env::temp_dir().join(format!("spelling-bk-tree-cycle-{}", std::process::id()));
+        let mut writer = Writer::new(super::MAGIC);
+        writer.str("levenshtein").unwrap();
+        writer.usize(1).unwrap();
+        writer.usize(1).unwrap();
+        writer.str("thin").unwrap();
+        writer.usize(1).unwrap();
+        writer.usize(0).unwrap();
+        // one child, pointing back at the node itself
+        writer.usize(1).unwrap();
+        writer.u64(1);
+        writer.usize(0).unwrap();
+        writer.save(&path).unwrap();
+        let error = crate::BkTree::<crate::Levenshtein>::load(&path).unwrap_err();
+        assert_eq!(error.to_string(), "index file has a cycle");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "check")]
+    proptest::proptest! {
+        #[test]
+        fn bk_tree_matches_spellcheck(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            word in "[abc]{0,6}",
+            distance in 0usize..4,
+        ) {
+            let dictionary_string = words.join("\n");
+            let tree: crate::BkTree = words.iter().map(String::as_str).collect();
+            proptest::prop_assert_eq!(
+                tree.suggest(&word, distance),
+                crate::spellcheck(&dictionary_string, &word, distance)
+            );
+        }
+    }
+}
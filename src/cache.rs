@@ -0,0 +1,188 @@
+//! A suggestion cache that's saved to disk, so repeated runs over the same
+//! files only work out suggestions for new words.
+
+use crate::check::SpellChecker;
+use crate::distance::DistanceMetric;
+
+/// The first word of a cache file, followed by the format version and the
+/// checker's fingerprint.
+const MAGIC: &str = "spelling-cache";
+const VERSION: u32 = 1;
+
+/// Suggestions saved across runs in a file. The file is tied to the checker
+/// it was made with: if the dictionary, frequencies or settings change, the
+/// old entries are thrown away. Fingerprints are only stable for one build of
+/// the crate, so upgrading also starts over.
+/// ```no_run
+/// use spelling::{SpellChecker, SuggestionCache};
+/// let checker = SpellChecker::new(include_str!("words.txt"));
+/// let mut cache = SuggestionCache::open(".spelling-cache", &checker)?;
+/// println!("{:?}", cache.suggest(&checker, "restaraunt", 2));
+/// cache.save()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SuggestionCache {
+    path: std::path::PathBuf,
+    fingerprint: u64,
+    entries: std::collections::HashMap<(String, usize), Vec<String>>,
+    dirty: bool,
+}
+
+impl SuggestionCache {
+    /// Loads the cache at `path` for `checker`. A missing file, a file for a
+    /// different checker, or a file that can't be parsed gives an empty cache
+    /// rather than an error, only failing to read the file is an error. Fails
+    /// with [`std::io::ErrorKind::InvalidInput`] if `checker` uses a
+    /// [`RankingStrategy::Custom`](crate::RankingStrategy::Custom) ranker,
+    /// since the file couldn't be tied to it. A custom metric has to include
+    /// its settings in its [`id`](DistanceMetric::id) for the same reason.
+    pub fn open<M: DistanceMetric>(
+        path: impl Into<std::path::PathBuf>,
+        checker: &SpellChecker<M>,
+    ) -> std::io::Result<Self> {
+        let fingerprint = checker.fingerprint().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "can't cache suggestions from a custom ranker")
+        })?;
+        let mut cache = SuggestionCache {
+            path: path.into(),
+            fingerprint,
+            entries: std::collections::HashMap::new(),
+            dirty: false,
+        };
+        let contents = match std::fs::read_to_string(&cache.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(cache),
+            Err(error) if error.kind() == std::io::ErrorKind::InvalidData => return Ok(cache),
+            Err(error) => return Err(error),
+        };
+        let mut lines = contents.lines();
+        if lines.next() != Some(&cache.header()) {
+            cache.dirty = true;
+            return Ok(cache);
+        }
+        for line in lines {
+            let mut fields = line.split('\t');
+            let distance = fields.next().and_then(|distance| distance.parse().ok());
+            match (distance, fields.next()) {
+                (Some(distance), Some(word)) => {
+                    let suggestions = fields.map(str::to_owned).collect();
+                    cache.entries.insert((word.to_owned(), distance), suggestions);
+                }
+                _ => cache.dirty = true,
+            }
+        }
+        Ok(cache)
+    }
+
+    fn header(&self) -> String {
+        format!("{} {} {:016x}", MAGIC, VERSION, self.fingerprint)
+    }
+
+    /// Suggestions for `word` within `distance`, from the cache if they're
+    /// there and from `checker` otherwise. Hits are reported to the checker's
+    /// [`Metrics`](crate::Metrics). `checker` should be the one the cache was
+    /// opened with.
+    pub fn suggest<M: DistanceMetric>(
+        &mut self,
+        checker: &SpellChecker<M>,
+        word: &str,
+        distance: usize,
+    ) -> &[String] {
+        let key = (word.to_owned(), distance);
+        if self.entries.contains_key(&key) {
+            checker.record_cache_hit();
+        } else {
+            let suggestions = checker.suggest(word, distance).into_iter().map(str::to_owned).collect();
+            self.dirty = true;
+            self.entries.insert(key.clone(), suggestions);
+        }
+        &self.entries[&key]
+    }
+
+    /// The number of cached queries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the cache back to its file if anything changed. The file is
+    /// replaced in one step, so a crash while saving leaves the old one.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let mut contents = self.header();
+        contents.push('\n');
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_unstable();
+        for ((word, distance), suggestions) in entries {
+            // tabs and newlines would break the format, those just aren't
+            // saved
+            let unsafe_field = |field: &str| field.contains(['\t', '\n', '\r']);
+            if unsafe_field(word) || suggestions.iter().any(|suggestion| unsafe_field(suggestion)) {
+                continue;
+            }
+            contents.push_str(&format!("{}\t{}", distance, word));
+            for suggestion in suggestions {
+                contents.push('\t');
+                contents.push_str(suggestion);
+            }
+            contents.push('\n');
+        }
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, contents)?;
+        std::fs::rename(&temporary, &self.path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn suggestion_cache() {
+        let path = std::env::temp_dir().join(format!("spelling-cache-{}", std::process::id()));
+        let counters = std::sync::Arc::new(crate::MetricsCounters::default());
+        let checker = crate::SpellChecker::new("thin\nthing").metrics(counters.clone());
+
+        let mut cache = crate::SuggestionCache::open(&path, &checker).unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(cache.suggest(&checker, "thng", 1), ["thing"]);
+        // not saved, the tab would break the file
+        assert_eq!(cache.suggest(&checker, "th\tng", 1), ["thing"]);
+        cache.save().unwrap();
+
+        let mut cache = crate::SuggestionCache::open(&path, &checker).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.suggest(&checker, "thng", 1), ["thing"]);
+        assert_eq!((counters.queries(), counters.cache_hits()), (3, 1));
+
+        // a different dictionary starts over
+        let other = crate::SpellChecker::new("thin\nthings");
+        assert!(crate::SuggestionCache::open(&path, &other).unwrap().is_empty());
+        let ranked = crate::SpellChecker::new("thin\nthing").ranking(crate::RankingStrategy::Frequency);
+        assert!(crate::SuggestionCache::open(&path, &ranked).unwrap().is_empty());
+        let scored = |phonetic: Box<dyn crate::Phonetic>| {
+            let scorer = crate::Scorer::new().phonetic(phonetic);
+            crate::SpellChecker::new("thin\nthing").ranking(crate::RankingStrategy::Scored(scorer))
+        };
+        assert_ne!(
+            scored(Box::new(crate::Soundex)).fingerprint(),
+            scored(Box::new(crate::DoubleMetaphone)).fingerprint()
+        );
+        let reverse = |_: &str, candidates: &mut [crate::Candidate]| candidates.reverse();
+        let custom = crate::SpellChecker::new("thin\nthing").ranking(crate::RankingStrategy::Custom(Box::new(reverse)));
+        let error = crate::SuggestionCache::open(&path, &custom).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        std::fs::write(&path, b"\xff").unwrap();
+        assert!(crate::SuggestionCache::open(&path, &checker).unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,3099 @@
+//! Finding suggestions, with the free functions, [`Pipeline`] and
+//! [`SpellChecker`].
+
+use crate::dict::{Dictionary, FrequencyDictionary};
+use crate::user_dict::UserDictionary;
+use crate::trie::Trie;
+use crate::metrics::{common_prefix_len, distance_ratio, Similarity};
+use crate::phonetic::Phonetic;
+use crate::distance::{
+    fold_char, levenshtein_distance_bounded_in, levenshtein_distance_folded, DistanceMetric, DistanceScratch,
+    Levenshtein, MaxDistance,
+};
+use crate::rank::{
+    Candidate, RankingStrategy, Ranker, ScoreBreakdown, ScoreRanker, SortPolicy, Suggestion,
+    NOISY_CHANNEL_EDIT_PROBABILITY,
+};
+
+/// Takes a `dictionary_string` (newline separated), a word and a distance and
+/// returns a vector of possible matches, with a limit of distance set up
+/// `distance`. Sorts by distance, ties keep dictionary order. Uses rayon.
+/// 
+/// Notes:
+/// 1. Use this whenever possible over the other `spellcheck` function.
+/// 2. This works on `char`s, see [`levenshtein_distance_bounded`](crate::levenshtein_distance_bounded).
+/// 3. This uses the Levenshtein distance.
+/// 4. This returns exactly the same thing, in the same order, as
+///    [`spellcheck`]. Enable the `verify_parallel` feature to check that on
+///    every call in debug builds.
+/// ```
+/// use spelling::spellcheck_rayon;
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// spellcheck_rayon(dictionary_string, "restaraunt", 3);
+/// ```
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+pub fn spellcheck_rayon<'a>(dictionary_string: &'a str, word: &str, distance: usize) -> Vec<&'a str> {
+    spellcheck_rayon_with_distances(dictionary_string, word, distance)
+        .into_iter()
+        .map(|x| x.0)
+        .collect()
+}
+
+/// Works like [`spellcheck_rayon`], but returns each suggestion's distance
+/// alongside it, see [`spellcheck_with_distances`].
+/// ```
+/// use spelling::spellcheck_rayon_with_distances;
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// for (word, distance) in spellcheck_rayon_with_distances(dictionary_string, "restaraunt", 3) {
+///     println!("{} ({} edits away)", word, distance);
+/// }
+/// ```
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+pub fn spellcheck_rayon_with_distances<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+) -> Vec<(&'a str, usize)> {
+    rayon_distances(dictionary_string.split('\n').collect(), word, distance, &ParallelOptions::new())
+}
+
+/// How the rayon functions split up and schedule their work, so library
+/// users can bound the CPU they use.
+/// ```
+/// use spelling::{spellcheck_rayon_with_options, ParallelOptions};
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+/// let options = ParallelOptions::new().thread_pool(&pool).min_chunk(1024);
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// spellcheck_rayon_with_options(dictionary_string, "restaraunt", 3, &options);
+/// ```
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelOptions<'p> {
+    thread_pool: Option<&'p rayon::ThreadPool>,
+    min_chunk: usize,
+}
+
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+impl<'p> ParallelOptions<'p> {
+    /// Runs on the global pool, letting rayon split the work as it likes.
+    pub fn new() -> Self {
+        ParallelOptions { thread_pool: None, min_chunk: 1 }
+    }
+
+    /// Runs on `thread_pool` instead of rayon's global pool, builder style.
+    pub fn thread_pool(mut self, thread_pool: &'p rayon::ThreadPool) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// Runs on `thread_pool`, or the global pool if `None`.
+    pub fn set_thread_pool(&mut self, thread_pool: Option<&'p rayon::ThreadPool>) {
+        self.thread_pool = thread_pool;
+    }
+
+    /// Never gives a thread fewer than `min_chunk` words at a time, builder
+    /// style. Larger chunks mean less scheduling overhead but coarser load
+    /// balancing. 0 is treated as 1.
+    pub fn min_chunk(mut self, min_chunk: usize) -> Self {
+        self.min_chunk = min_chunk;
+        self
+    }
+
+    /// Sets the smallest number of words a thread is given at a time.
+    pub fn set_min_chunk(&mut self, min_chunk: usize) {
+        self.min_chunk = min_chunk;
+    }
+}
+
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+impl Default for ParallelOptions<'_> {
+    fn default() -> Self {
+        ParallelOptions::new()
+    }
+}
+
+/// Works like [`spellcheck_rayon`], but runs on the pool and with the chunk
+/// size from `options`.
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+pub fn spellcheck_rayon_with_options<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    options: &ParallelOptions<'_>,
+) -> Vec<&'a str> {
+    rayon_distances(dictionary_string.split('\n').collect(), word, distance, options)
+        .into_iter()
+        .map(|x| x.0)
+        .collect()
+}
+
+/// Like [`spellcheck_rayon`], but takes the dictionary as any iterator of
+/// words, say the output of `str::split` or [`Dictionary::iter`], just like
+/// [`spellcheck_iter`]. The words are collected first so the work can be
+/// split evenly between threads.
+/// ```
+/// use spelling::spellcheck_rayon_iter;
+/// let dictionary_string = "thin,thing,things";
+/// assert_eq!(spellcheck_rayon_iter(dictionary_string.split(','), "thinga", 1), vec!["thing", "things"]);
+/// ```
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+pub fn spellcheck_rayon_iter<'a>(
+    words: impl IntoIterator<Item = &'a str>,
+    word: &str,
+    distance: usize,
+) -> Vec<&'a str> {
+    rayon_distances(words.into_iter().collect(), word, distance, &ParallelOptions::new())
+        .into_iter()
+        .map(|x| x.0)
+        .collect()
+}
+
+/// The words of `dictionary` within `distance` of `word` with their
+/// distances, sorted by distance, ties in dictionary order.
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+fn rayon_distances<'a>(
+    dictionary: Vec<&'a str>,
+    word: &str,
+    distance: usize,
+    options: &ParallelOptions<'_>,
+) -> Vec<(&'a str, usize)> {
+    use rayon::prelude::*;
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("spellcheck_rayon", word, distance).entered();
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    // collecting an indexed parallel iterator keeps dictionary order
+    let scan = || -> Vec<(&str, usize)> {
+        dictionary
+            .par_iter()
+            .with_min_len(options.min_chunk.max(1))
+            .map_init(DistanceScratch::new, |scratch, string_in| {
+                levenshtein_distance_bounded_in(scratch, word, string_in, distance)
+                    .within()
+                    .map(|out| (*string_in, out))
+            })
+            .flatten()
+            .collect()
+    };
+    let mut vec = match options.thread_pool {
+        Some(pool) => pool.install(scan),
+        None => scan(),
+    };
+
+    // sort by distance, stable so ties keep dictionary order just like
+    // `spellcheck`
+    vec.sort_by_key(|x| x.1);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(suggestions = vec.len(), elapsed = ?start.elapsed(), "spellcheck_rayon finished");
+    #[cfg(feature = "verify_parallel")]
+    debug_assert_eq!(
+        vec,
+        Pipeline::default()
+            .run(dictionary.iter().copied(), word, distance)
+            .into_iter()
+            .map(|candidate| (candidate.word, candidate.breakdown.distance))
+            .collect::<Vec<_>>(),
+        "spellcheck_rayon and spellcheck disagree on {:?}",
+        word
+    );
+    vec
+}
+
+/// Like [`spellcheck_rayon`], but returns at most `k` suggestions and stops
+/// scanning once `k` matches within `good_enough` have been found, instead of
+/// always going through the whole dictionary.
+///
+/// When it stops early, the result is `k` matches within `good_enough` but not
+/// necessarily the `k` best ones, and which ones depends on thread scheduling.
+/// When it doesn't, the result is the best `k` ties aside.
+/// ```
+/// use spelling::spellcheck_rayon_top_k;
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// let suggestions = spellcheck_rayon_top_k(dictionary_string, "restaraunt", 3, 5, 1);
+/// assert!(suggestions.len() <= 5);
+/// ```
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+pub fn spellcheck_rayon_top_k<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    k: usize,
+    good_enough: usize,
+) -> Vec<&'a str> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    let found = AtomicUsize::new(0);
+    let mut vec: Vec<(&str, usize)> = dictionary_string
+        .par_split('\n')
+        .map(|string_in| {
+            // `None` ends the scan, `Some(None)` is a word that didn't match
+            if found.load(Ordering::Relaxed) >= k {
+                return None;
+            }
+            let out = crate::distance::levenshtein_distance_bounded(word, string_in, distance).within();
+            if out.is_some_and(|out| out <= good_enough) {
+                found.fetch_add(1, Ordering::Relaxed);
+            }
+            Some(out.map(|out| (string_in, out)))
+        })
+        .while_some()
+        .flatten()
+        .collect();
+
+    vec.sort_by_key(|x| x.1);
+    vec.truncate(k);
+    vec.into_iter().map(|x| x.0).collect()
+}
+
+/// Like [`spellcheck_rayon`], but sends each `(word, distance)` match over
+/// `sender` as soon as it's found, so suggestions can be shown before the scan
+/// finishes. Matches arrive in no particular order. Returns once the whole
+/// dictionary has been scanned, or early if the receiver is dropped.
+/// ```
+/// use spelling::spellcheck_rayon_streaming;
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| spellcheck_rayon_streaming(dictionary_string, "restaraunt", 3, sender));
+///     for (word, distance) in receiver {
+///         println!("{} ({})", word, distance);
+///     }
+/// });
+/// ```
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+pub fn spellcheck_rayon_streaming<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    sender: std::sync::mpsc::Sender<(&'a str, usize)>,
+) {
+    use rayon::prelude::*;
+    // an error means the receiver is gone, which stops the scan
+    let _ = dictionary_string
+        .par_split('\n')
+        .try_for_each_with(sender, |sender, string_in| {
+            match crate::distance::levenshtein_distance_bounded(word, string_in, distance) {
+                crate::distance::Bounded::Within(out) => sender.send((string_in, out)),
+                crate::distance::Bounded::Exceeded => Ok(()),
+            }
+        });
+}
+
+/// Takes a `dictionary_string` (newline separated), a word and a distance and
+/// returns a vector of possible matches, with a limit of distance set up
+/// `distance`. Sorts by distance, ties keep dictionary order. This doesn't use
+/// rayon.
+/// 
+/// Notes:
+/// 1. This works on `char`s, see [`levenshtein_distance_bounded`](crate::levenshtein_distance_bounded).
+/// 2. This uses the Levenshtein distance.
+/// 3. A `distance` of 0 only returns exact matches.
+/// ```
+/// use spelling::spellcheck;
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// spellcheck(dictionary_string, "restaraunt", 3);
+/// ```
+/// How it works:
+///
+/// It loops through each word in the dictionary and then you have a word from
+/// the dictionary and a word to match against.
+///
+/// Lets say those words were `rumor` and `rotten`. You then create a vector
+/// that is the length of the shorter word + 1, counting up (but the first value
+/// is one).
+/// ```
+/// let mut list = vec![1, 1, 2, 3, 4, 5];
+/// ```
+/// You also keep track a left variable, which starts at the index of the
+/// longest string you're at and you create a temporary row.
+/// ```
+/// # let longer = "rotten";
+/// for x in 2..longer.len() {
+///     let mut left = x;
+///     let mut temp = Vec::new();
+///     # temp.push(10 as usize)
+/// }
+/// ```
+/// For every item in the list, except the first, check if letter you're on 
+/// is the same in both words. If it is left will become list at the current
+/// index you're on minus one. If it isn't left will become the minimum of 
+/// the current index on `list`, the current index minus on `list`, and left. 
+/// Then add `left` to `temp`.
+/// 
+/// After this set `list` to `temp` and repeat.
+/// 
+/// The distance will be in the last index of list at the end, so return that
+/// to filter.
+///
+/// ```
+/// # let longer = "rotten";
+/// # let shorter = "rumor";
+/// # let mut list: Vec<usize> = Vec::with_capacity(shorter.len() + 1);
+/// # list.push(1);
+/// # for index in 1..(shorter.len() + 1) {
+/// #     list.push(index) 
+/// # }
+/// for x in 2..longer.len() {
+///     let mut left = x;
+///     println!("{}", x);
+///     let mut temp = Vec::new();
+///     temp.push(left);
+///     let mut iter = list.iter().enumerate();
+///     iter.next(); // skip first item in list
+///     for (index, y) in iter {
+///         println!("{}", index);
+///         left = match longer.as_bytes()[x - 1] == shorter.as_bytes()[index - 1] {
+///             true => list[index - 1],
+///             false => list[index - 1].min(*y).min(left) + 1,
+///         };
+///         temp.push(left)
+///     }
+///     list = temp;
+/// }
+/// ```
+///
+pub fn spellcheck<'a>(dictionary_string: &'a str, word: &str, distance: usize) -> Vec<&'a str> {
+    spellcheck_with_distances(dictionary_string, word, distance)
+        .into_iter()
+        .map(|x| x.0)
+        .collect()
+}
+
+/// Like [`spellcheck`], with the distance worked out from `word`, see
+/// [`MaxDistance`].
+/// ```
+/// use spelling::{spellcheck_within, MaxDistance};
+/// let dictionary_string = "cat\ncot\ncart\nrestaurant";
+/// let adaptive = MaxDistance::Adaptive { per_chars: 4, cap: 3 };
+/// assert_eq!(spellcheck_within(dictionary_string, "cst", adaptive), vec!["cat", "cot"]);
+/// assert_eq!(spellcheck_within(dictionary_string, "resturnat", adaptive), vec!["restaurant"]);
+/// ```
+pub fn spellcheck_within<'a>(dictionary_string: &'a str, word: &str, max_distance: MaxDistance) -> Vec<&'a str> {
+    spellcheck(dictionary_string, word, max_distance.for_word(word))
+}
+
+/// Like [`spellcheck`], but takes the dictionary as any iterator of words,
+/// say the output of `str::split` or [`Dictionary::iter`]. Takes the same
+/// input as [`spellcheck_rayon_iter`].
+/// ```
+/// use spelling::spellcheck_iter;
+/// let dictionary_string = "thin,thing,things";
+/// assert_eq!(spellcheck_iter(dictionary_string.split(','), "thinga", 1), vec!["thing", "things"]);
+/// ```
+pub fn spellcheck_iter<'a>(words: impl IntoIterator<Item = &'a str>, word: &str, distance: usize) -> Vec<&'a str> {
+    Pipeline::default()
+        .run(words, word, distance)
+        .into_iter()
+        .map(|candidate| candidate.word)
+        .collect()
+}
+
+/// Works like [`spellcheck`], but returns each suggestion's distance alongside
+/// it, so you can apply your own thresholds or show "2 edits away" hints.
+/// ```
+/// use spelling::spellcheck_with_distances;
+/// let dictionary_string = "thin\nthing\nthings";
+/// assert_eq!(
+///     spellcheck_with_distances(dictionary_string, "thinga", 2),
+///     vec![("thing", 1), ("things", 1), ("thin", 2)]
+/// );
+/// ```
+pub fn spellcheck_with_distances<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+) -> Vec<(&'a str, usize)> {
+    Pipeline::default()
+        .run(dictionary_string.split('\n'), word, distance)
+        .into_iter()
+        .map(|candidate| (candidate.word, candidate.breakdown.distance))
+        .collect()
+}
+
+/// Scans `words` and returns every word within `distance` of `word` together
+/// with its distance, in dictionary order.
+fn distances<'a>(
+    words: impl Iterator<Item = &'a str>,
+    word: &str,
+    distance: usize,
+    metric: &impl DistanceMetric,
+) -> Vec<(&'a str, usize)> {
+    words
+        .filter_map(|string_in| {
+            metric
+                .distance_bounded(word, string_in, distance)
+                .within()
+                .map(|out| (string_in, out))
+        })
+        .collect()
+}
+
+/// Works like [`spellcheck`], but returns only the best `k` suggestions. Keeps
+/// them in a heap of size `k` and tightens the distance limit once it's full,
+/// so memory and sorting scale with `k` rather than the number of matches.
+/// Returns the same words, in the same order, as `spellcheck` truncated to
+/// `k`.
+/// ```
+/// use spelling::spellcheck_top_k;
+/// let dictionary_string = "thin\nthings\nthing\nthinga";
+/// assert_eq!(spellcheck_top_k(dictionary_string, "thingz", 2, 2), vec!["things", "thing"]);
+/// ```
+pub fn spellcheck_top_k<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    max_distance: usize,
+    k: usize,
+) -> Vec<&'a str> {
+    if k == 0 {
+        return Vec::new();
+    }
+    // a max-heap on (distance, dictionary index), so the worst kept suggestion
+    // is always on top
+    let mut heap = std::collections::BinaryHeap::with_capacity(k + 1);
+    let mut limit = max_distance;
+    let mut scratch = DistanceScratch::new();
+    for (index, string_in) in dictionary_string.split('\n').enumerate() {
+        if let Some(distance) = levenshtein_distance_bounded_in(&mut scratch, word, string_in, limit).within() {
+            heap.push((distance, index, string_in));
+            if heap.len() > k {
+                heap.pop();
+            }
+            if heap.len() == k {
+                // later words lose ties, so only strictly better ones can get in
+                match heap.peek() {
+                    Some(&(0, _, _)) => break,
+                    Some(&(worst, _, _)) => limit = worst - 1,
+                    None => {}
+                }
+            }
+        }
+    }
+    heap.into_sorted_vec().into_iter().map(|(_, _, word)| word).collect()
+}
+
+/// Works like [`spellcheck`], but measures distance with `metric` instead of
+/// the Levenshtein distance.
+/// ```
+/// use spelling::{spellcheck_with_metric, Levenshtein};
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// spellcheck_with_metric(dictionary_string, "restaraunt", 3, &Levenshtein);
+/// ```
+pub fn spellcheck_with_metric<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    metric: &impl DistanceMetric,
+) -> Vec<&'a str> {
+    let mut out = distances(dictionary_string.split('\n'), word, distance, metric);
+    // stable, so ties keep dictionary order
+    out.sort_by_key(|x| x.1);
+    out.into_iter().map(|x| x.0).collect()
+}
+
+/// How [`spellcheck_with_options`] compares words before measuring distance.
+/// Suggestions are always returned as they appear in the dictionary.
+/// ```
+/// use spelling::CheckOptions;
+/// let options = CheckOptions::new().case_insensitive(true);
+/// assert!(options.is_case_insensitive());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckOptions {
+    case_insensitive: bool,
+    strip_diacritics: bool,
+    #[cfg(feature = "unicode")]
+    normalization: crate::distance::Normalization,
+    #[cfg(feature = "unicode")]
+    graphemes: bool,
+}
+
+impl CheckOptions {
+    /// Compares words exactly as given.
+    pub fn new() -> Self {
+        CheckOptions::default()
+    }
+
+    /// Whether to lowercase both words before comparing them, builder style.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Whether to lowercase both words before comparing them.
+    pub fn set_case_insensitive(&mut self, case_insensitive: bool) {
+        self.case_insensitive = case_insensitive;
+    }
+
+    /// Whether words are lowercased before comparing them.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Whether to map accented letters to their base letter before comparing
+    /// words, so "creme brulee" matches "crème brûlée", builder style. This
+    /// covers Latin-1, Latin Extended-A and combining accents.
+    pub fn strip_diacritics(mut self, strip_diacritics: bool) -> Self {
+        self.strip_diacritics = strip_diacritics;
+        self
+    }
+
+    /// Whether to map accented letters to their base letter before comparing
+    /// words.
+    pub fn set_strip_diacritics(&mut self, strip_diacritics: bool) {
+        self.strip_diacritics = strip_diacritics;
+    }
+
+    /// Sets the [`Normalization`](crate::Normalization) applied to both words
+    /// before comparing them, builder style. Use
+    /// [`Nfc`](crate::Normalization::Nfc) so precomposed and combining forms
+    /// of the same character match.
+    #[cfg(feature = "unicode")]
+    pub fn normalization(mut self, normalization: crate::distance::Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Sets the [`Normalization`](crate::Normalization) applied to both words
+    /// before comparing them.
+    #[cfg(feature = "unicode")]
+    pub fn set_normalization(&mut self, normalization: crate::distance::Normalization) {
+        self.normalization = normalization;
+    }
+
+    /// Whether to count edits in grapheme clusters instead of `char`s, see
+    /// [`levenshtein_distance_graphemes`](crate::levenshtein_distance_graphemes),
+    /// builder style.
+    #[cfg(feature = "unicode")]
+    pub fn graphemes(mut self, graphemes: bool) -> Self {
+        self.graphemes = graphemes;
+        self
+    }
+
+    /// Whether to count edits in grapheme clusters instead of `char`s.
+    #[cfg(feature = "unicode")]
+    pub fn set_graphemes(&mut self, graphemes: bool) {
+        self.graphemes = graphemes;
+    }
+
+    /// The distance between two prepared words.
+    pub(crate) fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> crate::distance::Bounded {
+        #[cfg(feature = "unicode")]
+        if self.graphemes {
+            return crate::distance::levenshtein_distance_graphemes_bounded(a, b, max_distance);
+        }
+        crate::distance::levenshtein_distance_bounded(a, b, max_distance)
+    }
+
+    /// `word` as it should be compared, only allocating when something
+    /// changes.
+    pub(crate) fn prepare<'a>(&self, word: &'a str) -> std::borrow::Cow<'a, str> {
+        let word = if self.case_insensitive && word.chars().any(|c| c.to_lowercase().ne(Some(c))) {
+            std::borrow::Cow::Owned(word.to_lowercase())
+        } else {
+            std::borrow::Cow::Borrowed(word)
+        };
+        #[cfg(feature = "unicode")]
+        let word = match word {
+            std::borrow::Cow::Borrowed(word) => self.normalization.apply(word),
+            std::borrow::Cow::Owned(word) => std::borrow::Cow::Owned(self.normalization.apply(&word).into_owned()),
+        };
+        match word {
+            std::borrow::Cow::Borrowed(word) if self.strip_diacritics => crate::distance::strip_diacritics(word),
+            std::borrow::Cow::Owned(word) if self.strip_diacritics => {
+                std::borrow::Cow::Owned(crate::distance::strip_diacritics(&word).into_owned())
+            }
+            word => word,
+        }
+    }
+}
+
+/// Works like [`spellcheck`], but compares words as set by `options`, for
+/// example ignoring case. Suggestions keep their dictionary casing.
+/// ```
+/// use spelling::{spellcheck_with_options, CheckOptions};
+/// let options = CheckOptions::new().case_insensitive(true);
+/// assert_eq!(spellcheck_with_options("Orange\norange", "ORANGE", 0, &options), vec!["Orange", "orange"]);
+/// ```
+pub fn spellcheck_with_options<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    options: &CheckOptions,
+) -> Vec<&'a str> {
+    let word = options.prepare(word);
+    let mut out: Vec<(&str, usize)> = dictionary_string
+        .split('\n')
+        .filter_map(|string_in| {
+            options
+                .distance_bounded(&word, &options.prepare(string_in), distance)
+                .within()
+                .map(|out| (string_in, out))
+        })
+        .collect();
+    // stable, so ties keep dictionary order
+    out.sort_by_key(|x| x.1);
+    out.into_iter().map(|x| x.0).collect()
+}
+
+/// Works like [`spellcheck`], but ranks by a [`Similarity`] like
+/// [`JaroWinkler`](crate::JaroWinkler) instead of by edit distance. Returns
+/// the words with a similarity of at least `min_similarity`, most similar
+/// first, ties in dictionary order.
+/// ```
+/// use spelling::{spellcheck_with_similarity, JaroWinkler};
+/// let dictionary_string = "dickson\nnixon\ndixie";
+/// assert_eq!(spellcheck_with_similarity(dictionary_string, "dixon", 0.8, &JaroWinkler), vec!["nixon", "dickson", "dixie"]);
+/// ```
+pub fn spellcheck_with_similarity<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    min_similarity: f64,
+    similarity: &impl Similarity,
+) -> Vec<&'a str> {
+    let mut out: Vec<(&str, f64)> = dictionary_string
+        .split('\n')
+        .map(|string_in| (string_in, similarity.similarity(word, string_in)))
+        .filter(|&(_, score)| score >= min_similarity)
+        .collect();
+    // stable, so ties keep dictionary order
+    out.sort_by(|a, b| b.1.total_cmp(&a.1));
+    out.into_iter().map(|x| x.0).collect()
+}
+
+/// Works like [`spellcheck`], but takes how similar suggestions must be
+/// rather than how many edits away, see
+/// [`normalized_levenshtein`](crate::normalized_levenshtein). Returns
+/// the words with a similarity of at least `min_ratio`, so 0.85 for 85%
+/// similar, most similar first, ties in dictionary order.
+/// ```
+/// use spelling::spellcheck_by_ratio;
+/// let dictionary_string = "restaurant\nrestrain\nrest";
+/// assert_eq!(spellcheck_by_ratio(dictionary_string, "restaraunt", 0.8), vec!["restaurant"]);
+/// assert_eq!(spellcheck_by_ratio(dictionary_string, "restaraunt", 0.5), vec!["restaurant", "restrain"]);
+/// ```
+pub fn spellcheck_by_ratio<'a>(dictionary_string: &'a str, word: &str, min_ratio: f64) -> Vec<&'a str> {
+    let word_len = word.chars().count();
+    let mut out: Vec<(&str, f64)> = dictionary_string
+        .split('\n')
+        .filter_map(|string_in| {
+            let longest = word_len.max(string_in.chars().count());
+            // rounded up, the ratio check below is exact
+            let bound = ((1.0 - min_ratio) * longest as f64).ceil().min(longest as f64) as usize;
+            let distance = crate::distance::levenshtein_distance_bounded(word, string_in, bound).within()?;
+            let ratio = distance_ratio(distance, longest);
+            (ratio >= min_ratio).then_some((string_in, ratio))
+        })
+        .collect();
+    // stable, so ties keep dictionary order
+    out.sort_by(|a, b| b.1.total_cmp(&a.1));
+    out.into_iter().map(|x| x.0).collect()
+}
+
+/// Works like [`spellcheck`], but ranks the words that sound like `word`
+/// according to `phonetic` first, so "sity" suggests "site" before "city".
+/// Within each group suggestions are sorted by distance, ties in dictionary
+/// order.
+/// ```
+/// use spelling::{spellcheck, spellcheck_phonetic, Soundex};
+/// let dictionary_string = "city\nsite";
+/// assert_eq!(spellcheck(dictionary_string, "sity", 1), vec!["city", "site"]);
+/// assert_eq!(spellcheck_phonetic(dictionary_string, "sity", 1, &Soundex), vec!["site", "city"]);
+/// ```
+pub fn spellcheck_phonetic<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    phonetic: &impl Phonetic,
+) -> Vec<&'a str> {
+    let codes: Vec<String> = phonetic.codes(word).into_iter().filter(|code| !code.is_empty()).collect();
+    let mut out: Vec<(&str, bool, usize)> = spellcheck_with_distances(dictionary_string, word, distance)
+        .into_iter()
+        .map(|(string_in, out)| {
+            let alike = phonetic.codes(string_in).iter().any(|code| codes.contains(code));
+            (string_in, alike, out)
+        })
+        .collect();
+    // stable and already sorted by distance
+    out.sort_by_key(|x| !x.1);
+    out.into_iter().map(|x| x.0).collect()
+}
+
+/// Works like [`spellcheck`], but takes a [`FrequencyDictionary`] and orders
+/// suggestions at the same distance by descending count instead of dictionary
+/// order. Words with the same distance and count keep dictionary order.
+///
+/// Each [`Suggestion`]'s score is its distance minus its count scaled below
+/// 1, so it sorts the same way.
+/// ```
+/// use spelling::{spellcheck_ranked, FrequencyDictionary};
+/// let dictionary = FrequencyDictionary::from_tab_separated("thin\t10\nthings\t5\nthing\t200");
+/// let suggestions = spellcheck_ranked(&dictionary, "thinga", 2);
+/// let words: Vec<&str> = suggestions.iter().map(|suggestion| suggestion.word).collect();
+/// assert_eq!(words, vec!["thing", "things", "thin"]);
+/// assert_eq!((suggestions[0].distance, suggestions[0].frequency), (1, 200));
+/// ```
+pub fn spellcheck_ranked<'a>(
+    dictionary: &'a FrequencyDictionary,
+    word: &str,
+    distance: usize,
+) -> Vec<Suggestion<'a>> {
+    let mut scratch = DistanceScratch::new();
+    let mut out: Vec<((&str, u64), usize)> = dictionary
+        .iter()
+        .filter_map(|entry| {
+            levenshtein_distance_bounded_in(&mut scratch, word, entry.0, distance)
+                .within()
+                .map(|out| (entry, out))
+        })
+        .collect();
+    // stable, so ties keep dictionary order
+    out.sort_by_key(|&((_, count), distance)| (distance, std::cmp::Reverse(count)));
+    // kept below 1 so it only ever breaks ties between distances
+    let max = dictionary.iter().map(|(_, count)| count).max().unwrap_or(0) as f64 + 1.0;
+    out.into_iter()
+        .map(|((word, frequency), distance)| Suggestion {
+            word,
+            distance,
+            frequency,
+            score: distance as f64 - frequency as f64 / max,
+        })
+        .collect()
+}
+
+/// Works like [`spellcheck`], but returns the [`ScoreBreakdown`] for every
+/// suggestion so you can see why it ranked where it did. Sorts by score.
+/// ```
+/// use spelling::spellcheck_explained;
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// for candidate in spellcheck_explained(dictionary_string, "restaraunt", 3) {
+///     println!("{}: {:?}", candidate.word, candidate.breakdown);
+/// }
+/// ```
+pub fn spellcheck_explained<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+) -> Vec<Candidate<'a>> {
+    Pipeline::default().run(dictionary_string.split('\n'), word, distance)
+}
+
+/// The first stage of a [`Pipeline`], finds the words in the dictionary that
+/// could be suggestions.
+pub trait CandidateGenerator: Send + Sync {
+    /// Returns the candidates for `word` within `distance` out of `words`, in
+    /// dictionary order.
+    fn generate<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+    ) -> Vec<Candidate<'a>>;
+
+    /// Like [`generate`](CandidateGenerator::generate) but appends to `out`,
+    /// so a buffer can be reused across many queries.
+    fn generate_into<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+        out: &mut Vec<Candidate<'a>>,
+    ) {
+        out.extend(self.generate(words, word, distance));
+    }
+}
+
+/// Drops candidates from a [`Pipeline`]. Implemented for any
+/// `Fn(&str, &Candidate) -> bool` where the first argument is the query.
+pub trait Filter: Send + Sync {
+    /// Returns `true` if `candidate` should be kept as a suggestion for `word`.
+    fn keep(&self, word: &str, candidate: &Candidate) -> bool;
+}
+
+impl<F> Filter for F
+where
+    F: Fn(&str, &Candidate) -> bool + Send + Sync,
+{
+    fn keep(&self, word: &str, candidate: &Candidate) -> bool {
+        self(word, candidate)
+    }
+}
+
+/// Drops candidates shorter than the given number of `char`s. `MinLength(1)`
+/// drops empty words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinLength(pub usize);
+
+impl Filter for MinLength {
+    fn keep(&self, _word: &str, candidate: &Candidate) -> bool {
+        self.0 == 0 || candidate.word.chars().nth(self.0 - 1).is_some()
+    }
+}
+
+/// Generates candidates by Levenshtein distance. This is what [`spellcheck`]
+/// uses.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LevenshteinGenerator;
+
+impl CandidateGenerator for LevenshteinGenerator {
+    fn generate<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+    ) -> Vec<Candidate<'a>> {
+        let mut out = Vec::new();
+        self.generate_into(words, word, distance, &mut out);
+        out
+    }
+
+    fn generate_into<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+        out: &mut Vec<Candidate<'a>>,
+    ) {
+        let mut scratch = DistanceScratch::new();
+        out.extend(words.filter_map(|string_in| {
+            levenshtein_distance_bounded_in(&mut scratch, word, string_in, distance)
+                .within()
+                .map(|distance| Candidate {
+                    word: string_in,
+                    breakdown: ScoreBreakdown::from_distance(distance),
+                })
+        }));
+    }
+}
+
+/// Generates candidates with any [`DistanceMetric`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricGenerator<M>(pub M);
+
+impl<M: DistanceMetric> CandidateGenerator for MetricGenerator<M> {
+    fn generate<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+    ) -> Vec<Candidate<'a>> {
+        let mut out = Vec::new();
+        self.generate_into(words, word, distance, &mut out);
+        out
+    }
+
+    fn generate_into<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+        out: &mut Vec<Candidate<'a>>,
+    ) {
+        out.extend(words.filter_map(|string_in| {
+            self.0
+                .distance_bounded(word, string_in, distance)
+                .within()
+                .map(|distance| Candidate {
+                    word: string_in,
+                    breakdown: ScoreBreakdown::from_distance(distance),
+                })
+        }));
+    }
+}
+
+/// Suggestion generation as a series of stages:
+/// candidate generation → filters → rankers → truncation.
+///
+/// [`Pipeline::default`] is what [`spellcheck`] runs. Use [`Pipeline::builder`]
+/// to swap out or add stages.
+/// ```
+/// use spelling::{Candidate, Pipeline};
+/// let pipeline = Pipeline::builder()
+///     .filter(|word: &str, candidate: &Candidate| candidate.word.len() >= word.len())
+///     .limit(5)
+///     .build();
+/// let dictionary_string = include_str!("words.txt"); // newline separated
+/// pipeline.run(dictionary_string.split('\n'), "restaraunt", 3);
+/// ```
+pub struct Pipeline {
+    generator: Box<dyn CandidateGenerator>,
+    filters: Vec<Box<dyn Filter>>,
+    rankers: Vec<Box<dyn Ranker>>,
+    limit: Option<usize>,
+}
+
+impl Pipeline {
+    /// Starts building a pipeline.
+    pub fn builder() -> PipelineBuilder {
+        PipelineBuilder::default()
+    }
+
+    /// Runs every stage for `word` against the dictionary `words` and returns
+    /// the surviving candidates, best first.
+    pub fn run<'a>(
+        &self,
+        words: impl IntoIterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+    ) -> Vec<Candidate<'a>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("pipeline", word, distance).entered();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        let mut candidates = self.generator.generate(&mut words.into_iter(), word, distance);
+        #[cfg(feature = "tracing")]
+        let generated = candidates.len();
+        candidates.retain(|candidate| self.filters.iter().all(|filter| filter.keep(word, candidate)));
+        for ranker in &self.rankers {
+            ranker.rank(word, &mut candidates);
+        }
+        if let Some(limit) = self.limit {
+            candidates.truncate(limit);
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            generated,
+            suggestions = candidates.len(),
+            elapsed = ?start.elapsed(),
+            "pipeline finished"
+        );
+        candidates
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Pipeline::builder().build()
+    }
+}
+
+/// Builds a [`Pipeline`].
+///
+/// Starts with [`LevenshteinGenerator`], no filters and no limit. If no
+/// rankers are added, [`ScoreRanker`] is used. Rankers run in the order they
+/// were added, so with stable sorts the last one added has the final say.
+#[derive(Default)]
+pub struct PipelineBuilder {
+    generator: Option<Box<dyn CandidateGenerator>>,
+    filters: Vec<Box<dyn Filter>>,
+    rankers: Vec<Box<dyn Ranker>>,
+    limit: Option<usize>,
+}
+
+impl PipelineBuilder {
+    /// Replaces the candidate generator.
+    pub fn generator(mut self, generator: impl CandidateGenerator + 'static) -> Self {
+        self.generator = Some(Box::new(generator));
+        self
+    }
+
+    /// Adds a filter. Candidates have to pass every filter to be kept.
+    pub fn filter(mut self, filter: impl Filter + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Adds a ranker.
+    pub fn ranker(mut self, ranker: impl Ranker + 'static) -> Self {
+        self.rankers.push(Box::new(ranker));
+        self
+    }
+
+    /// Keeps at most `limit` suggestions.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Finishes the pipeline.
+    pub fn build(self) -> Pipeline {
+        let mut rankers = self.rankers;
+        if rankers.is_empty() {
+            rankers.push(Box::new(ScoreRanker));
+        }
+        Pipeline {
+            generator: self.generator.unwrap_or_else(|| Box::new(LevenshteinGenerator)),
+            filters: self.filters,
+            rankers,
+            limit: self.limit,
+        }
+    }
+}
+
+/// Builds a [`SpellChecker`] from several dictionaries, say a base word
+/// list, a domain glossary and a [`UserDictionary`]. A word in any of them is
+/// correct. The dictionaries are merged highest priority first, so with the
+/// default [`SortPolicy::DictionaryOrder`] suggestions from higher priority
+/// dictionaries win ties; equal priorities keep the order they were added in.
+/// ```
+/// use spelling::{Dictionary, SpellChecker};
+/// let checker = SpellChecker::builder()
+///     .add_dictionary(Dictionary::from_newline_separated("cat\ncar"), 0)
+///     .add_dictionary(Dictionary::from_newline_separated("cab"), 1)
+///     .build();
+/// assert_eq!(checker.suggest("caz", 1), vec!["cab", "cat", "car"]);
+/// assert!(checker.contains("cat") && checker.contains("cab"));
+/// ```
+#[derive(Debug, Default)]
+pub struct SpellCheckerBuilder {
+    dictionaries: Vec<(Dictionary, u32)>,
+    user_dictionary: UserDictionary,
+}
+
+impl SpellCheckerBuilder {
+    /// Adds `dictionary` with `priority`, higher wins.
+    pub fn add_dictionary(mut self, dictionary: Dictionary, priority: u32) -> Self {
+        self.dictionaries.push((dictionary, priority));
+        self
+    }
+
+    /// Sets the [`UserDictionary`], see
+    /// [`SpellChecker::set_user_dictionary`].
+    pub fn user_dictionary(mut self, user_dictionary: UserDictionary) -> Self {
+        self.user_dictionary = user_dictionary;
+        self
+    }
+
+    /// Merges the dictionaries, keeping each word once, where it has the
+    /// highest priority.
+    pub fn build(self) -> SpellChecker {
+        let mut dictionaries = self.dictionaries;
+        // stable, so equal priorities keep their order
+        dictionaries.sort_by_key(|&(_, priority)| std::cmp::Reverse(priority));
+        let mut seen = std::collections::HashSet::new();
+        let merged = dictionaries
+            .iter()
+            .flat_map(|(dictionary, _)| dictionary.iter())
+            .filter(|word| seen.insert(*word))
+            .collect();
+        let mut checker = SpellChecker::from_dictionary(merged);
+        checker.set_user_dictionary(self.user_dictionary);
+        checker
+    }
+}
+
+/// Generates candidates with [`levenshtein_distance_folded`], so only real
+/// edits count towards the distance limit and case or accent differences are
+/// recorded as [`ScoreBreakdown::minor_edits`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FoldedLevenshteinGenerator;
+
+impl CandidateGenerator for FoldedLevenshteinGenerator {
+    fn generate<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+    ) -> Vec<Candidate<'a>> {
+        let mut out = Vec::new();
+        self.generate_into(words, word, distance, &mut out);
+        out
+    }
+
+    fn generate_into<'a>(
+        &self,
+        words: &mut dyn Iterator<Item = &'a str>,
+        word: &str,
+        distance: usize,
+        out: &mut Vec<Candidate<'a>>,
+    ) {
+        out.extend(words.filter_map(|string_in| {
+            let (edits, minor_edits) = levenshtein_distance_folded(word, string_in);
+            if edits > distance {
+                return None;
+            }
+            let mut breakdown = ScoreBreakdown::from_distance(edits);
+            breakdown.minor_edits = minor_edits;
+            Some(Candidate {
+                word: string_in,
+                breakdown,
+            })
+        }));
+    }
+}
+
+/// How a [`SpellChecker`] measures distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMode {
+    /// Plain distance from the checker's [`DistanceMetric`], every difference
+    /// is a full edit.
+    #[default]
+    Strict,
+    /// Case-only and accent-only substitutions are minor edits that rank below
+    /// real ones, see [`levenshtein_distance_folded`]. This always uses the
+    /// Levenshtein distance, whatever the checker's metric.
+    CaseAndAccentTolerant,
+}
+
+/// A dictionary along with how to measure distance and rank suggestions from
+/// it. Generic over the [`DistanceMetric`], which is [`Levenshtein`] unless
+/// changed with [`metric`](SpellChecker::metric).
+/// ```
+/// use spelling::{RankingStrategy, SpellChecker};
+/// let mut checker = SpellChecker::with_frequencies(vec![("thin", 10), ("thing", 200)]);
+/// assert_eq!(checker.suggest("thinb", 1), vec!["thin", "thing"]);
+/// checker.set_ranking(RankingStrategy::Frequency);
+/// assert_eq!(checker.suggest("thinb", 1), vec!["thing", "thin"]);
+/// ```
+#[derive(Debug)]
+pub struct SpellChecker<M = Levenshtein> {
+    metric: M,
+    dictionary: Dictionary,
+    /// The same words, for [`contains`](SpellChecker::contains).
+    words: std::collections::HashSet<String>,
+    user_dictionary: UserDictionary,
+    /// Words that are correct for now but never suggested.
+    ignored: std::collections::HashSet<String>,
+    /// The dictionary and added words for [`complete`](SpellChecker::complete),
+    /// built on first use.
+    trie: std::sync::OnceLock<Trie>,
+    frequencies: std::collections::HashMap<String, u64>,
+    total_frequency: u64,
+    ranking: RankingStrategy,
+    sort_policy: SortPolicy,
+    distance_mode: DistanceMode,
+    min_candidate_length: usize,
+    max_word_length: usize,
+    invisible_characters: InvisibleCharacters,
+    first_character: FirstCharacter,
+    compounds: Option<Compounds>,
+    min_confidence: f64,
+    metrics: SharedMetrics,
+}
+
+/// What [`SpellChecker::check`] found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckResult<'a> {
+    /// The word is spelled correctly.
+    Correct,
+    /// The word isn't in the dictionary.
+    Misspelled {
+        /// The suggestions, best first, possibly none.
+        suggestions: Vec<&'a str>,
+    },
+}
+
+/// Whether a [`SpellChecker`] favours suggestions starting with the same
+/// character as the misspelled word. Most typos keep the first letter, so
+/// this is a cheap way to rank or narrow down suggestions. In
+/// [`DistanceMode::CaseAndAccentTolerant`] the first characters are compared
+/// ignoring case and accents.
+/// ```
+/// use spelling::{FirstCharacter, SpellChecker};
+/// let checker = SpellChecker::new("at\ncat\nbrat");
+/// assert_eq!(checker.suggest("bat", 1), vec!["at", "cat", "brat"]);
+/// let checker = checker.first_character(FirstCharacter::Prefer);
+/// assert_eq!(checker.suggest("bat", 1), vec!["brat", "at", "cat"]);
+/// let checker = checker.first_character(FirstCharacter::Restrict { min_suggestions: 1 });
+/// assert_eq!(checker.suggest("bat", 1), vec!["brat"]);
+/// assert_eq!(checker.suggest("xat", 1), vec!["at", "cat"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FirstCharacter {
+    /// The first character is like any other. This is the default.
+    #[default]
+    Ignore,
+    /// Suggestions starting with the same character come first, each group
+    /// ranked as usual.
+    Prefer,
+    /// Only words starting with the same character are scanned, unless that
+    /// finds fewer than `min_suggestions`, in which case the whole dictionary
+    /// is.
+    Restrict {
+        /// How many suggestions are enough not to fall back to a full scan.
+        min_suggestions: usize,
+    },
+}
+
+/// What a [`SpellChecker`] does with words containing control characters or
+/// invisible formatting characters like zero-width joiners and byte order
+/// marks. Left alone, these make words look misspelled by one edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvisibleCharacters {
+    /// Check the word as is.
+    Keep,
+    /// Remove them and check what's left. This is the default.
+    #[default]
+    Strip,
+    /// Don't suggest anything for words containing them.
+    Reject,
+}
+
+/// How a [`SpellChecker`] accepts compound words that aren't in its
+/// dictionary, like German "Fußballspieler" out of "Fußball" and "Spieler".
+/// A word is accepted if it splits into two or more dictionary words of at
+/// least [`min_part_length`](Compounds::min_part_length) `char`s, optionally
+/// joined by linking elements, like the "s" in "Arbeitszimmer". Parts after
+/// the first also match dictionary words with a capital first letter, as
+/// nouns lose theirs inside a compound.
+/// ```
+/// use spelling::{CheckResult, Compounds, SpellChecker};
+/// let checker = SpellChecker::new("Arbeit\nFußball\nSpieler\nZimmer").compounds(Compounds::german());
+/// assert!(checker.contains("Fußballspieler"));
+/// assert!(checker.contains("Arbeitszimmer"));
+/// assert!(!checker.contains("Fußballs"));
+/// assert_eq!(checker.check("Fußballspieler", 1), CheckResult::Correct);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compounds {
+    min_part_length: usize,
+    linking_elements: Vec<String>,
+}
+
+impl Default for Compounds {
+    fn default() -> Self {
+        Compounds {
+            min_part_length: 3,
+            linking_elements: Vec::new(),
+        }
+    }
+}
+
+impl Compounds {
+    /// Parts of at least 3 `char`s, without linking elements.
+    pub fn new() -> Self {
+        Compounds::default()
+    }
+
+    /// German linking elements: "s", "es", "n", "en", "e", "er" and "ens".
+    pub fn german() -> Self {
+        ["s", "es", "n", "en", "e", "er", "ens"]
+            .iter()
+            .fold(Compounds::new(), |compounds, element| compounds.linking_element(element))
+    }
+
+    /// Dutch linking elements: "s", "e" and "en".
+    pub fn dutch() -> Self {
+        ["s", "e", "en"]
+            .iter()
+            .fold(Compounds::new(), |compounds, element| compounds.linking_element(element))
+    }
+
+    /// Sets the shortest part, in `char`s, builder style. Short parts let
+    /// misspellings through as compounds of short words, so the default is 3.
+    pub fn min_part_length(mut self, min_part_length: usize) -> Self {
+        self.min_part_length = min_part_length;
+        self
+    }
+
+    /// Sets the shortest part, in `char`s.
+    pub fn set_min_part_length(&mut self, min_part_length: usize) {
+        self.min_part_length = min_part_length;
+    }
+
+    /// Allows `element` between parts, builder style. Empty elements are
+    /// ignored.
+    pub fn linking_element(mut self, element: &str) -> Self {
+        self.add_linking_element(element);
+        self
+    }
+
+    /// Allows `element` between parts.
+    pub fn add_linking_element(&mut self, element: &str) {
+        if !element.is_empty() && !self.linking_elements.iter().any(|existing| existing == element) {
+            self.linking_elements.push(element.to_owned());
+        }
+    }
+
+    /// The elements allowed between parts.
+    pub fn linking_elements(&self) -> &[String] {
+        &self.linking_elements
+    }
+}
+
+/// `word` with its first `char` uppercased.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Whether `c` is a control character or an invisible formatting character.
+fn is_invisible(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{ad}' // soft hyphen
+                | '\u{180e}' // mongolian vowel separator
+                | '\u{200b}'..='\u{200f}' // zero-width space, (non-)joiner, direction marks
+                | '\u{202a}'..='\u{202e}' // direction embeddings and overrides
+                | '\u{2060}'..='\u{2064}' // word joiner and invisible operators
+                | '\u{2066}'..='\u{2069}' // direction isolates
+                | '\u{feff}' // byte order mark
+        )
+}
+
+/// Receives counts from a [`SpellChecker`] as it answers queries, so servers
+/// can export them to Prometheus or similar without wrapping every call.
+/// Every method does nothing by default.
+/// ```
+/// use std::sync::Arc;
+/// use spelling::{MetricsCounters, SpellChecker};
+/// let counters = Arc::new(MetricsCounters::default());
+/// let checker = SpellChecker::new("thin\nthing").metrics(counters.clone());
+/// checker.suggest("thinn", 1);
+/// assert_eq!(counters.queries(), 1);
+/// assert_eq!(counters.average_scanned(), 2.0);
+/// ```
+pub trait Metrics: Send + Sync {
+    /// Called once per query with how many dictionary words had their distance
+    /// measured and how many suggestions came back.
+    fn query(&self, scanned: usize, suggestions: usize) {
+        let _ = (scanned, suggestions);
+    }
+
+    /// Called when a query is answered from a cache instead of scanning the
+    /// dictionary.
+    fn cache_hit(&self) {}
+}
+
+impl<T: Metrics + ?Sized> Metrics for std::sync::Arc<T> {
+    fn query(&self, scanned: usize, suggestions: usize) {
+        (**self).query(scanned, suggestions)
+    }
+
+    fn cache_hit(&self) {
+        (**self).cache_hit()
+    }
+}
+
+/// [`Metrics`] that throws everything away, the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoMetrics;
+
+impl Metrics for NoMetrics {}
+
+/// [`Metrics`] that keeps running totals in atomics.
+#[derive(Debug, Default)]
+pub struct MetricsCounters {
+    queries: std::sync::atomic::AtomicU64,
+    cache_hits: std::sync::atomic::AtomicU64,
+    scanned: std::sync::atomic::AtomicU64,
+}
+
+impl MetricsCounters {
+    /// How many queries have been served, cache hits included.
+    pub fn queries(&self) -> u64 {
+        self.queries.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// How many queries were answered from a cache.
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// The average number of dictionary words scanned per query, 0 before the
+    /// first query.
+    pub fn average_scanned(&self) -> f64 {
+        match self.queries() {
+            0 => 0.0,
+            queries => self.scanned.load(std::sync::atomic::Ordering::Relaxed) as f64 / queries as f64,
+        }
+    }
+}
+
+impl Metrics for MetricsCounters {
+    fn query(&self, scanned: usize, _suggestions: usize) {
+        use std::sync::atomic::Ordering;
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.scanned.fetch_add(scanned as u64, Ordering::Relaxed);
+    }
+
+    fn cache_hit(&self) {
+        use std::sync::atomic::Ordering;
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Lets [`SpellChecker`] keep deriving `Debug`.
+#[derive(Clone)]
+struct SharedMetrics(std::sync::Arc<dyn Metrics>);
+
+impl std::fmt::Debug for SharedMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Metrics(..)")
+    }
+}
+
+/// The default for [`SpellChecker::min_confidence`].
+pub const DEFAULT_MIN_CONFIDENCE: f64 = 0.75;
+
+/// The default for [`SpellChecker::max_word_length`].
+pub const DEFAULT_MAX_WORD_LENGTH: usize = 64;
+
+impl<M: Default> Default for SpellChecker<M> {
+    fn default() -> Self {
+        SpellChecker {
+            metric: M::default(),
+            dictionary: Dictionary::default(),
+            words: Default::default(),
+            user_dictionary: UserDictionary::default(),
+            ignored: Default::default(),
+            trie: Default::default(),
+            frequencies: Default::default(),
+            total_frequency: 0,
+            ranking: RankingStrategy::default(),
+            sort_policy: SortPolicy::default(),
+            distance_mode: DistanceMode::default(),
+            min_candidate_length: 1,
+            max_word_length: DEFAULT_MAX_WORD_LENGTH,
+            invisible_characters: InvisibleCharacters::default(),
+            first_character: FirstCharacter::default(),
+            compounds: None,
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+            metrics: SharedMetrics(std::sync::Arc::new(NoMetrics)),
+        }
+    }
+}
+
+impl SpellChecker {
+    /// Creates a checker from a newline separated `dictionary_string`, see
+    /// [`Dictionary::from_newline_separated`].
+    pub fn new(dictionary_string: &str) -> Self {
+        SpellChecker::from_dictionary(Dictionary::from_newline_separated(dictionary_string))
+    }
+
+    /// Creates a checker from a [`Dictionary`].
+    pub fn from_dictionary(dictionary: Dictionary) -> Self {
+        SpellChecker {
+            words: dictionary.iter().map(str::to_owned).collect(),
+            dictionary,
+            ..Default::default()
+        }
+    }
+
+    /// Starts building a checker from several dictionaries.
+    pub fn builder() -> SpellCheckerBuilder {
+        SpellCheckerBuilder::default()
+    }
+
+    /// Creates a checker from words and how often they occur.
+    pub fn with_frequencies<S: AsRef<str>>(words: impl IntoIterator<Item = (S, u64)>) -> Self {
+        let mut checker = SpellChecker::default();
+        let mut dictionary = Vec::new();
+        for (word, count) in words {
+            let word = word.as_ref();
+            dictionary.push(word.to_owned());
+            checker.words.insert(word.to_owned());
+            checker.total_frequency += count;
+            checker.frequencies.insert(word.to_owned(), count);
+        }
+        checker.dictionary = dictionary.into_iter().collect();
+        checker
+    }
+}
+
+impl<M: DistanceMetric> SpellChecker<M> {
+    /// Switches to measuring distance with `metric`.
+    /// ```
+    /// use spelling::{Levenshtein, SpellChecker};
+    /// let checker = SpellChecker::new("thin\nthing").metric(Levenshtein);
+    /// assert_eq!(checker.suggest("thinga", 1), vec!["thing"]);
+    /// ```
+    pub fn metric<N: DistanceMetric>(self, metric: N) -> SpellChecker<N> {
+        SpellChecker {
+            metric,
+            dictionary: self.dictionary,
+            words: self.words,
+            user_dictionary: self.user_dictionary,
+            ignored: self.ignored,
+            trie: self.trie,
+            frequencies: self.frequencies,
+            total_frequency: self.total_frequency,
+            ranking: self.ranking,
+            sort_policy: self.sort_policy,
+            distance_mode: self.distance_mode,
+            min_candidate_length: self.min_candidate_length,
+            max_word_length: self.max_word_length,
+            invisible_characters: self.invisible_characters,
+            first_character: self.first_character,
+            compounds: self.compounds,
+            min_confidence: self.min_confidence,
+            metrics: self.metrics,
+        }
+    }
+
+    /// Sets the [`RankingStrategy`], builder style.
+    pub fn ranking(mut self, ranking: RankingStrategy) -> Self {
+        self.ranking = ranking;
+        self
+    }
+
+    /// Sets the [`RankingStrategy`].
+    pub fn set_ranking(&mut self, ranking: RankingStrategy) {
+        self.ranking = ranking;
+    }
+
+    /// Sets how suggestions with the same score are ordered, builder style.
+    /// ```
+    /// use spelling::{SortPolicy, SpellChecker};
+    /// let checker = SpellChecker::new("thins\nthing\nthin");
+    /// assert_eq!(checker.suggest("thinx", 1), vec!["thins", "thing", "thin"]);
+    /// let checker = checker.sort_policy(SortPolicy::Lexicographic);
+    /// assert_eq!(checker.suggest("thinx", 1), vec!["thin", "thing", "thins"]);
+    /// ```
+    pub fn sort_policy(mut self, sort_policy: SortPolicy) -> Self {
+        self.sort_policy = sort_policy;
+        self
+    }
+
+    /// Sets how suggestions with the same score are ordered, see
+    /// [`SortPolicy`].
+    pub fn set_sort_policy(&mut self, sort_policy: SortPolicy) {
+        self.sort_policy = sort_policy;
+    }
+
+    /// Sets the [`DistanceMode`], builder style.
+    pub fn distance_mode(mut self, distance_mode: DistanceMode) -> Self {
+        self.distance_mode = distance_mode;
+        self
+    }
+
+    /// Sets the [`DistanceMode`].
+    pub fn set_distance_mode(&mut self, distance_mode: DistanceMode) {
+        self.distance_mode = distance_mode;
+    }
+
+    /// Only suggests words with at least `min_candidate_length` `char`s,
+    /// builder style. Defaults to 1, so empty words are never suggested; set
+    /// it to 0 to allow them.
+    pub fn min_candidate_length(mut self, min_candidate_length: usize) -> Self {
+        self.min_candidate_length = min_candidate_length;
+        self
+    }
+
+    /// Sets the minimum suggestion length, see
+    /// [`min_candidate_length`](SpellChecker::min_candidate_length).
+    pub fn set_min_candidate_length(&mut self, min_candidate_length: usize) {
+        self.min_candidate_length = min_candidate_length;
+    }
+
+    /// Doesn't try to find suggestions for words longer than
+    /// `max_word_length` `char`s, builder style. Defaults to
+    /// [`DEFAULT_MAX_WORD_LENGTH`], so a pathological token in scraped text
+    /// can't blow up the time or memory spent on it.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("thin\nthing").max_word_length(4);
+    /// assert_eq!(checker.suggest("thin", 1), vec!["thin", "thing"]);
+    /// assert!(checker.suggest("thing", 1).is_empty());
+    /// ```
+    pub fn max_word_length(mut self, max_word_length: usize) -> Self {
+        self.max_word_length = max_word_length;
+        self
+    }
+
+    /// Sets the maximum word length, see
+    /// [`max_word_length`](SpellChecker::max_word_length).
+    pub fn set_max_word_length(&mut self, max_word_length: usize) {
+        self.max_word_length = max_word_length;
+    }
+
+    /// Sets what to do with control and invisible characters in the words
+    /// being checked, builder style.
+    /// ```
+    /// use spelling::{InvisibleCharacters, SpellChecker};
+    /// let checker = SpellChecker::new("thing");
+    /// assert_eq!(checker.suggest("\u{feff}thing", 0), vec!["thing"]);
+    /// let checker = checker.invisible_characters(InvisibleCharacters::Reject);
+    /// assert!(checker.suggest("\u{feff}thing", 1).is_empty());
+    /// ```
+    pub fn invisible_characters(mut self, invisible_characters: InvisibleCharacters) -> Self {
+        self.invisible_characters = invisible_characters;
+        self
+    }
+
+    /// Sets what to do with control and invisible characters, see
+    /// [`invisible_characters`](SpellChecker::invisible_characters).
+    pub fn set_invisible_characters(&mut self, invisible_characters: InvisibleCharacters) {
+        self.invisible_characters = invisible_characters;
+    }
+
+    /// Sets whether suggestions starting with the same character as the word
+    /// are favoured, builder style, see [`FirstCharacter`].
+    pub fn first_character(mut self, first_character: FirstCharacter) -> Self {
+        self.first_character = first_character;
+        self
+    }
+
+    /// Sets whether suggestions starting with the same character are
+    /// favoured, see [`FirstCharacter`].
+    pub fn set_first_character(&mut self, first_character: FirstCharacter) {
+        self.first_character = first_character;
+    }
+
+    /// Accepts words made of dictionary words, builder style, see
+    /// [`Compounds`].
+    pub fn compounds(mut self, compounds: Compounds) -> Self {
+        self.compounds = Some(compounds);
+        self
+    }
+
+    /// Sets whether and how compound words are accepted, see [`Compounds`].
+    /// `None`, the default, only accepts whole dictionary words.
+    pub fn set_compounds(&mut self, compounds: Option<Compounds>) {
+        self.compounds = compounds;
+    }
+
+    /// Sets how sure [`correct`](SpellChecker::correct) has to be to change a
+    /// word, from 0.0 to 1.0, builder style. Defaults to
+    /// [`DEFAULT_MIN_CONFIDENCE`].
+    pub fn min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// Sets how sure [`correct`](SpellChecker::correct) has to be, see
+    /// [`min_confidence`](SpellChecker::min_confidence).
+    pub fn set_min_confidence(&mut self, min_confidence: f64) {
+        self.min_confidence = min_confidence;
+    }
+
+    /// Sets where query counts are reported, builder style. Pass an
+    /// `Arc` to keep a handle you can read from, see [`Metrics`].
+    pub fn metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+        self.set_metrics(metrics);
+        self
+    }
+
+    /// Sets where query counts are reported.
+    pub fn set_metrics(&mut self, metrics: impl Metrics + 'static) {
+        self.metrics = SharedMetrics(std::sync::Arc::new(metrics));
+    }
+
+    /// The words suggestions come from.
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    /// The words added with [`add_word`](SpellChecker::add_word), say to save
+    /// them.
+    pub fn user_dictionary(&self) -> &UserDictionary {
+        &self.user_dictionary
+    }
+
+    /// Replaces the added words, say with ones loaded from a file.
+    /// ```
+    /// use spelling::{SpellChecker, UserDictionary};
+    /// let mut checker = SpellChecker::new("thin\nthing");
+    /// checker.set_user_dictionary(UserDictionary::from_newline_separated("thingy"));
+    /// assert!(checker.contains("thingy"));
+    /// ```
+    pub fn set_user_dictionary(&mut self, user_dictionary: UserDictionary) {
+        self.user_dictionary = user_dictionary;
+        self.trie.take();
+    }
+
+    /// Adds `word` to the [`UserDictionary`], so it's correct and can be
+    /// suggested. Returns whether it wasn't there already, see
+    /// [`UserDictionary::add_word`].
+    /// ```
+    /// use spelling::{CheckResult, SpellChecker};
+    /// let mut checker = SpellChecker::new("thin\nthing");
+    /// checker.add_word("thingy");
+    /// assert_eq!(checker.check("thingy", 1), CheckResult::Correct);
+    /// assert_eq!(checker.suggest("thingyy", 1), vec!["thingy"]);
+    /// checker.remove_word("thingy");
+    /// assert_eq!(checker.suggest("thingyy", 1), Vec::<&str>::new());
+    /// ```
+    pub fn add_word(&mut self, word: &str) -> bool {
+        let added = self.user_dictionary.add_word(word);
+        if added {
+            self.trie.take();
+        }
+        added
+    }
+
+    /// Removes `word` from the [`UserDictionary`], returning whether it was
+    /// there. Words in the main dictionary can't be removed.
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        let removed = self.user_dictionary.remove_word(word);
+        if removed {
+            self.trie.take();
+        }
+        removed
+    }
+
+    /// Treats `word` as correct from now on, without adding it to any
+    /// dictionary or suggesting it, say for an identifier in the project
+    /// being checked. Returns whether it wasn't ignored already.
+    /// ```
+    /// use spelling::{CheckResult, SpellChecker};
+    /// let mut checker = SpellChecker::new("thin\nthing");
+    /// checker.ignore("thingamajig");
+    /// assert_eq!(checker.check("thingamajig", 1), CheckResult::Correct);
+    /// assert!(checker.suggest("thingamajg", 1).is_empty());
+    /// ```
+    pub fn ignore(&mut self, word: &str) -> bool {
+        self.ignored.insert(word.to_owned())
+    }
+
+    /// [`ignore`](SpellChecker::ignore)s every word, say ones saved with
+    /// [`ignored`](SpellChecker::ignored) in an earlier session.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let mut checker = SpellChecker::new("thin");
+    /// checker.ignore_all(vec!["serde", "tokio"]);
+    /// let saved = checker.ignored().join("\n");
+    /// let mut next_session = SpellChecker::new("thin");
+    /// next_session.ignore_all(saved.lines());
+    /// assert_eq!(next_session.ignored(), vec!["serde", "tokio"]);
+    /// ```
+    pub fn ignore_all<S: AsRef<str>>(&mut self, words: impl IntoIterator<Item = S>) {
+        for word in words {
+            self.ignore(word.as_ref());
+        }
+    }
+
+    /// Stops ignoring `word`, returning whether it was ignored.
+    pub fn unignore(&mut self, word: &str) -> bool {
+        self.ignored.remove(word)
+    }
+
+    /// Stops ignoring every word.
+    pub fn clear_ignored(&mut self) {
+        self.ignored.clear();
+    }
+
+    /// The ignored words, sorted, to save them for the next session.
+    pub fn ignored(&self) -> Vec<&str> {
+        let mut ignored: Vec<&str> = self.ignored.iter().map(String::as_str).collect();
+        ignored.sort_unstable();
+        ignored
+    }
+
+    /// A hash of everything that affects suggestions: the words, their
+    /// frequencies and the settings, with the metric told apart by its
+    /// [`id`](DistanceMetric::id). `None` with a [`RankingStrategy::Custom`]
+    /// ranker, which can't be told apart from another.
+    #[cfg(feature = "cache")]
+    pub(crate) fn fingerprint(&self) -> Option<u64> {
+        use std::hash::{Hash, Hasher};
+        if let RankingStrategy::Custom(_) = self.ranking {
+            return None;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for word in self.dictionary.iter() {
+            word.hash(&mut hasher);
+        }
+        self.user_dictionary.hash(&mut hasher);
+        let mut frequencies: Vec<_> = self.frequencies.iter().collect();
+        frequencies.sort_unstable();
+        frequencies.hash(&mut hasher);
+        self.metric.id().hash(&mut hasher);
+        format!(
+            "{:?} {:?} {:?} {:?} {:?} {:?}",
+            self.ranking,
+            self.sort_policy,
+            self.distance_mode,
+            self.invisible_characters,
+            self.first_character,
+            self.compounds
+        )
+        .hash(&mut hasher);
+        (self.min_candidate_length, self.max_word_length).hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    /// Tells the checker's [`Metrics`] a query was answered from a cache.
+    #[cfg(any(feature = "tokenize", feature = "cache"))]
+    pub(crate) fn record_cache_hit(&self) {
+        self.metrics.0.cache_hit();
+    }
+
+    /// Whether `word` is in the dictionary or the [`UserDictionary`], or is
+    /// [`ignore`](SpellChecker::ignore)d, exactly, or is made of such words
+    /// when [`compounds`](SpellChecker::compounds) are on. This is a hash
+    /// lookup, unlike [`Dictionary::contains`].
+    pub fn contains(&self, word: &str) -> bool {
+        self.is_known(word) || self.ignored.contains(word) || self.is_compound(word)
+    }
+
+    fn is_known(&self, word: &str) -> bool {
+        self.words.contains(word) || self.user_dictionary.contains(word)
+    }
+
+    /// Whether `word` splits into two or more known words, see [`Compounds`].
+    fn is_compound(&self, word: &str) -> bool {
+        let compounds = match &self.compounds {
+            Some(compounds) => compounds,
+            None => return false,
+        };
+        let ends: Vec<usize> = word.char_indices().map(|(at, _)| at).chain(Some(word.len())).collect();
+        let last = ends.len() - 1;
+        // where a part can start: the beginning, or after a part and maybe a
+        // linking element
+        let mut starts = vec![false; ends.len()];
+        starts[0] = true;
+        for start in 0..last {
+            if !starts[start] {
+                continue;
+            }
+            for end in start + compounds.min_part_length.max(1)..=last {
+                let part = &word[ends[start]..ends[end]];
+                let inner = start > 0;
+                if !(self.is_known(part) || inner && self.is_known(&capitalize(part))) {
+                    continue;
+                }
+                if end == last {
+                    if inner {
+                        return true;
+                    }
+                    continue;
+                }
+                starts[end] = true;
+                for element in &compounds.linking_elements {
+                    if word[ends[end]..].starts_with(element.as_str()) {
+                        if let Ok(next) = ends.binary_search(&(ends[end] + element.len())) {
+                            starts[next] = true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Checks `word`, only looking for suggestions within `distance` if it
+    /// isn't in the dictionary. A word that matches a dictionary word once
+    /// invisible characters are stripped, or up to case and accents in
+    /// [`DistanceMode::CaseAndAccentTolerant`], is correct too.
+    /// ```
+    /// use spelling::{CheckResult, SpellChecker};
+    /// let checker = SpellChecker::new("thin\nthing");
+    /// assert_eq!(checker.check("thing", 1), CheckResult::Correct);
+    /// assert_eq!(
+    ///     checker.check("thinga", 1),
+    ///     CheckResult::Misspelled { suggestions: vec!["thing"] }
+    /// );
+    /// ```
+    pub fn check(&self, word: &str, distance: usize) -> CheckResult<'_> {
+        if self.contains(word) {
+            return CheckResult::Correct;
+        }
+        let candidates = self.suggest_explained(word, distance);
+        if candidates.iter().any(|candidate| candidate.breakdown.distance == 0) {
+            return CheckResult::Correct;
+        }
+        CheckResult::Misspelled {
+            suggestions: candidates.into_iter().map(|candidate| candidate.word).collect(),
+        }
+    }
+
+    /// How often `word` occurs, 0 if unknown.
+    pub fn frequency(&self, word: &str) -> u64 {
+        self.frequencies.get(word).copied().unwrap_or(0)
+    }
+
+    /// Returns suggestions for `word` within `distance`, best first.
+    pub fn suggest(&self, word: &str, distance: usize) -> Vec<&str> {
+        self.suggest_explained(word, distance)
+            .into_iter()
+            .map(|candidate| candidate.word)
+            .collect()
+    }
+
+    /// Returns the single best correction for `word`, or `word` itself if
+    /// it's correct or there's no confident one, for correcting as you type.
+    /// The confidence of a suggestion is `1 - distance / length`, so only
+    /// suggestions close enough for [`min_confidence`](SpellChecker::min_confidence)
+    /// are looked for, and a tie for the best isn't confident at all. A word
+    /// starting with a capital, or all in capitals, is also looked up in
+    /// lowercase and keeps its capitals.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::with_frequencies(vec![("hello", 90), ("help", 30), ("restaurant", 5), ("cat", 1), ("bat", 1)]);
+    /// assert_eq!(checker.correct("Restaraunt"), "Restaurant");
+    /// assert_eq!(checker.correct("hello"), "hello");
+    /// // "hello" and "help" tie
+    /// assert_eq!(checker.correct("helo"), "helo");
+    /// // one edit in three is too many
+    /// assert_eq!(checker.correct("cst"), "cst");
+    /// assert_eq!(checker.min_confidence(0.6).correct("cst"), "cat");
+    /// ```
+    pub fn correct<'a>(&'a self, word: &'a str) -> std::borrow::Cow<'a, str> {
+        use std::borrow::Cow;
+        let length = word.chars().count();
+        let lowercase = word.to_lowercase();
+        if self.contains(word) || self.contains(&lowercase) || length == 0 {
+            return Cow::Borrowed(word);
+        }
+        let max_distance = ((1.0 - self.min_confidence.clamp(0.0, 1.0)) * length as f64 + 1e-9) as usize;
+        let mut candidates = self.suggest_explained(word, max_distance);
+        if lowercase != word {
+            let lowercase_candidates = self.suggest_explained(&lowercase, max_distance);
+            let closest = |candidates: &[Candidate<'_>]| candidates.first().map_or(usize::MAX, |best| best.breakdown.distance);
+            if closest(&lowercase_candidates) < closest(&candidates) {
+                candidates = lowercase_candidates;
+            }
+        }
+        let best = match candidates.first() {
+            Some(best) if best.breakdown.distance > 0 => best,
+            _ => return Cow::Borrowed(word),
+        };
+        if candidates.get(1).is_some_and(|second| second.breakdown.score() <= best.breakdown.score()) {
+            return Cow::Borrowed(word);
+        }
+        let is_upper = |c: char| !c.is_lowercase();
+        if length > 1 && word.chars().all(is_upper) {
+            Cow::Owned(best.word.to_uppercase())
+        } else if word.chars().next().is_some_and(char::is_uppercase) {
+            Cow::Owned(capitalize(best.word))
+        } else {
+            Cow::Borrowed(best.word)
+        }
+    }
+
+    /// Like [`suggest`](SpellChecker::suggest), with the distance worked out
+    /// from `word`, see [`MaxDistance`].
+    /// ```
+    /// use spelling::{MaxDistance, SpellChecker};
+    /// let checker = SpellChecker::new("cat\ncart\ncaterpillar");
+    /// assert_eq!(checker.suggest_within("cst", MaxDistance::default()), vec!["cat"]);
+    /// assert_eq!(checker.suggest_within("catepillra", MaxDistance::default()), vec!["caterpillar"]);
+    /// ```
+    pub fn suggest_within(&self, word: &str, max_distance: MaxDistance) -> Vec<&str> {
+        self.suggest(word, max_distance.for_word(word))
+    }
+
+    /// Like [`suggest`](SpellChecker::suggest), for a word that may only be
+    /// partly typed, like in a search box. Words starting with `word` are
+    /// suggested even when they're more than `max_distance` longer, and rank
+    /// first, shortest first. The rest rank by how many `char`s they share at
+    /// the start with `word`, then as usual.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("rust\nresist\nrestaurant\nrestore");
+    /// assert_eq!(checker.suggest("rest", 2), vec!["rust", "resist"]);
+    /// assert_eq!(checker.suggest_prefix_biased("rest", 2), vec!["restore", "restaurant", "resist", "rust"]);
+    /// ```
+    pub fn suggest_prefix_biased(&self, word: &str, max_distance: usize) -> Vec<&str> {
+        let mut candidates = self.suggest_explained(word, max_distance);
+        let word_length = word.chars().count();
+        if !word.is_empty() {
+            let added = self.user_dictionary.iter().map(|word| (word, word.chars().count()));
+            for (candidate, length) in self.dictionary.iter_with_lengths().chain(added) {
+                // a completion's distance is just the characters added
+                if length > word_length + max_distance && candidate.starts_with(word) {
+                    candidates.push(Candidate {
+                        word: candidate,
+                        breakdown: ScoreBreakdown::from_distance(length - word_length),
+                    });
+                }
+            }
+        }
+        // stable, so each group keeps its ranking
+        candidates.sort_by_key(|candidate| {
+            let prefix = common_prefix_len(word, candidate.word);
+            let completion_distance = if prefix == word_length { candidate.breakdown.distance } else { 0 };
+            (std::cmp::Reverse(prefix), completion_distance)
+        });
+        candidates.into_iter().map(|candidate| candidate.word).collect()
+    }
+
+    /// Returns up to `k` words, from the dictionary or added, that start with
+    /// something within `max_distance` Levenshtein edits of `prefix`, for
+    /// search as you type. They're sorted by how close their closest prefix
+    /// is, then by frequency, then in dictionary order.
+    ///
+    /// The first call builds a [`Trie`] of the words, which is kept until
+    /// words are added or removed, so only the branches that can still match
+    /// are visited after that.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::with_frequencies(vec![("restore", 5), ("restaurant", 20), ("rust", 90)]);
+    /// assert_eq!(checker.complete("rest", 0, 10), vec!["restaurant", "restore"]);
+    /// assert_eq!(checker.complete("resy", 1, 2), vec!["restaurant", "restore"]);
+    /// assert_eq!(checker.complete("rsut", 2, 10), vec!["rust", "restaurant", "restore"]);
+    /// ```
+    pub fn complete(&self, prefix: &str, max_distance: usize, k: usize) -> Vec<&str> {
+        let trie = self.trie.get_or_init(|| {
+            let mut trie = Trie::from_dictionary(&self.dictionary);
+            for word in self.user_dictionary.iter() {
+                trie.insert(word);
+            }
+            trie
+        });
+        let mut found = trie.completions(prefix, max_distance);
+        found.sort_unstable_by_key(|&(distance, index)| {
+            (distance, std::cmp::Reverse(self.frequency(trie.word(index))), index)
+        });
+        found.truncate(k);
+        found.into_iter().map(|(_, index)| trie.word(index)).collect()
+    }
+
+    /// Splits run-together words in `text` into dictionary words, like
+    /// "helloworld" into "hello" and "world". Each run of non-whitespace is
+    /// split the most likely way: a word's chance is its frequency, plus one
+    /// so words without one still count, and anything not in the dictionary
+    /// is very unlikely, more so the longer it is. Without frequencies that
+    /// means the fewest words.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::with_frequencies(vec![("hello", 50), ("hell", 5), ("low", 20), ("world", 40), ("or", 30)]);
+    /// assert_eq!(checker.segment("helloworld"), vec!["hello", "world"]);
+    /// assert_eq!(checker.segment("hellxworld  or"), vec!["hell", "x", "world", "or"]);
+    /// ```
+    pub fn segment<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        let mut segments = Vec::new();
+        for run in text.split_whitespace() {
+            // the char boundaries, and for each the cheapest way to get there
+            let ends: Vec<usize> = run.char_indices().map(|(at, _)| at).chain(Some(run.len())).collect();
+            let mut best: Vec<(f64, usize)> = vec![(f64::INFINITY, 0); ends.len()];
+            best[0].0 = 0.0;
+            for end in 1..ends.len() {
+                for start in end.saturating_sub(self.max_word_length.max(1))..end {
+                    let cost = best[start].0 + self.segment_cost(&run[ends[start]..ends[end]]);
+                    if cost < best[end].0 {
+                        best[end] = (cost, start);
+                    }
+                }
+            }
+            let first = segments.len();
+            let mut end = ends.len() - 1;
+            while end > 0 {
+                let start = best[end].1;
+                segments.push(&run[ends[start]..ends[end]]);
+                end = start;
+            }
+            segments[first..].reverse();
+        }
+        segments
+    }
+
+    /// The negative log chance of `word` for [`segment`](SpellChecker::segment).
+    fn segment_cost(&self, word: &str) -> f64 {
+        let words = self.words.len() + self.user_dictionary.len();
+        let total = (self.total_frequency as f64 + words as f64).max(1.0).ln();
+        if self.is_known(word) || self.ignored.contains(word) {
+            total - (self.frequency(word) as f64 + 1.0).ln()
+        } else {
+            total + word.chars().count() as f64 * 10f64.ln()
+        }
+    }
+
+    /// The likeliest way to split `word` into two suggestable words, see
+    /// [`segment`](SpellChecker::segment). Ties go to the shorter first word.
+    #[cfg(feature = "tokenize")]
+    pub(crate) fn split_in_two(&self, word: &str) -> Option<(&str, &str)> {
+        let get = |part: &str| {
+            self.words
+                .get(part)
+                .map(String::as_str)
+                .or_else(|| self.user_dictionary.get(part))
+        };
+        let mut best: Option<(f64, (&str, &str))> = None;
+        for (at, _) in word.char_indices().skip(1) {
+            let (first, second) = word.split_at(at);
+            if let (Some(first), Some(second)) = (get(first), get(second)) {
+                let cost = self.segment_cost(first) + self.segment_cost(second);
+                if best.is_none_or(|(best, _)| cost < best) {
+                    best = Some((cost, (first, second)));
+                }
+            }
+        }
+        best.map(|(_, split)| split)
+    }
+
+    /// Like [`suggest`](SpellChecker::suggest) but returns each word's
+    /// distance, frequency and score along with it.
+    /// ```
+    /// use spelling::{RankingStrategy, SpellChecker};
+    /// let checker = SpellChecker::with_frequencies(vec![("thin", 10), ("thing", 200)])
+    ///     .ranking(RankingStrategy::Frequency);
+    /// let suggestions = checker.suggest_ranked("thinb", 1);
+    /// assert_eq!(suggestions[0].word, "thing");
+    /// assert_eq!((suggestions[0].distance, suggestions[0].frequency), (1, 200));
+    /// assert!(suggestions[0].score < suggestions[1].score);
+    /// ```
+    pub fn suggest_ranked(&self, word: &str, distance: usize) -> Vec<Suggestion<'_>> {
+        self.suggest_explained(word, distance)
+            .into_iter()
+            .map(|candidate| Suggestion {
+                word: candidate.word,
+                distance: candidate.breakdown.distance,
+                frequency: self.frequency(candidate.word),
+                score: candidate.breakdown.score(),
+            })
+            .collect()
+    }
+
+    /// Like [`suggest`](SpellChecker::suggest) but with the
+    /// [`ScoreBreakdown`] of every suggestion.
+    pub fn suggest_explained(&self, word: &str, distance: usize) -> Vec<Candidate<'_>> {
+        let mut out = Vec::new();
+        self.suggest_explained_into(word, distance, &mut out);
+        out
+    }
+
+    /// Like [`suggest_explained`](SpellChecker::suggest_explained) but writes
+    /// into `out`, replacing what was there. Reusing one buffer for every
+    /// word of a large document saves an allocation per word.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("thin\nthing");
+    /// let mut buffer = Vec::new();
+    /// for word in &["thinga", "thinng"] {
+    ///     checker.suggest_explained_into(word, 1, &mut buffer);
+    ///     assert_eq!(buffer[0].word, "thing");
+    /// }
+    /// ```
+    pub fn suggest_explained_into<'a>(
+        &'a self,
+        word: &str,
+        distance: usize,
+        out: &mut Vec<Candidate<'a>>,
+    ) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("suggest", word, distance).entered();
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+
+        out.clear();
+        let scanned = self.find_candidates(word, distance, out);
+        self.metrics.0.query(scanned, out.len());
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            scanned,
+            suggestions = out.len(),
+            elapsed = ?start.elapsed(),
+            "suggest finished"
+        );
+    }
+
+    /// Appends the suggestions to the empty `candidates` and returns how many
+    /// dictionary words had their distance measured.
+    fn find_candidates<'a>(
+        &'a self,
+        word: &str,
+        distance: usize,
+        candidates: &mut Vec<Candidate<'a>>,
+    ) -> usize {
+        let stripped: String;
+        let word = match self.invisible_characters {
+            InvisibleCharacters::Keep => word,
+            InvisibleCharacters::Strip if word.chars().any(is_invisible) => {
+                stripped = word.chars().filter(|&c| !is_invisible(c)).collect();
+                &stripped
+            }
+            InvisibleCharacters::Strip => word,
+            InvisibleCharacters::Reject if word.chars().any(is_invisible) => return 0,
+            InvisibleCharacters::Reject => word,
+        };
+        if word.chars().nth(self.max_word_length).is_some() {
+            return 0;
+        }
+        // folding maps characters one to one, so lengths bound it like they
+        // do the Levenshtein distance
+        let word_length = word.chars().count();
+        let lower_bound = |length: usize| match self.distance_mode {
+            DistanceMode::Strict => self.metric.length_lower_bound(word_length, length),
+            DistanceMode::CaseAndAccentTolerant => word_length.max(length) - word_length.min(length),
+        };
+        let fold = |c: char| match self.distance_mode {
+            DistanceMode::Strict => c,
+            DistanceMode::CaseAndAccentTolerant => fold_char(c),
+        };
+        let first = word.chars().next().map(fold);
+        let same_first = |candidate: &str| candidate.chars().next().map(fold) == first;
+        let generate = |restrict: bool, candidates: &mut Vec<Candidate<'a>>| {
+            // added words that are in the dictionary anyway aren't suggested
+            // twice
+            let added = self
+                .user_dictionary
+                .iter()
+                .filter(|word| !self.words.contains(*word))
+                .map(|word| (word, word.chars().count()));
+            let mut scanned = 0;
+            {
+                let mut words = self
+                    .dictionary
+                    .iter_with_lengths()
+                    .chain(added)
+                    .filter(|&(_, length)| lower_bound(length) <= distance)
+                    .map(|(word, _)| word)
+                    .filter(|candidate| !restrict || same_first(candidate))
+                    .inspect(|_| scanned += 1);
+                match self.distance_mode {
+                    DistanceMode::Strict => MetricGenerator(&self.metric)
+                        .generate_into(&mut words, word, distance, candidates),
+                    DistanceMode::CaseAndAccentTolerant => FoldedLevenshteinGenerator
+                        .generate_into(&mut words, word, distance, candidates),
+                }
+            }
+            scanned
+        };
+        let scanned = match self.first_character {
+            FirstCharacter::Restrict { min_suggestions } => {
+                let mut scanned = generate(true, candidates);
+                if candidates.len() < min_suggestions {
+                    candidates.clear();
+                    scanned += generate(false, candidates);
+                }
+                scanned
+            }
+            FirstCharacter::Ignore | FirstCharacter::Prefer => generate(false, candidates),
+        };
+        let min_length = MinLength(self.min_candidate_length);
+        candidates.retain(|candidate| min_length.keep(word, candidate));
+        // candidates come in dictionary order and the rankers below sort
+        // stably, so putting them in tie-break order first is enough
+        match self.sort_policy {
+            SortPolicy::DictionaryOrder => {}
+            SortPolicy::Lexicographic => candidates.sort_by(|a, b| a.word.cmp(b.word)),
+            SortPolicy::Frequency => {
+                candidates.sort_by_key(|candidate| std::cmp::Reverse(self.frequency(candidate.word)))
+            }
+        }
+        match &self.ranking {
+            RankingStrategy::DistanceOnly => {}
+            RankingStrategy::Frequency => {
+                // kept below 1 so it only ever breaks ties between distances
+                let max = self.frequencies.values().copied().max().unwrap_or(0) as f64 + 1.0;
+                for candidate in candidates.iter_mut() {
+                    candidate.breakdown.frequency_weight =
+                        Some(self.frequency(candidate.word) as f64 / max);
+                }
+            }
+            RankingStrategy::NoisyChannel => {
+                // log probabilities, scaled so one edit costs 1
+                let total = (self.total_frequency + self.frequencies.len() as u64 + 1) as f64;
+                let edit_cost = -NOISY_CHANNEL_EDIT_PROBABILITY.ln();
+                for candidate in candidates.iter_mut() {
+                    let probability = (self.frequency(candidate.word) + 1) as f64 / total;
+                    candidate.breakdown.frequency_weight = Some(probability.ln() / edit_cost);
+                }
+            }
+            RankingStrategy::Scored(scorer) => {
+                let max = self.frequencies.values().copied().max().unwrap_or(0);
+                for candidate in candidates.iter_mut() {
+                    candidate.breakdown.weighted_score = Some(scorer.score(
+                        word,
+                        candidate.word,
+                        candidate.breakdown.distance,
+                        self.frequency(candidate.word),
+                        max,
+                    ));
+                }
+            }
+            RankingStrategy::Custom(ranker) => ranker.rank(word, candidates),
+        }
+        if !matches!(self.ranking, RankingStrategy::Custom(_)) {
+            ScoreRanker.rank(word, candidates);
+        }
+        if self.first_character == FirstCharacter::Prefer {
+            // stable, so each group keeps its ranking
+            candidates.sort_by_key(|candidate| !same_first(candidate.word));
+        }
+        scanned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+    use test::Bencher;
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn actual_dict() {
+        let dictionary_string = include_str!("words.txt");
+        let thing = crate::spellcheck_rayon(dictionary_string, "restaraunt", 3);
+        assert_eq!("restaurant", thing[0]);
+    }
+
+    #[bench]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn bench_actual_dict(bench: &mut Bencher) {
+        let dictionary_string = include_str!("words.txt");
+        bench.iter(|| crate::spellcheck_rayon(dictionary_string, "restaraunt", 3))
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn fake_dict() {
+        let string = "\
+thin
+thing
+";
+        assert_eq!(
+            crate::spellcheck_rayon(string, "thinga", 3),
+            vec!["thing", "thin"]
+        )
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn rayon_iter() {
+        let dictionary = crate::Dictionary::from_newline_separated("thin\nthing\nthings\nthinga");
+        for word in &["thinga", "thnig", "", "x"] {
+            for distance in 0..3 {
+                assert_eq!(
+                    crate::spellcheck_rayon_iter(dictionary.iter(), word, distance),
+                    crate::spellcheck_iter(dictionary.iter(), word, distance)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn rayon_with_options() {
+        use crate::ParallelOptions;
+        let string = "hint\nthin\nthing\nthings\nthinga";
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let expected = crate::spellcheck(string, "thinga", 2);
+        for min_chunk in 0..6 {
+            let options = ParallelOptions::new().thread_pool(&pool).min_chunk(min_chunk);
+            assert_eq!(crate::spellcheck_rayon_with_options(string, "thinga", 2, &options), expected);
+            let mut options = options;
+            options.set_thread_pool(None);
+            assert_eq!(crate::spellcheck_rayon_with_options(string, "thinga", 2, &options), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn rayon_top_k() {
+        let string = "hint\nthin\nthing\nthings";
+        let mut all = crate::spellcheck_rayon_top_k(string, "thinga", 2, 10, 0);
+        all.sort_unstable();
+        assert_eq!(all, vec!["thin", "thing", "things"]);
+        assert_eq!(crate::spellcheck_rayon_top_k(string, "thing", 2, 1, 0), vec!["thing"]);
+        assert!(crate::spellcheck_rayon_top_k(string, "thing", 2, 0, 0).is_empty());
+
+        let dictionary_string = include_str!("words.txt");
+        for word in crate::spellcheck_rayon_top_k(dictionary_string, "restaraunt", 3, 2, 1) {
+            assert!(crate::levenshtein_distance("restaraunt", word) <= 3);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn rayon_streaming() {
+        let string = "thin\nthing\nhint\nthings";
+        let (sender, receiver) = std::sync::mpsc::channel();
+        crate::spellcheck_rayon_streaming(string, "thinga", 2, sender);
+        let mut streamed: Vec<(&str, usize)> = receiver.into_iter().collect();
+        streamed.sort_unstable();
+        assert_eq!(streamed, vec![("thin", 2), ("thing", 1), ("things", 1)]);
+
+        // a dropped receiver stops the scan instead of erroring
+        let (sender, receiver) = std::sync::mpsc::channel();
+        drop(receiver);
+        crate::spellcheck_rayon_streaming(string, "thinga", 2, sender);
+    }
+
+    #[test]
+    fn no_rayon_actual_dict() {
+        let dictionary_string = include_str!("words.txt");
+        let thing = crate::spellcheck(dictionary_string, "restaraunt", 3);
+        assert_eq!("restaurant", thing[0]);
+    }
+
+    #[bench]
+    fn no_rayon_bench_actual_dict(bench: &mut Bencher) {
+        let dictionary_string = include_str!("words.txt");
+        bench.iter(|| crate::spellcheck(dictionary_string, "restaraunt", 3))
+    }
+
+    #[bench]
+    fn length_bucketed_bench_actual_dict(bench: &mut Bencher) {
+        let dictionary: crate::LengthBucketedDictionary = include_str!("words.txt").lines().collect();
+        bench.iter(|| dictionary.suggest("restaraunt", 3))
+    }
+
+    #[bench]
+    fn dawg_bench_actual_dict(bench: &mut Bencher) {
+        let dictionary: crate::DawgDictionary = include_str!("words.txt").lines().collect();
+        bench.iter(|| dictionary.suggest("restaraunt", 3))
+    }
+
+    #[test]
+    fn huge_distance_is_no_limit() {
+        let string = "thin\nthing\na\n";
+        assert_eq!(crate::spellcheck(string, "thin", usize::MAX), vec!["thin", "thing", "a", ""]);
+        let checker = crate::SpellChecker::new(string);
+        assert_eq!(checker.suggest("thin", usize::MAX), vec!["thin", "thing", "a"]);
+    }
+
+    #[test]
+    fn no_rayon_fake_dict() {
+        let string = "\
+thin
+thing
+";
+        assert_eq!(
+            crate::spellcheck(string, "thinga", 3),
+            vec!["thing", "thin"]
+        )
+    }
+
+    #[test]
+    fn explained_fake_dict() {
+        let string = "\
+thin
+thing
+";
+        let explained = crate::spellcheck_explained(string, "thinga", 3);
+        assert_eq!(
+            explained.iter().map(|x| x.word).collect::<Vec<_>>(),
+            crate::spellcheck(string, "thinga", 3)
+        );
+        assert_eq!(explained[0].breakdown, crate::ScoreBreakdown::from_distance(1));
+        assert_eq!(explained[1].breakdown.distance, 2);
+        assert_eq!(explained[1].breakdown.score(), 2.0);
+    }
+
+    #[test]
+    fn custom_pipeline() {
+        let string = "\
+thin
+thing
+things
+";
+        let pipeline = crate::Pipeline::builder()
+            .filter(|_: &str, candidate: &crate::Candidate| candidate.word != "thing")
+            .ranker(|_: &str, candidates: &mut [crate::Candidate]| {
+                candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.word.len()))
+            })
+            .limit(1)
+            .build();
+        let out = pipeline.run(string.split('\n'), "thinga", 3);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].word, "things");
+    }
+
+    fn ranking_checker() -> crate::SpellChecker {
+        crate::SpellChecker::with_frequencies(vec![("thin", 1), ("thing", 100), ("things", 100_000)])
+    }
+
+    #[test]
+    fn ranking_distance_only() {
+        let checker = ranking_checker().ranking(crate::RankingStrategy::DistanceOnly);
+        assert_eq!(checker.suggest("thinb", 2), vec!["thin", "thing", "things"]);
+    }
+
+    #[test]
+    fn ranking_frequency() {
+        let checker = ranking_checker().ranking(crate::RankingStrategy::Frequency);
+        assert_eq!(checker.suggest("thinb", 2), vec!["thing", "thin", "things"]);
+    }
+
+    #[test]
+    fn ranking_noisy_channel() {
+        let checker = ranking_checker().ranking(crate::RankingStrategy::NoisyChannel);
+        assert_eq!(checker.suggest("thinb", 2), vec!["things", "thing", "thin"]);
+    }
+
+    #[test]
+    fn ranking_custom() {
+        let checker = ranking_checker().ranking(crate::RankingStrategy::Custom(Box::new(
+            |_: &str, candidates: &mut [crate::Candidate]| candidates.reverse(),
+        )));
+        assert_eq!(checker.suggest("thinb", 2), vec!["things", "thing", "thin"]);
+    }
+
+    #[test]
+    fn case_and_accent_tolerant() {
+        let checker = crate::SpellChecker::new("schroedinger\nSchrödinger")
+            .distance_mode(crate::DistanceMode::CaseAndAccentTolerant);
+        assert_eq!(checker.suggest("schrodinger", 1), vec!["Schrödinger", "schroedinger"]);
+        let explained = checker.suggest_explained("schrodinger", 1);
+        assert_eq!(explained[0].breakdown.distance, 0);
+        assert_eq!(explained[0].breakdown.minor_edits, 2);
+    }
+
+    #[test]
+    fn spellcheck_max_distance_edge_cases() {
+        assert_eq!(crate::spellcheck("thin\nthing\nthinga", "thing", 0), vec!["thing"]);
+        assert!(crate::spellcheck("thin\nthinga", "thing", 0).is_empty());
+        assert_eq!(crate::spellcheck("thin\nthing", "thinga", usize::MAX), vec!["thing", "thin"]);
+        #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+        assert_eq!(crate::spellcheck_rayon("thin\nthing", "thinga", usize::MAX), vec!["thing", "thin"]);
+    }
+
+    #[test]
+    fn spellcheck_ranked() {
+        fn words<'a>(suggestions: Vec<crate::Suggestion<'a>>) -> Vec<&'a str> {
+            suggestions.iter().map(|suggestion| suggestion.word).collect()
+        }
+        let dictionary = crate::FrequencyDictionary::from_tab_separated("thin\t7\nthings\t7\nthing\t7\nthinga\t1");
+        assert_eq!(words(crate::spellcheck_ranked(&dictionary, "thinga", 2)), vec!["thinga", "things", "thing", "thin"]);
+        let dictionary: crate::FrequencyDictionary = vec![("thing", 1), ("things", 2)].into_iter().collect();
+        let suggestions = crate::spellcheck_ranked(&dictionary, "thingz", 1);
+        assert_eq!(words(suggestions.clone()), vec!["things", "thing"]);
+        assert_eq!(suggestions[1].frequency, 1);
+        assert!(suggestions[0].score < suggestions[1].score && suggestions[1].score < 1.0);
+    }
+
+    #[test]
+    fn spellcheck_top_k() {
+        let dictionary_string = "thin\nthinga\nthings\nthing\nthinga";
+        assert_eq!(crate::spellcheck_top_k(dictionary_string, "thinga", 2, 3), vec!["thinga", "thinga", "things"]);
+        assert_eq!(crate::spellcheck_top_k(dictionary_string, "thin", 0, 3), vec!["thin"]);
+        assert!(crate::spellcheck_top_k(dictionary_string, "thinga", 2, 0).is_empty());
+    }
+
+    #[test]
+    fn spellcheck_with_options() {
+        use crate::CheckOptions;
+        let dictionary_string = "ORANGE\nOrange\noranges\nΣΟΦΙΑ";
+        assert!(crate::spellcheck_with_options(dictionary_string, "orange", 0, &CheckOptions::new()).is_empty());
+        let options = CheckOptions::new().case_insensitive(true);
+        assert_eq!(
+            crate::spellcheck_with_options(dictionary_string, "orange", 1, &options),
+            vec!["ORANGE", "Orange", "oranges"]
+        );
+        assert_eq!(crate::spellcheck_with_options(dictionary_string, "σοφια", 0, &options), vec!["ΣΟΦΙΑ"]);
+        assert_eq!(
+            crate::spellcheck_with_options(dictionary_string, "orange", 1, &CheckOptions::default()),
+            crate::spellcheck(dictionary_string, "orange", 1)
+        );
+    }
+
+    #[test]
+    fn spellcheck_strip_diacritics() {
+        use crate::CheckOptions;
+        let dictionary_string = "crème brûlée\nCrème brûlée\nnaïve";
+        let options = CheckOptions::new().strip_diacritics(true);
+        assert_eq!(crate::spellcheck_with_options(dictionary_string, "creme brulee", 0, &options), vec!["crème brûlée"]);
+        assert_eq!(crate::spellcheck_with_options(dictionary_string, "nai\u{308}ve", 0, &options), vec!["naïve"]);
+        let options = options.case_insensitive(true);
+        assert_eq!(
+            crate::spellcheck_with_options(dictionary_string, "CREME BRULEE", 0, &options),
+            vec!["crème brûlée", "Crème brûlée"]
+        );
+        assert!(crate::spellcheck_with_options(dictionary_string, "creme brulee", 2, &CheckOptions::new()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn spellcheck_with_normalization() {
+        use crate::{CheckOptions, Normalization};
+        let dictionary_string = "caf\u{e9}\ncafe\u{301}s";
+        assert_eq!(
+            crate::spellcheck_with_options(dictionary_string, "cafe\u{301}", 1, &CheckOptions::new()),
+            vec!["cafe\u{301}s"]
+        );
+        let options = CheckOptions::new().normalization(Normalization::Nfc);
+        assert_eq!(
+            crate::spellcheck_with_options(dictionary_string, "cafe\u{301}", 1, &options),
+            vec!["caf\u{e9}", "cafe\u{301}s"]
+        );
+        let options = options.case_insensitive(true);
+        assert_eq!(crate::spellcheck_with_options(dictionary_string, "CAF\u{c9}", 0, &options), vec!["caf\u{e9}"]);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn spellcheck_with_graphemes() {
+        use crate::CheckOptions;
+        let dictionary_string = "\u{1f44d}\u{1f3fd}\nthumbs";
+        assert!(crate::spellcheck_with_options(dictionary_string, "", 1, &CheckOptions::new()).is_empty());
+        let options = CheckOptions::new().graphemes(true);
+        assert_eq!(crate::spellcheck_with_options(dictionary_string, "", 1, &options), vec!["\u{1f44d}\u{1f3fd}"]);
+        assert_eq!(
+            crate::spellcheck_with_metric(dictionary_string, "\u{1f44d}", 1, &crate::GraphemeLevenshtein),
+            vec!["\u{1f44d}\u{1f3fd}"]
+        );
+    }
+
+    #[test]
+    fn spellcheck_with_distances() {
+        let dictionary_string = "thin\nthings\nthing\nthinga";
+        let expected = vec![("thinga", 0), ("things", 1), ("thing", 1), ("thin", 2)];
+        assert_eq!(crate::spellcheck_with_distances(dictionary_string, "thinga", 2), expected);
+        #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+        assert_eq!(crate::spellcheck_rayon_with_distances(dictionary_string, "thinga", 2), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled_words")]
+    fn bundled_words() {
+        let checker = crate::SpellChecker::from_dictionary(crate::Dictionary::bundled());
+        assert_eq!(checker.suggest("restaraunt", 3)[0], "restaurant");
+    }
+
+    #[test]
+    fn dictionary_line_endings() {
+        let dictionary = crate::Dictionary::from_newline_separated("thin\r\nthing\r\n\n \tthings\t\n");
+        assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec!["thin", "thing", "things"]);
+        assert_eq!(dictionary.len(), 3);
+        let checker = crate::SpellChecker::from_dictionary(dictionary);
+        assert_eq!(checker.suggest("thing", 0), vec!["thing"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn serial_and_rayon_order() {
+        let string = "\
+thing
+thin
+things
+thong
+thing
+héllo
+";
+        for word in &["thing", "thinga", "hello", "t", ""] {
+            for distance in 0..4 {
+                assert_eq!(
+                    crate::spellcheck(string, word, distance),
+                    crate::spellcheck_rayon(string, word, distance)
+                );
+            }
+        }
+        assert_eq!(
+            crate::spellcheck(string, "thing", 1),
+            vec!["thing", "thing", "thin", "things", "thong"]
+        );
+    }
+
+    #[test]
+    fn exact_match() {
+        // the distance used to be stored less one, which underflowed here
+        assert_eq!(crate::spellcheck("thin\nthing", "thin", 0), vec!["thin"]);
+    }
+
+    #[test]
+    fn public_api_does_not_panic() {
+        let inputs = [
+            "",
+            "\n",
+            "\r\n",
+            "\u{feff}",
+            "a",
+            "thin",
+            "e\u{301}",
+            "\u{e9}",
+            "🦀🦀",
+            "\u{0}\u{1}",
+            "ǅ",
+            "İ",
+            "ﬃ",
+        ];
+        let dictionary_string = inputs.join("\n");
+        let checker = crate::SpellChecker::new(&dictionary_string);
+        let folded = crate::SpellChecker::new(&dictionary_string)
+            .distance_mode(crate::DistanceMode::CaseAndAccentTolerant)
+            .ranking(crate::RankingStrategy::NoisyChannel);
+        for a in &inputs {
+            for b in &inputs {
+                for &max in &[0, 1, 2, usize::MAX / 2, usize::MAX] {
+                    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        crate::levenshtein_distance(a, b);
+                        crate::levenshtein_distance_bounded(a, b, max);
+                        crate::levenshtein_distance_folded(a, b);
+                        crate::spellcheck(&dictionary_string, a, max);
+                        crate::spellcheck_explained(&dictionary_string, a, max);
+                        #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+                        crate::spellcheck_rayon(&dictionary_string, a, max);
+                        checker.suggest(a, max);
+                        folded.suggest_explained(a, max);
+                    }));
+                    assert!(result.is_ok(), "{:?} {:?} {}", a, b, max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn max_word_length() {
+        let long = "a".repeat(crate::DEFAULT_MAX_WORD_LENGTH);
+        let checker = crate::SpellChecker::new(&long);
+        assert_eq!(checker.suggest(&long, 0), vec![long.as_str()]);
+        assert!(checker.suggest(&format!("{}a", long), 1).is_empty());
+        let checker = checker.max_word_length(usize::MAX);
+        assert_eq!(checker.suggest(&format!("{}a", long), 1), vec![long.as_str()]);
+    }
+
+    #[test]
+    fn invisible_characters() {
+        use crate::InvisibleCharacters;
+        let mut checker = crate::SpellChecker::new("thing\nthin");
+        for word in &["th\u{200d}ing", "\u{feff}thing", "thing\u{0}", "thi\u{ad}ng\r"] {
+            checker.set_invisible_characters(InvisibleCharacters::Strip);
+            assert_eq!(checker.suggest(word, 0), vec!["thing"]);
+            checker.set_invisible_characters(InvisibleCharacters::Reject);
+            assert!(checker.suggest(word, 2).is_empty());
+            checker.set_invisible_characters(InvisibleCharacters::Keep);
+            assert!(checker.suggest(word, 0).is_empty());
+            assert_eq!(checker.suggest(word, 2)[0], "thing");
+        }
+    }
+
+    #[test]
+    fn check() {
+        use crate::{CheckResult, DistanceMode};
+        let counters = std::sync::Arc::new(crate::MetricsCounters::default());
+        let checker = crate::SpellChecker::with_frequencies(vec![("thin", 1), ("Thing", 2)])
+            .metrics(counters.clone());
+        assert!(checker.contains("Thing"));
+        assert!(!checker.contains("thing"));
+        assert_eq!(checker.check("thin", 1), CheckResult::Correct);
+        assert_eq!(counters.queries(), 0);
+        assert_eq!(checker.check("thin\u{200b}", 1), CheckResult::Correct);
+        assert_eq!(checker.check("thing", 1), CheckResult::Misspelled { suggestions: vec!["thin", "Thing"] });
+        assert_eq!(checker.check("qqqq", 1), CheckResult::Misspelled { suggestions: vec![] });
+        let checker = checker.distance_mode(DistanceMode::CaseAndAccentTolerant);
+        assert_eq!(checker.check("thing", 0), CheckResult::Correct);
+    }
+
+    #[test]
+    fn user_dictionary() {
+        use crate::CheckResult;
+        let mut checker = crate::SpellChecker::new("thin\nthing");
+        assert!(checker.add_word("thinks"));
+        // already in the dictionary, but not suggested twice
+        assert!(checker.add_word("thing"));
+        assert_eq!(checker.suggest("think", 1), vec!["thin", "thing", "thinks"]);
+        assert_eq!(checker.check("thinks", 1), CheckResult::Correct);
+        assert!(!checker.remove_word("thin"));
+        assert!(checker.contains("thin"));
+        assert!(checker.remove_word("thinks"));
+        assert_eq!(checker.check("thinks", 0), CheckResult::Misspelled { suggestions: vec![] });
+        assert_eq!(checker.user_dictionary().iter().collect::<Vec<_>>(), vec!["thing"]);
+    }
+
+    #[test]
+    fn ignore() {
+        let mut checker = crate::SpellChecker::new("thin\nthing");
+        assert!(checker.ignore("thinx"));
+        assert!(!checker.ignore("thinx"));
+        checker.ignore_all(vec![String::from("serde"), String::from("thin")]);
+        assert!(checker.contains("thinx"));
+        assert_eq!(checker.suggest("thinxx", 1), Vec::<&str>::new());
+        assert_eq!(checker.ignored(), vec!["serde", "thin", "thinx"]);
+        assert!(checker.unignore("thin"));
+        // still in the dictionary
+        assert!(checker.contains("thin"));
+        checker.clear_ignored();
+        assert!(!checker.contains("thinx"));
+        assert!(checker.ignored().is_empty());
+    }
+
+    #[test]
+    fn builder() {
+        use crate::{Dictionary, UserDictionary};
+        let checker = crate::SpellChecker::builder()
+            .add_dictionary(Dictionary::from_newline_separated("thin\nthing"), 1)
+            .add_dictionary(Dictionary::from_newline_separated("thins\nthing"), 5)
+            .add_dictionary(Dictionary::from_newline_separated("thine"), 1)
+            .user_dictionary(UserDictionary::from_newline_separated("thinx"))
+            .build();
+        assert_eq!(checker.dictionary().iter().collect::<Vec<_>>(), vec!["thins", "thing", "thin", "thine"]);
+        assert_eq!(checker.suggest("thinz", 1), vec!["thins", "thing", "thin", "thine", "thinx"]);
+        assert!(checker.contains("thinx"));
+        assert!(crate::SpellChecker::builder().build().dictionary().is_empty());
+    }
+
+    #[test]
+    fn scored_ranking() {
+        use crate::{RankingStrategy, Scorer, Soundex};
+        let words = vec![("city", 1), ("site", 1), ("sits", 1000)];
+        let scored = |scorer: Scorer| {
+            let checker = crate::SpellChecker::with_frequencies(words.clone()).ranking(RankingStrategy::Scored(scorer));
+            checker.suggest("sity", 1).into_iter().map(str::to_owned).collect::<Vec<_>>()
+        };
+        assert_eq!(crate::SpellChecker::with_frequencies(words.clone()).suggest("sity", 1), vec!["city", "site", "sits"]);
+        let distance_only = Scorer::new().phonetic_weight(0.0).frequency_weight(0.0);
+        assert_eq!(scored(distance_only), vec!["city", "site", "sits"]);
+        assert_eq!(scored(Scorer::new().phonetic(Soundex).frequency_weight(0.0)), vec!["site", "city", "sits"]);
+        assert_eq!(scored(Scorer::new().phonetic_weight(0.0).frequency_weight(1.0)), vec!["sits", "city", "site"]);
+        let checker = crate::SpellChecker::with_frequencies(vec![("cite", 0)])
+            .ranking(RankingStrategy::Scored(Scorer::default()));
+        let ranked = checker.suggest_ranked("cite", 0);
+        assert_eq!((ranked[0].distance, ranked[0].score), (0, -0.25));
+    }
+
+    #[test]
+    fn prefix_biased() {
+        let mut checker = crate::SpellChecker::new("cart\ncat\ncatalogue\ncatapult\ncoat\ncatalog");
+        checker.add_word("catamaran");
+        assert_eq!(
+            checker.suggest_prefix_biased("cat", 1),
+            vec!["cat", "catalog", "catapult", "catalogue", "catamaran", "cart", "coat"]
+        );
+        assert_eq!(checker.suggest_prefix_biased("catl", 2), vec!["cat", "cart", "coat"]);
+        assert_eq!(checker.suggest_prefix_biased("", 3), vec!["cat"]);
+    }
+
+    #[test]
+    fn segment() {
+        let checker = crate::SpellChecker::new("a\nan\ncat\ncats\nnaïve\nscat");
+        assert_eq!(checker.segment("acat"), vec!["a", "cat"]);
+        // fewest words without frequencies
+        assert_eq!(checker.segment("catsan"), vec!["cats", "an"]);
+        assert_eq!(checker.segment(" anaïvecat\tzzz "), vec!["a", "naïve", "cat", "zzz"]);
+        assert!(checker.segment("").is_empty());
+        assert!(crate::SpellChecker::new("").segment("").is_empty());
+        assert_eq!(crate::SpellChecker::new("").segment("xyz"), vec!["xyz"]);
+    }
+
+    #[test]
+    fn compounds() {
+        use crate::Compounds;
+        let mut checker = crate::SpellChecker::new("foot\nball\nplayer\nfootball\nkit\nwerk");
+        assert!(!checker.contains("footballplayer"));
+        checker.set_compounds(Some(Compounds::new()));
+        assert!(checker.contains("footballplayer"));
+        assert!(checker.contains("footballkit"));
+        assert!(!checker.contains("footballkits"));
+        // "ball" alone isn't a compound, and parts are at least 3 chars
+        assert!(!checker.contains("balls"));
+        assert!(!checker.contains("kitx"));
+        checker.set_compounds(Some(Compounds::new().min_part_length(5)));
+        assert!(!checker.contains("footballkit"));
+        assert!(checker.contains("footballplayer"));
+        // linking elements only go between parts
+        checker.set_compounds(Some(Compounds::dutch()));
+        assert!(checker.contains("footswerk"));
+        assert!(checker.contains("footenwerk"));
+        assert!(!checker.contains("footwerks"));
+        assert!(!checker.contains("sfootwerk"));
+        assert!(!checker.contains(""));
+        assert_eq!(Compounds::german().linking_element("s").linking_element("").linking_elements().len(), 7);
+        // compounds don't change how words are split up
+        assert_eq!(checker.segment("footballplayer"), vec!["football", "player"]);
+        checker.set_compounds(None);
+        assert!(!checker.contains("footballplayer"));
+    }
+
+    #[test]
+    fn correct() {
+        let mut checker = crate::SpellChecker::new("the\nthin\nthing\ncat\nbat\nnaïve");
+        checker.set_min_confidence(0.5);
+        assert_eq!(checker.correct("thinng"), "thing");
+        assert_eq!(checker.correct("THINNG"), "THING");
+        assert_eq!(checker.correct("Naive"), "Naïve");
+        assert!(matches!(checker.correct("Thinng"), std::borrow::Cow::Owned(_)));
+        assert!(matches!(checker.correct("thinng"), std::borrow::Cow::Borrowed(_)));
+        // "cat" and "bat" tie
+        assert_eq!(checker.correct("xat"), "xat");
+        assert_eq!(checker.correct("zzzzzz"), "zzzzzz");
+        assert_eq!(checker.correct(""), "");
+        checker.set_min_confidence(1.0);
+        assert_eq!(checker.correct("thinng"), "thinng");
+        checker.set_min_confidence(0.0);
+        assert_eq!(checker.correct("thq"), "the");
+    }
+
+    #[test]
+    fn complete() {
+        let mut checker = crate::SpellChecker::new("cart\ncat\ncatalogue\ncoat\ncatalog");
+        assert_eq!(checker.complete("cata", 0, 10), vec!["catalogue", "catalog"]);
+        assert_eq!(checker.complete("cata", 1, 10), vec!["catalogue", "catalog", "cat"]);
+        assert_eq!(checker.complete("cata", 2, 4), vec!["catalogue", "catalog", "cat", "cart"]);
+        checker.add_word("catamaran");
+        assert_eq!(checker.complete("catam", 0, 10), vec!["catamaran"]);
+        checker.remove_word("catamaran");
+        assert!(checker.complete("catam", 0, 10).is_empty());
+        assert_eq!(checker.complete("", 0, 2), vec!["cart", "cat"]);
+        assert!(checker.complete("cat", 3, 0).is_empty());
+    }
+
+    #[test]
+    fn sort_policy() {
+        use crate::SortPolicy;
+        let mut checker = crate::SpellChecker::with_frequencies(vec![("thins", 1), ("thing", 5), ("thin", 5), ("thi", 9)]);
+        assert_eq!(checker.suggest("thinx", 1), vec!["thins", "thing", "thin"]);
+        checker.set_sort_policy(SortPolicy::Frequency);
+        assert_eq!(checker.suggest("thinx", 1), vec!["thing", "thin", "thins"]);
+        checker.set_sort_policy(SortPolicy::Lexicographic);
+        assert_eq!(checker.suggest("thinx", 1), vec!["thin", "thing", "thins"]);
+        // only ties are reordered
+        assert_eq!(checker.suggest("thins", 2), vec!["thins", "thin", "thing", "thi"]);
+    }
+
+    #[test]
+    fn first_character() {
+        use crate::{DistanceMode, FirstCharacter, RankingStrategy};
+        let mut checker = crate::SpellChecker::new("Éclair\nclair\nflair")
+            .first_character(FirstCharacter::Restrict { min_suggestions: 1 });
+        assert_eq!(checker.suggest("flar", 2), vec!["flair"]);
+        assert_eq!(checker.suggest("eclair", 1), vec!["Éclair", "clair"]);
+        checker.set_first_character(FirstCharacter::Restrict { min_suggestions: 2 });
+        assert_eq!(checker.suggest("flar", 2), vec!["flair", "clair"]);
+        checker.set_distance_mode(DistanceMode::CaseAndAccentTolerant);
+        checker.set_first_character(FirstCharacter::Restrict { min_suggestions: 1 });
+        assert_eq!(checker.suggest("eclair", 1), vec!["Éclair"]);
+        checker.set_first_character(FirstCharacter::Prefer);
+        assert_eq!(checker.suggest("fclair", 1), vec!["flair", "Éclair", "clair"]);
+        assert!(checker.suggest("", 0).is_empty());
+
+        // a custom ranker's order is kept within each group
+        let reverse = |_: &str, candidates: &mut [crate::Candidate]| candidates.reverse();
+        checker.set_ranking(RankingStrategy::Custom(Box::new(reverse)));
+        assert_eq!(checker.suggest("fclair", 2), vec!["flair", "clair", "Éclair"]);
+    }
+
+    #[test]
+    fn metrics() {
+        use crate::{InvisibleCharacters, Metrics, MetricsCounters};
+        let counters = std::sync::Arc::new(MetricsCounters::default());
+        assert_eq!(counters.average_scanned(), 0.0);
+        let checker = crate::SpellChecker::new("thin\nthing\nhint")
+            .invisible_characters(InvisibleCharacters::Reject)
+            .metrics(counters.clone());
+        checker.suggest("thin", 1);
+        checker.suggest("th\u{200d}in", 1);
+        counters.cache_hit();
+        assert_eq!(counters.queries(), 3);
+        assert_eq!(counters.cache_hits(), 1);
+        assert_eq!(counters.average_scanned(), 1.0);
+
+        // words too long or short to be within the distance aren't scanned
+        let counters = std::sync::Arc::new(MetricsCounters::default());
+        let mut checker = crate::SpellChecker::new("a\nthin\nthing\nthinking")
+            .metrics(counters.clone());
+        checker.suggest("thin", 1);
+        assert_eq!(counters.average_scanned(), 2.0);
+        // and added words already in the dictionary are only scanned once
+        checker.add_word("thin");
+        checker.add_word("thins");
+        checker.suggest("thin", 1);
+        assert_eq!(counters.average_scanned(), 2.5);
+
+        // a restricted search that falls back counts both passes
+        let counters = std::sync::Arc::new(MetricsCounters::default());
+        let checker = crate::SpellChecker::new("Éclair\nclair\nflair")
+            .first_character(crate::FirstCharacter::Restrict { min_suggestions: 2 })
+            .metrics(counters.clone());
+        assert_eq!(checker.suggest("flar", 2), vec!["flair", "clair"]);
+        assert_eq!(counters.average_scanned(), 4.0);
+    }
+
+    #[test]
+    fn skip_short_candidates() {
+        let dictionary: crate::Dictionary = vec!["", "a", "ab", "abc"].into_iter().collect();
+        assert_eq!(dictionary.len(), 3);
+        let checker = crate::SpellChecker::from_dictionary(dictionary);
+        assert_eq!(checker.suggest("b", 2), vec!["a", "ab", "abc"]);
+        let checker = checker.min_candidate_length(2);
+        assert_eq!(checker.suggest("b", 2), vec!["ab", "abc"]);
+
+        let pipeline = crate::Pipeline::builder().filter(crate::MinLength(1)).build();
+        assert_eq!(pipeline.run("\nthin\n".split('\n'), "t", 3).len(), 1);
+    }
+
+    /// Counts differing bytes at the same position plus the length difference.
+    struct Positional;
+
+    impl crate::DistanceMetric for Positional {
+        fn distance(&self, a: &str, b: &str) -> usize {
+            let different = a.bytes().zip(b.bytes()).filter(|(a, b)| a != b).count();
+            different + a.len().max(b.len()) - a.len().min(b.len())
+        }
+    }
+
+    /// Levenshtein, counting how many times it's asked for a distance.
+    #[derive(Default)]
+    struct Counting(std::sync::atomic::AtomicUsize);
+
+    impl crate::DistanceMetric for Counting {
+        fn distance(&self, a: &str, b: &str) -> usize {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            crate::levenshtein_distance(a, b)
+        }
+
+        fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+            crate::Levenshtein.length_lower_bound(a, b)
+        }
+    }
+
+    #[test]
+    fn length_lower_bound_skips_words() {
+        use crate::DistanceMetric;
+        let checker =
+            crate::SpellChecker::new("a\nthin\nthing\nthings\nthingamajig").metric(Counting::default());
+        assert_eq!(checker.suggest("thinga", 1), vec!["thing", "things"]);
+        assert_eq!(checker.metric.0.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert_eq!(Positional.length_lower_bound(1, 5), 0);
+    }
+
+    #[test]
+    fn custom_metric() {
+        let string = "hint\nthin\nthing";
+        assert_eq!(crate::spellcheck(string, "thin", 2), vec!["thin", "thing", "hint"]);
+        assert_eq!(crate::spellcheck_with_metric(string, "thin", 2, &Positional), vec!["thin", "thing"]);
+        assert_eq!(
+            crate::spellcheck_with_metric(string, "thin", 2, &crate::Levenshtein),
+            crate::spellcheck(string, "thin", 2)
+        );
+        assert_eq!(crate::spellcheck("ten\nthe", "teh", 1), vec!["ten"]);
+        assert_eq!(
+            crate::spellcheck_with_metric("ten\nthe", "teh", 1, &crate::DamerauLevenshtein),
+            vec!["ten", "the"]
+        );
+        let checker = crate::SpellChecker::new(string).metric(Positional);
+        assert_eq!(checker.suggest("thin", 2), vec!["thin", "thing"]);
+        let boxed: Box<dyn crate::DistanceMetric> = Box::new(Positional);
+        let checker = checker.metric(boxed);
+        assert_eq!(checker.suggest("hint", 0), vec!["hint"]);
+    }
+
+    #[test]
+    fn lcs_metric() {
+        // a substitution costs 2 without substitutions, so the insertion wins
+        let checker = crate::SpellChecker::new("cut\ncart");
+        assert_eq!(checker.suggest("cat", 2), vec!["cut", "cart"]);
+        let checker = checker.metric(crate::Lcs);
+        assert_eq!(checker.suggest("cat", 2), vec!["cart", "cut"]);
+        assert_eq!(checker.suggest("cat", 1), vec!["cart"]);
+        assert_eq!(crate::spellcheck_with_metric("cut\ncart", "cat", 1, &crate::Lcs), vec!["cart"]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn top_k_is_truncated_spellcheck(
+            dictionary in proptest::collection::vec("[a-dé]{0,6}", 0..40),
+            word in "[a-dé]{0,6}",
+            distance in 0usize..5,
+            k in 0usize..8,
+        ) {
+            let dictionary_string = dictionary.join("\n");
+            let mut expected = crate::spellcheck(&dictionary_string, &word, distance);
+            expected.truncate(k);
+            proptest::prop_assert_eq!(crate::spellcheck_top_k(&dictionary_string, &word, distance, k), expected);
+        }
+
+        #[test]
+        fn by_ratio_matches_similarity(
+            dictionary in proptest::collection::vec("[a-dé]{0,6}", 0..40),
+            word in "[a-dé]{0,6}",
+            min_ratio in 0.0f64..1.0,
+        ) {
+            let dictionary_string = dictionary.join("\n");
+            proptest::prop_assert_eq!(
+                crate::spellcheck_by_ratio(&dictionary_string, &word, min_ratio),
+                crate::spellcheck_with_similarity(&dictionary_string, &word, min_ratio, &crate::NormalizedLevenshtein)
+            );
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+        fn serial_and_rayon_equivalent(
+            dictionary in proptest::collection::vec("[a-dé]{0,6}", 0..40),
+            word in "[a-dé]{0,6}",
+            distance in 0usize..5,
+        ) {
+            let dictionary_string = dictionary.join("\n");
+            proptest::prop_assert_eq!(
+                crate::spellcheck(&dictionary_string, &word, distance),
+                crate::spellcheck_rayon(&dictionary_string, &word, distance)
+            );
+            proptest::prop_assert_eq!(
+                crate::spellcheck_with_distances(&dictionary_string, &word, distance),
+                crate::spellcheck_rayon_with_distances(&dictionary_string, &word, distance)
+            );
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Fuzzy matching of typed commands against a known set, for command line
+//! tools and games.
+
+use crate::distance::{levenshtein_distance_bounded, MaxDistance};
+
+/// Matches user input against registered command names and aliases.
+/// ```
+/// use spelling::CommandMatcher;
+/// let matcher = CommandMatcher::new()
+///     .command("quit", &["exit"])
+///     .command("help", &["?"]);
+/// let found = matcher.match_input("qiut").unwrap();
+/// assert_eq!(found.command, "quit");
+/// assert_eq!(found.did_you_mean().as_deref(), Some("did you mean `quit`?"));
+/// assert_eq!(matcher.match_input("exit").unwrap().confidence, 1.0);
+/// assert!(matcher.match_input("launch").is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandMatcher {
+    /// `(name or alias, index of the command)`
+    entries: Vec<(String, usize)>,
+    commands: Vec<String>,
+    max_distance: usize,
+}
+
+/// The default for [`CommandMatcher::max_distance`].
+pub const DEFAULT_COMMAND_MAX_DISTANCE: usize = 2;
+
+impl Default for CommandMatcher {
+    fn default() -> Self {
+        CommandMatcher {
+            entries: Vec::new(),
+            commands: Vec::new(),
+            max_distance: DEFAULT_COMMAND_MAX_DISTANCE,
+        }
+    }
+}
+
+impl CommandMatcher {
+    /// Creates a matcher with no commands.
+    pub fn new() -> Self {
+        CommandMatcher::default()
+    }
+
+    /// Registers a command and its aliases, builder style.
+    pub fn command(mut self, name: &str, aliases: &[&str]) -> Self {
+        self.add_command(name, aliases);
+        self
+    }
+
+    /// Registers a command and its aliases. Earlier commands win ties.
+    pub fn add_command(&mut self, name: &str, aliases: &[&str]) {
+        let index = self.commands.len();
+        self.commands.push(name.to_owned());
+        self.entries.push((name.to_owned(), index));
+        for alias in aliases {
+            self.entries.push((alias.to_string(), index));
+        }
+    }
+
+    /// Sets how many edits away input can be and still match, builder style.
+    /// Defaults to [`DEFAULT_COMMAND_MAX_DISTANCE`].
+    pub fn max_distance(mut self, max_distance: usize) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Sets how many edits away input can be and still match.
+    pub fn set_max_distance(&mut self, max_distance: usize) {
+        self.max_distance = max_distance;
+    }
+
+    /// Returns the closest command to `input`, or `None` if nothing is within
+    /// [`max_distance`](CommandMatcher::max_distance). Surrounding whitespace
+    /// is ignored.
+    pub fn match_input(&self, input: &str) -> Option<CommandMatch<'_>> {
+        let input = input.trim();
+        let mut best: Option<(usize, &str, usize)> = None;
+        // only look for strictly better matches, so earlier entries win ties
+        let mut limit = self.max_distance;
+        for (entry, index) in &self.entries {
+            if let Some(distance) = levenshtein_distance_bounded(input, entry, limit).within() {
+                best = Some((distance, entry, *index));
+                if distance == 0 {
+                    break;
+                }
+                limit = distance - 1;
+            }
+        }
+        best.map(|(distance, matched, index)| {
+            let length = input.chars().count().max(matched.chars().count()).max(1);
+            CommandMatch {
+                command: &self.commands[index],
+                matched,
+                distance,
+                confidence: 1.0 - distance as f64 / length as f64,
+            }
+        })
+    }
+}
+
+/// How far [`did_you_mean`] looks: one edit for every 3 `char`s of input.
+const DID_YOU_MEAN_DISTANCE: MaxDistance = MaxDistance::Adaptive { per_chars: 3, cap: usize::MAX };
+
+/// Returns the closest of `candidates` to `input`, say a subcommand for a
+/// "did you mean" hint, ignoring case and surrounding whitespace. Input of
+/// up to 3 `char`s can be one edit away, up to 6 two, and so on; earlier
+/// candidates win ties. For aliases or a fixed distance, use a
+/// [`CommandMatcher`].
+/// ```
+/// use spelling::did_you_mean;
+/// let subcommands = ["build", "bench", "check", "clean"];
+/// assert_eq!(did_you_mean(&subcommands, "biuld"), Some("build"));
+/// assert_eq!(did_you_mean(&subcommands, "CHECK"), Some("check"));
+/// assert_eq!(did_you_mean(&subcommands, "publish"), None);
+/// let owned: Vec<String> = vec!["install".into()];
+/// assert_eq!(did_you_mean(&owned, "instal"), Some("install"));
+/// ```
+pub fn did_you_mean<'a, S: AsRef<str> + ?Sized + 'a>(
+    candidates: impl IntoIterator<Item = &'a S>,
+    input: &str,
+) -> Option<&'a str> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+    let mut limit = DID_YOU_MEAN_DISTANCE.for_word(&input);
+    let mut best = None;
+    for candidate in candidates {
+        let candidate = candidate.as_ref();
+        // strictly better matches only, like `match_input`
+        if let Some(distance) = levenshtein_distance_bounded(&input, &candidate.to_lowercase(), limit).within() {
+            best = Some(candidate);
+            if distance == 0 {
+                break;
+            }
+            limit = distance - 1;
+        }
+    }
+    best
+}
+
+/// A command found by [`CommandMatcher::match_input`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandMatch<'a> {
+    /// The command's name.
+    pub command: &'a str,
+    /// The name or alias that matched.
+    pub matched: &'a str,
+    /// How many edits away the input was from `matched`.
+    pub distance: usize,
+    /// From 0 to 1, 1 for an exact match.
+    pub confidence: f64,
+}
+
+impl CommandMatch<'_> {
+    /// Whether the input was exactly a name or alias.
+    pub fn is_exact(&self) -> bool {
+        self.distance == 0
+    }
+
+    /// "did you mean `command`?", or `None` for an exact match.
+    pub fn did_you_mean(&self) -> Option<String> {
+        if self.is_exact() {
+            None
+        } else {
+            Some(format!("did you mean `{}`?", self.command))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn command_matcher() {
+        let matcher = crate::CommandMatcher::new()
+            .command("status", &["st"])
+            .command("stash", &[])
+            .command("commit", &["ci"]);
+        let found = matcher.match_input(" st ").unwrap();
+        assert_eq!((found.command, found.matched, found.is_exact()), ("status", "st", true));
+        assert_eq!(found.did_you_mean(), None);
+        assert_eq!(matcher.match_input("statsh").unwrap().command, "stash");
+        // two away from "status", "st" and "stash", the first one wins
+        assert_eq!(matcher.match_input("stat").unwrap().matched, "status");
+        assert_eq!(matcher.match_input("comit").unwrap().confidence, 1.0 - 1.0 / 6.0);
+        assert_eq!(matcher.match_input("cmomit").unwrap().distance, 2);
+        assert!(matcher.clone().max_distance(1).match_input("cmomit").is_none());
+        assert!(crate::CommandMatcher::new().match_input("").is_none());
+    }
+
+    #[test]
+    fn did_you_mean() {
+        use crate::did_you_mean;
+        let candidates = ["run", "rum", "remove", "Status"];
+        assert_eq!(did_you_mean(&candidates, "ru"), Some("run"));
+        // one away from both, the first one wins
+        assert_eq!(did_you_mean(&candidates, "rux"), Some("run"));
+        assert_eq!(did_you_mean(&candidates, "rxm"), Some("rum"));
+        assert_eq!(did_you_mean(&candidates, " rum "), Some("rum"));
+        assert_eq!(did_you_mean(&candidates, "status"), Some("Status"));
+        // 2 edits is too many for 3 chars, but not for 6
+        assert_eq!(did_you_mean(&candidates, "rxx"), None);
+        assert_eq!(did_you_mean(&candidates, "remvoe"), Some("remove"));
+        assert_eq!(did_you_mean(&candidates, ""), None);
+        assert_eq!(did_you_mean(candidates.iter().copied().filter(|c| c.len() > 3), "stat"), Some("Status"));
+        assert_eq!(did_you_mean(Vec::<&str>::new().iter(), "run"), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Conformance tests driven by the Levenshtein, optimal string alignment and
+//! Damerau-Levenshtein test vectors in `src/test_vectors`, and
+//! optionally a typo corpus.
+//!
+//! The typo corpus test is ignored by default. Download a corpus in the
+//! `typo->correct` format, like
+//! <https://en.wikipedia.org/wiki/Wikipedia:Lists_of_common_misspellings/For_machines>,
+//! and run it with
+//! `SPELLING_TYPO_CORPUS=path/to/corpus.txt cargo test -- --ignored`.
+
+/// Parses a tab separated vector file, skipping comments. Each line is two
+/// strings followed by one or more distances.
+fn vectors(file: &str) -> Vec<(&str, &str, Vec<usize>)> {
+    file.lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .map(|line| {
+            let mut parts = line.split('\t');
+            let a = parts.next().expect("missing first string");
+            let b = parts.next().expect("missing second string");
+            let distances: Vec<usize> = parts.map(|distance| distance.parse().expect("invalid distance")).collect();
+            assert!(!distances.is_empty(), "missing distance");
+            (a, b, distances)
+        })
+        .collect()
+}
+
+/// Checks `distance` and `bounded` against `expected`, both ways round, with
+/// the bound at `expected` and one below it.
+fn check_vector(
+    a: &str,
+    b: &str,
+    expected: usize,
+    distance: fn(&str, &str) -> usize,
+    bounded: fn(&str, &str, usize) -> crate::Bounded,
+) {
+    assert_eq!(distance(a, b), expected, "{:?} {:?}", a, b);
+    assert_eq!(distance(b, a), expected, "{:?} {:?}", b, a);
+    assert_eq!(bounded(a, b, expected), crate::Bounded::Within(expected), "{:?} {:?}", a, b);
+    if expected > 0 {
+        assert_eq!(bounded(a, b, expected - 1), crate::Bounded::Exceeded, "{:?} {:?}", a, b);
+    }
+}
+
+#[test]
+fn levenshtein_vectors() {
+    let vectors = vectors(include_str!("test_vectors/levenshtein.tsv"));
+    assert!(vectors.len() > 20);
+    for (a, b, distances) in vectors {
+        check_vector(a, b, distances[0], crate::levenshtein_distance, crate::levenshtein_distance_bounded);
+    }
+}
+
+#[test]
+fn damerau_vectors() {
+    let vectors = vectors(include_str!("test_vectors/damerau.tsv"));
+    assert!(vectors.len() > 20);
+    for (a, b, distances) in vectors {
+        check_vector(a, b, distances[0], crate::osa_distance, crate::osa_distance_bounded);
+        check_vector(
+            a,
+            b,
+            distances[1],
+            crate::damerau_levenshtein_distance,
+            crate::damerau_levenshtein_distance_bounded,
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn typo_corpus() {
+    let path = match std::env::var("SPELLING_TYPO_CORPUS") {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    let corpus = std::fs::read_to_string(path).expect("couldn't read the typo corpus");
+    let pairs: Vec<(&str, &str)> = corpus
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, "->");
+            Some((parts.next()?.trim(), parts.next()?.trim()))
+        })
+        .collect();
+    for (typo, correct) in pairs {
+        // some entries list several corrections separated by commas
+        for correct in correct.split(',').map(str::trim) {
+            let distance = crate::levenshtein_distance(typo, correct);
+            assert_eq!(crate::levenshtein_distance(correct, typo), distance);
+            assert_eq!(
+                crate::levenshtein_distance_bounded(typo, correct, distance),
+                crate::Bounded::Within(distance)
+            );
+            #[cfg(feature = "check")]
+            assert_eq!(
+                crate::spellcheck(correct, typo, distance),
+                vec![correct],
+                "{:?} -> {:?}",
+                typo,
+                correct
+            );
+        }
+    }
+}
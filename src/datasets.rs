@@ -0,0 +1,118 @@
+//! Loaders for public misspelling corpora, for evaluating suggestions. The
+//! corpora themselves aren't bundled, download them and point these at the
+//! files.
+
+/// `(typo, correct)` pairs from a misspelling corpus.
+/// ```
+/// use spelling::TypoCorpus;
+/// let corpus = TypoCorpus::from_wikipedia("abandonned->abandoned\naccension->accession, ascension");
+/// assert_eq!(
+///     corpus.pairs().collect::<Vec<_>>(),
+///     vec![("abandonned", "abandoned"), ("accension", "accession"), ("accension", "ascension")]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypoCorpus {
+    pairs: Vec<(String, String)>,
+}
+
+impl TypoCorpus {
+    /// Parses the Birkbeck spelling error corpus format: a `$correct` line
+    /// followed by one misspelling per line. Anything after the first word on
+    /// a line, like a count, is ignored and `_` in corrections becomes a
+    /// space.
+    /// ```
+    /// use spelling::TypoCorpus;
+    /// let corpus = TypoCorpus::from_birkbeck("$thing\nthng\nthimg 2\n$a_lot\nalot\n");
+    /// assert_eq!(
+    ///     corpus.pairs().collect::<Vec<_>>(),
+    ///     vec![("thng", "thing"), ("thimg", "thing"), ("alot", "a lot")]
+    /// );
+    /// ```
+    pub fn from_birkbeck(text: &str) -> Self {
+        let mut correct: Option<String> = None;
+        let mut pairs = Vec::new();
+        for line in text.lines() {
+            let word = match line.split_whitespace().next() {
+                Some(word) => word,
+                None => continue,
+            };
+            match (word.strip_prefix('$'), &correct) {
+                (Some(""), _) => correct = None,
+                (Some(word), _) => correct = Some(word.replace('_', " ")),
+                (None, Some(correct)) => pairs.push((word.to_owned(), correct.clone())),
+                // misspellings without a correct word before them have
+                // nothing to be a misspelling of
+                (None, None) => {}
+            }
+        }
+        TypoCorpus { pairs }
+    }
+
+    /// Parses Wikipedia's list of common misspellings in its machine readable
+    /// `typo->correct` format. Typos with several possible corrections,
+    /// separated by `,`, give a pair for each. Lines without `->` are skipped.
+    pub fn from_wikipedia(text: &str) -> Self {
+        let mut pairs = Vec::new();
+        for line in text.lines() {
+            let mut parts = line.splitn(2, "->");
+            let (typo, corrections) = match (parts.next(), parts.next()) {
+                (Some(typo), Some(corrections)) => (typo.trim(), corrections),
+                _ => continue,
+            };
+            for correct in corrections.split(',').map(str::trim) {
+                if !typo.is_empty() && !correct.is_empty() {
+                    pairs.push((typo.to_owned(), correct.to_owned()));
+                }
+            }
+        }
+        TypoCorpus { pairs }
+    }
+
+    /// Reads a file in the Birkbeck format, see
+    /// [`from_birkbeck`](TypoCorpus::from_birkbeck).
+    pub fn load_birkbeck(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(TypoCorpus::from_birkbeck(&std::fs::read_to_string(path)?))
+    }
+
+    /// Reads a file in Wikipedia's format, see
+    /// [`from_wikipedia`](TypoCorpus::from_wikipedia).
+    pub fn load_wikipedia(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(TypoCorpus::from_wikipedia(&std::fs::read_to_string(path)?))
+    }
+
+    /// Iterates over the `(typo, correct)` pairs in file order.
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(typo, correct)| (typo.as_str(), correct.as_str()))
+    }
+
+    /// The number of pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Whether there are no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl<T: Into<String>, C: Into<String>> std::iter::FromIterator<(T, C)> for TypoCorpus {
+    fn from_iter<I: IntoIterator<Item = (T, C)>>(iter: I) -> Self {
+        TypoCorpus {
+            pairs: iter.into_iter().map(|(typo, correct)| (typo.into(), correct.into())).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn malformed_corpora() {
+        let corpus = crate::TypoCorpus::from_birkbeck("orphan\n\n  \n$thing\nthng\n$\nthimg\n");
+        assert_eq!(corpus.pairs().collect::<Vec<_>>(), vec![("thng", "thing")]);
+        let corpus = crate::TypoCorpus::from_wikipedia("<pre>\n->x\nthng->\nthng-> thing ,\r\n");
+        assert_eq!(corpus.pairs().collect::<Vec<_>>(), vec![("thng", "thing")]);
+        assert!(crate::TypoCorpus::load_wikipedia("/nonexistent/corpus").is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! A [DAWG](https://en.wikipedia.org/wiki/Deterministic_acyclic_finite_state_automaton),
+//! a word list compressed into a minimal automaton.
+
+use crate::dict::Dictionary;
+
+/// A word list stored as a minimal acyclic automaton: a trie where identical
+/// subtrees, like the `-ing` and `-ed` endings shared by thousands of words,
+/// are stored once. Large word lists take a fraction of the memory they do
+/// as a `Vec<String>`.
+///
+/// Words are kept sorted and without repeats. [`iter`](Dawg::iter) can feed
+/// them to the other index types.
+/// ```
+/// use spelling::{Dawg, SymSpellIndex};
+/// let dawg: Dawg = "thing\nthin\nthings\nhint".lines().collect();
+/// assert!(dawg.contains("thin"));
+/// assert!(!dawg.contains("thi"));
+/// assert_eq!(dawg.iter().collect::<Vec<_>>(), vec!["hint", "thin", "thing", "things"]);
+/// let index = SymSpellIndex::new(dawg.iter(), 1);
+/// assert_eq!(index.suggest("thinga", 1), vec!["thing", "things"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Dawg {
+    pub(crate) nodes: Vec<DawgNode>,
+    pub(crate) root: usize,
+    len: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct DawgNode {
+    /// `(char, node)` for each child, sorted by `char`.
+    pub(crate) edges: Vec<(char, usize)>,
+    pub(crate) is_final: bool,
+}
+
+impl Default for Dawg {
+    fn default() -> Self {
+        DawgBuilder::new().finish()
+    }
+}
+
+impl Dawg {
+    /// Builds a DAWG out of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of states in the automaton, which is what memory use
+    /// grows with.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether `word` is in the DAWG.
+    pub fn contains(&self, word: &str) -> bool {
+        let mut current = self.root;
+        for c in word.chars() {
+            let edges = &self.nodes[current].edges;
+            match edges.binary_search_by_key(&c, |&(edge, _)| edge) {
+                Ok(index) => current = edges[index].1,
+                Err(_) => return false,
+            }
+        }
+        self.nodes[current].is_final
+    }
+
+    /// Iterates over the words in sorted order.
+    pub fn iter(&self) -> DawgIter<'_> {
+        DawgIter {
+            dawg: self,
+            stack: vec![(self.root, 0)],
+            prefix: String::new(),
+        }
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does. The words don't have
+/// to be sorted, but are collected and sorted first when they aren't; use a
+/// [`DawgBuilder`] to build from sorted words without holding them all.
+impl<'a> std::iter::FromIterator<&'a str> for Dawg {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut words: Vec<&str> = iter.into_iter().collect();
+        words.sort_unstable();
+        let mut builder = DawgBuilder::new();
+        for word in words {
+            // sorted, so this can't fail
+            let _ = builder.insert(word);
+        }
+        builder.finish()
+    }
+}
+
+/// The words of a [`Dawg`], from [`Dawg::iter`].
+#[derive(Debug, Clone)]
+pub struct DawgIter<'a> {
+    dawg: &'a Dawg,
+    /// `(node, next edge)` for the path to the current word.
+    stack: Vec<(usize, usize)>,
+    prefix: String,
+}
+
+impl Iterator for DawgIter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let (node, edge) = self.stack.last_mut()?;
+            match self.dawg.nodes[*node].edges.get(*edge) {
+                Some(&(c, child)) => {
+                    *edge += 1;
+                    self.prefix.push(c);
+                    self.stack.push((child, 0));
+                    if self.dawg.nodes[child].is_final {
+                        return Some(self.prefix.clone());
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    // the root has no character of its own
+                    if !self.stack.is_empty() {
+                        self.prefix.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds a [`Dawg`] from sorted words in one pass, merging each finished
+/// branch with an identical one already built, so memory stays close to the
+/// size of the result.
+/// ```
+/// use spelling::DawgBuilder;
+/// let mut builder = DawgBuilder::new();
+/// builder.insert("tapping")?;
+/// builder.insert("tipping")?;
+/// assert!(builder.insert("topped").is_ok());
+/// assert!(builder.insert("tap").is_err());
+/// let dawg = builder.finish();
+/// assert_eq!(dawg.len(), 3);
+/// # Ok::<(), spelling::DawgOrderError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct DawgBuilder {
+    nodes: Vec<DawgNode>,
+    /// Every finished node, to find an identical one for the next.
+    register: std::collections::HashMap<DawgNode, usize>,
+    /// The nodes on the path to the last word, not finished yet, with the
+    /// character leading to each. The root's character is unused.
+    pending: Vec<(char, DawgNode)>,
+    previous: String,
+    len: usize,
+}
+
+/// Returned by [`DawgBuilder::insert`] for a word that sorts before the one
+/// inserted before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DawgOrderError {
+    /// The word inserted before.
+    pub previous: String,
+    /// The word that was out of order.
+    pub word: String,
+}
+
+impl std::fmt::Display for DawgOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} was inserted after {:?}, words have to be sorted", self.word, self.previous)
+    }
+}
+
+impl std::error::Error for DawgOrderError {}
+
+impl DawgBuilder {
+    /// A builder with no words.
+    pub fn new() -> Self {
+        DawgBuilder {
+            pending: vec![('\0', DawgNode::default())],
+            ..DawgBuilder::default()
+        }
+    }
+
+    /// Adds `word`, which has to sort after every word already added. Empty
+    /// and repeated words are skipped.
+    pub fn insert(&mut self, word: &str) -> Result<(), DawgOrderError> {
+        if word.is_empty() || word == self.previous {
+            return Ok(());
+        }
+        if word < self.previous.as_str() {
+            return Err(DawgOrderError {
+                previous: self.previous.clone(),
+                word: word.to_owned(),
+            });
+        }
+        let common = word
+            .chars()
+            .zip(self.previous.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.freeze(common + 1);
+        self.pending.extend(word.chars().skip(common).map(|c| (c, DawgNode::default())));
+        if let Some((_, last)) = self.pending.last_mut() {
+            last.is_final = true;
+        }
+        self.previous.clear();
+        self.previous.push_str(word);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Finishes the pending nodes past the first `keep`, linking each to its
+    /// parent.
+    fn freeze(&mut self, keep: usize) {
+        while self.pending.len() > keep {
+            let (c, node) = self.pending.pop().unwrap_or_default();
+            let id = self.register_node(node);
+            if let Some((_, parent)) = self.pending.last_mut() {
+                parent.edges.push((c, id));
+            }
+        }
+    }
+
+    fn register_node(&mut self, node: DawgNode) -> usize {
+        if let Some(&id) = self.register.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.register.insert(node, id);
+        id
+    }
+
+    /// The finished [`Dawg`].
+    pub fn finish(mut self) -> Dawg {
+        self.freeze(1);
+        let root = self.pending.pop().map(|(_, root)| root).unwrap_or_default();
+        let root = self.register_node(root);
+        Dawg {
+            nodes: self.nodes,
+            root,
+            len: self.len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn dawg() {
+        let dawg = crate::Dawg::from_dictionary(&crate::Dictionary::from_newline_separated(
+            "tapping\ntipping\ntopping\ntapped\ntipped\ntopped\ntap\n\ntap\nnaïve",
+        ));
+        assert_eq!(dawg.len(), 8);
+        // t, three vowels, two p's, then -ing and -ed share their ends
+        assert!(dawg.node_count() < 20);
+        assert!(dawg.contains("naïve"));
+        assert!(dawg.contains("tap"));
+        assert!(!dawg.contains("tip"));
+        assert!(!dawg.contains(""));
+        assert_eq!(dawg.iter().next(), Some("naïve".to_owned()));
+        assert_eq!(dawg.iter().count(), 8);
+
+        let empty = crate::Dawg::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().next(), None);
+        assert!(!empty.contains(""));
+    }
+
+    #[test]
+    fn builder_order() {
+        let mut builder = crate::DawgBuilder::new();
+        builder.insert("b").unwrap();
+        builder.insert("b").unwrap();
+        builder.insert("").unwrap();
+        let error = builder.insert("a").unwrap_err();
+        assert_eq!(error.to_string(), "\"a\" was inserted after \"b\", words have to be sorted");
+        assert_eq!(builder.finish().iter().collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn dawg_holds_its_words(words in proptest::collection::vec("[abc]{0,6}", 0..30), word in "[abc]{0,6}") {
+            let dawg: crate::Dawg = words.iter().map(String::as_str).collect();
+            let mut expected: Vec<String> = words.iter().filter(|word| !word.is_empty()).cloned().collect();
+            expected.sort_unstable();
+            expected.dedup();
+            proptest::prop_assert_eq!(dawg.contains(&word), expected.contains(&word));
+            proptest::prop_assert_eq!(dawg.len(), expected.len());
+            proptest::prop_assert_eq!(dawg.iter().collect::<Vec<_>>(), expected);
+        }
+    }
+}
@@ -0,0 +1,527 @@
+//! Word lists.
+
+/// The bundled english word list, newline separated. Only available with the
+/// `bundled_words` feature, so it doesn't end up in your binary unless you ask
+/// for it.
+#[cfg(feature = "bundled_words")]
+pub const BUNDLED_WORDS: &str = include_str!("words.txt");
+
+/// A list of words to check against.
+/// ```
+/// use spelling::Dictionary;
+/// let dictionary = Dictionary::from_newline_separated("thin\r\n  thing \r\n\r\n");
+/// assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec!["thin", "thing"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Vec<String>", into = "Vec<String>"))]
+pub struct Dictionary {
+    words: Vec<String>,
+    /// The length of each word in `char`s, worked out once so checkers can
+    /// skip words by length without counting.
+    lengths: Vec<usize>,
+}
+
+impl Dictionary {
+    /// Parses a newline separated word list. Handles `\r\n` line endings,
+    /// trailing newlines and whitespace around words, and skips blank lines,
+    /// so a word list checked out on Windows works the same as anywhere else.
+    pub fn from_newline_separated(dictionary_string: &str) -> Self {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("load_dictionary", bytes = dictionary_string.len()).entered();
+
+        let dictionary: Dictionary = dictionary_string
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(words = dictionary.len(), "dictionary loaded");
+        dictionary
+    }
+
+    /// Reads a newline separated word list from `reader` a line at a time, so
+    /// it can come from a file, stdin or a socket without being held in
+    /// memory whole. Lines are trimmed like
+    /// [`from_newline_separated`](Dictionary::from_newline_separated), and
+    /// blank lines and lines starting with `#` are skipped. Fails on a read
+    /// error or on a line that isn't UTF-8.
+    /// ```
+    /// use spelling::Dictionary;
+    /// let file = "# words\r\nthin\r\n\r\n  thing\n#thing\n";
+    /// let dictionary = Dictionary::from_reader(file.as_bytes())?;
+    /// assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec!["thin", "thing"]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_reader(mut reader: impl std::io::BufRead) -> std::io::Result<Self> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("load_dictionary_from_reader").entered();
+
+        let mut words = Vec::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line)? != 0 {
+            let word = line.trim();
+            if !word.is_empty() && !word.starts_with('#') {
+                words.push(word.to_owned());
+            }
+            line.clear();
+        }
+        let dictionary: Dictionary = words.into_iter().collect();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(words = dictionary.len(), "dictionary loaded");
+        Ok(dictionary)
+    }
+
+    /// Reads a word list like [`from_reader`](Dictionary::from_reader),
+    /// decompressing it first if it's gzipped (with the `gzip` feature) or
+    /// zstd compressed (with the `zstd` feature), so a word list can ship as
+    /// a much smaller asset. Uncompressed input is read as is. Compressed
+    /// input without its feature fails with
+    /// [`std::io::ErrorKind::Unsupported`].
+    /// ```
+    /// # #[cfg(feature = "gzip")] {
+    /// use std::io::Write;
+    /// use spelling::Dictionary;
+    /// let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    /// encoder.write_all(b"thin\nthing\n")?;
+    /// let compressed = encoder.finish()?;
+    /// let dictionary = Dictionary::from_compressed_reader(&compressed[..])?;
+    /// assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec!["thin", "thing"]);
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    pub fn from_compressed_reader(reader: impl std::io::Read) -> std::io::Result<Self> {
+        use std::io::BufRead;
+        const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+        const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+        let mut reader = std::io::BufReader::new(reader);
+        let start = reader.fill_buf()?;
+        if start.starts_with(GZIP_MAGIC) {
+            #[cfg(feature = "gzip")]
+            return Dictionary::from_reader(std::io::BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)));
+            #[cfg(not(feature = "gzip"))]
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "gzipped word lists need the gzip feature",
+            ));
+        }
+        if start.starts_with(ZSTD_MAGIC) {
+            #[cfg(feature = "zstd")]
+            return Dictionary::from_reader(std::io::BufReader::new(zstd::Decoder::with_buffer(reader)?));
+            #[cfg(not(feature = "zstd"))]
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "zstd compressed word lists need the zstd feature",
+            ));
+        }
+        Dictionary::from_reader(reader)
+    }
+
+    /// The bundled english word list, see [`BUNDLED_WORDS`].
+    #[cfg(feature = "bundled_words")]
+    pub fn bundled() -> Self {
+        Dictionary::from_newline_separated(BUNDLED_WORDS)
+    }
+
+    /// Iterates over the words in dictionary order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// Iterates over the words in dictionary order along with their length in
+    /// `char`s.
+    #[cfg(feature = "check")]
+    pub(crate) fn iter_with_lengths(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.iter().zip(self.lengths.iter().copied())
+    }
+
+    /// The number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Whether `word` is in the dictionary, exactly. This scans every word.
+    pub fn contains(&self, word: &str) -> bool {
+        self.iter().any(|entry| entry == word)
+    }
+
+    /// Finds entries that are likely to cause "why doesn't this word match"
+    /// bugs: words that differ only in case and, with the `unicode` feature,
+    /// words that aren't in NFC. Use this to warn about them, or
+    /// [`strict`](Dictionary::strict) to reject the dictionary outright.
+    /// ```
+    /// use spelling::{Dictionary, DictionaryIssue};
+    /// let dictionary = Dictionary::from_newline_separated("Thing\nthin\nthing");
+    /// assert_eq!(
+    ///     dictionary.issues(),
+    ///     vec![DictionaryIssue::CaseDuplicate { first: 0, second: 2 }]
+    /// );
+    /// ```
+    pub fn issues(&self) -> Vec<DictionaryIssue> {
+        let mut issues = Vec::new();
+        let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        for (index, word) in self.words.iter().enumerate() {
+            #[cfg(feature = "unicode")]
+            {
+                if !unicode_normalization::is_nfc(word) {
+                    issues.push(DictionaryIssue::NotNfc { index });
+                }
+            }
+            match seen.entry(word.to_lowercase()) {
+                std::collections::hash_map::Entry::Occupied(first) => {
+                    if self.words[*first.get()] != *word {
+                        issues.push(DictionaryIssue::CaseDuplicate {
+                            first: *first.get(),
+                            second: index,
+                        });
+                    }
+                }
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    entry.insert(index);
+                }
+            }
+        }
+        issues
+    }
+
+    /// Returns the dictionary if it has no [`issues`](Dictionary::issues),
+    /// otherwise all of them.
+    pub fn strict(self) -> Result<Self, DictionaryError> {
+        let issues = self.issues();
+        if issues.is_empty() {
+            Ok(self)
+        } else {
+            Err(DictionaryError { issues })
+        }
+    }
+
+    /// The word at `index`, in dictionary order.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.words.get(index).map(String::as_str)
+    }
+}
+
+/// A problem found by [`Dictionary::issues`]. Words are referred to by their
+/// index in the dictionary, see [`Dictionary::get`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictionaryIssue {
+    /// The two words only differ in case.
+    CaseDuplicate {
+        /// The earlier word.
+        first: usize,
+        /// The later word.
+        second: usize,
+    },
+    /// The word isn't in NFC, so it won't match the same word typed on most
+    /// keyboards. Only looked for with the `unicode` feature.
+    NotNfc {
+        /// The word.
+        index: usize,
+    },
+}
+
+impl std::fmt::Display for DictionaryIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DictionaryIssue::CaseDuplicate { first, second } => {
+                write!(f, "words {} and {} only differ in case", first, second)
+            }
+            DictionaryIssue::NotNfc { index } => write!(f, "word {} isn't in NFC", index),
+        }
+    }
+}
+
+/// Returned by [`Dictionary::strict`] when the dictionary has issues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryError {
+    /// Every issue that was found.
+    pub issues: Vec<DictionaryIssue>,
+}
+
+impl std::fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "dictionary has {} issue(s)", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+/// Empty words are skipped, they would otherwise match every query with a
+/// `distance` of at least its length.
+impl<S: Into<String>> std::iter::FromIterator<S> for Dictionary {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let words: Vec<String> = iter
+            .into_iter()
+            .map(Into::into)
+            .filter(|word: &String| !word.is_empty())
+            .collect();
+        let lengths = words.iter().map(|word| word.chars().count()).collect();
+        Dictionary { words, lengths }
+    }
+}
+
+/// Serialized as just the words, lengths are worked out again on load.
+#[cfg(feature = "serde")]
+impl From<Vec<String>> for Dictionary {
+    fn from(words: Vec<String>) -> Self {
+        words.into_iter().collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Dictionary> for Vec<String> {
+    fn from(dictionary: Dictionary) -> Self {
+        dictionary.words
+    }
+}
+
+/// A list of words with how often each one is used, for ranking suggestions
+/// at the same distance, see [`spellcheck_ranked`](crate::spellcheck_ranked).
+/// ```
+/// use spelling::FrequencyDictionary;
+/// let dictionary = FrequencyDictionary::from_tab_separated("thin\t10\r\nthing\t200\n");
+/// assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec![("thin", 10), ("thing", 200)]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyDictionary {
+    words: Vec<(String, u64)>,
+}
+
+impl FrequencyDictionary {
+    /// Parses lines of `word<TAB>count`. Blank lines are skipped like
+    /// [`Dictionary::from_newline_separated`], and a line without a count, or
+    /// with one that isn't a number, gets a count of 0.
+    pub fn from_tab_separated(dictionary_string: &str) -> Self {
+        dictionary_string
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| match line.split_once('\t') {
+                Some((word, count)) => (word.trim_end(), count.trim_start().parse().unwrap_or(0)),
+                None => (line, 0),
+            })
+            .collect()
+    }
+
+    /// Iterates over the words and their counts in dictionary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.words.iter().map(|(word, count)| (word.as_str(), *count))
+    }
+
+    /// The number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Empty words are skipped, the same as for [`Dictionary`].
+impl<S: Into<String>> std::iter::FromIterator<(S, u64)> for FrequencyDictionary {
+    fn from_iter<I: IntoIterator<Item = (S, u64)>>(iter: I) -> Self {
+        let words = iter
+            .into_iter()
+            .map(|(word, count)| (word.into(), count))
+            .filter(|(word, _): &(String, u64)| !word.is_empty())
+            .collect();
+        FrequencyDictionary { words }
+    }
+}
+
+impl From<FrequencyDictionary> for Dictionary {
+    fn from(dictionary: FrequencyDictionary) -> Self {
+        dictionary.words.into_iter().map(|(word, _)| word).collect()
+    }
+}
+
+/// A word list built at compile time by `include_dict!` (the `macros`
+/// feature), sorted by length in `char`s and then by word, so loading it costs
+/// nothing at runtime.
+/// ```
+/// # #[cfg(feature = "macros")] {
+/// use spelling::{include_dict, StaticDictionary};
+/// static WORDS: StaticDictionary = include_dict!("src/words.txt");
+/// assert!(WORDS.contains("thing"));
+/// assert!(WORDS.with_length(5).contains(&"thing"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticDictionary {
+    words: &'static [&'static str],
+    buckets: &'static [usize],
+}
+
+impl StaticDictionary {
+    /// Used by `include_dict!`. `words` has to be sorted by length in `char`s
+    /// and then by word, and `buckets[n]` has to be the index of the first
+    /// word with `n` `char`s, with one more entry after the longest length.
+    #[doc(hidden)]
+    pub const fn __from_parts(words: &'static [&'static str], buckets: &'static [usize]) -> Self {
+        StaticDictionary { words, buckets }
+    }
+
+    /// Iterates over the words, shortest first.
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> {
+        self.words.iter().copied()
+    }
+
+    /// The number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The words with exactly `length` `char`s, in order.
+    pub fn with_length(&self, length: usize) -> &'static [&'static str] {
+        let words = self.words;
+        let end = length.checked_add(1).and_then(|next| self.buckets.get(next));
+        match (self.buckets.get(length), end) {
+            (Some(&start), Some(&end)) => words.get(start..end).unwrap_or(&[]),
+            _ => &[],
+        }
+    }
+
+    /// Whether `word` is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.with_length(word.chars().count())
+            .binary_search(&word)
+            .is_ok()
+    }
+}
+
+impl From<StaticDictionary> for Dictionary {
+    fn from(dictionary: StaticDictionary) -> Self {
+        dictionary.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn strict_dictionary() {
+        use crate::{Dictionary, DictionaryIssue};
+        let clean = Dictionary::from_newline_separated("thin\nthing\nthing");
+        assert!(clean.issues().is_empty());
+        assert_eq!(clean.clone().strict(), Ok(clean));
+
+        let dictionary = Dictionary::from_newline_separated("Thin\nthing\nTHIN\nthin");
+        let error = dictionary.strict().unwrap_err();
+        assert_eq!(
+            error.issues,
+            vec![
+                DictionaryIssue::CaseDuplicate { first: 0, second: 2 },
+                DictionaryIssue::CaseDuplicate { first: 0, second: 3 },
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            "dictionary has 2 issue(s)\nwords 0 and 2 only differ in case\nwords 0 and 3 only differ in case"
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        use crate::Dictionary;
+        let dictionary = Dictionary::from_reader(std::io::BufReader::with_capacity(2, "thin\n\t#x\nthing".as_bytes()));
+        assert_eq!(dictionary.unwrap().iter().collect::<Vec<_>>(), vec!["thin", "thing"]);
+        assert!(Dictionary::from_reader(&b""[..]).unwrap().is_empty());
+        let error = Dictionary::from_reader(&b"thin\n\xff\n"[..]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn from_zstd_reader() {
+        use crate::Dictionary;
+        // two frames, like `cat a.zst b.zst`
+        let mut compressed = zstd::encode_all(&b"thin\n# comment\n"[..], 0).unwrap();
+        compressed.extend(zstd::encode_all(&b"thing\n"[..], 19).unwrap());
+        let dictionary = Dictionary::from_compressed_reader(&compressed[..]).unwrap();
+        assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec!["thin", "thing"]);
+        assert!(Dictionary::from_compressed_reader(&compressed[..8]).is_err());
+        #[cfg(not(feature = "gzip"))]
+        {
+            let error = Dictionary::from_compressed_reader(&[0x1f, 0x8b, 0][..]).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn from_compressed_reader() {
+        use std::io::Write;
+        use crate::Dictionary;
+        let mut compressed = Vec::new();
+        // two gzip members, like `cat a.gz b.gz`
+        for part in &["thin\n# comment\n", "thing\n"] {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder.write_all(part.as_bytes()).unwrap();
+            compressed.extend(encoder.finish().unwrap());
+        }
+        let dictionary = Dictionary::from_compressed_reader(&compressed[..]).unwrap();
+        assert_eq!(dictionary.iter().collect::<Vec<_>>(), vec!["thin", "thing"]);
+        let plain = Dictionary::from_compressed_reader(&b"thin\nthing"[..]).unwrap();
+        assert_eq!(plain, dictionary);
+        assert!(Dictionary::from_compressed_reader(&b""[..]).unwrap().is_empty());
+        #[cfg(not(feature = "zstd"))]
+        {
+            let error = Dictionary::from_compressed_reader(&[0x28, 0xb5, 0x2f, 0xfd, 0][..]).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+        }
+        let error = Dictionary::from_compressed_reader(&compressed[..12]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn static_dictionary() {
+        use crate::{Dictionary, StaticDictionary};
+        static WORDS: StaticDictionary =
+            StaticDictionary::__from_parts(&["a", "é", "ab", "thin"], &[0, 0, 2, 3, 3, 4]);
+        assert_eq!(WORDS.with_length(1), &["a", "é"]);
+        assert!(WORDS.with_length(3).is_empty());
+        assert!(WORDS.with_length(usize::MAX).is_empty());
+        assert!(WORDS.contains("é") && WORDS.contains("thin"));
+        assert!(!WORDS.contains("") && !WORDS.contains("thing"));
+        assert_eq!(Dictionary::from(WORDS), Dictionary::from_newline_separated("a\né\nab\nthin"));
+    }
+
+    #[test]
+    fn frequency_dictionary() {
+        use crate::{Dictionary, FrequencyDictionary};
+        let dictionary = FrequencyDictionary::from_tab_separated("thin\t10\n\nthing \t 200\nthings\nthinga\tmany");
+        assert_eq!(
+            dictionary.iter().collect::<Vec<_>>(),
+            vec![("thin", 10), ("thing", 200), ("things", 0), ("thinga", 0)]
+        );
+        assert_eq!(dictionary.len(), 4);
+        assert_eq!(Dictionary::from(dictionary).len(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn strict_dictionary_nfc() {
+        use crate::{Dictionary, DictionaryIssue};
+        let dictionary = Dictionary::from_newline_separated("caf\u{e9}\ncafe\u{301}");
+        assert_eq!(dictionary.issues(), vec![DictionaryIssue::NotNfc { index: 1 }]);
+    }
+}
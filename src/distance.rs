@@ -0,0 +1,1459 @@
+//! Distance functions and the [`DistanceMetric`] trait.
+
+/// Returns the Levenshtein distance between `a` and `b`, counted in `char`s.
+/// ```
+/// use spelling::levenshtein_distance;
+/// assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+/// ```
+pub fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    levenshtein_distance_slices(&a, &b)
+}
+
+/// The Levenshtein distance between two sequences of anything comparable.
+fn levenshtein_distance_slices<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut list: Vec<usize> = (0..(b.len() + 1)).collect();
+    let mut temp: Vec<usize> = Vec::with_capacity(b.len() + 1);
+    for (x, a_char) in a.iter().enumerate() {
+        temp.clear();
+        temp.push(x + 1);
+        for index in 1..(b.len() + 1) {
+            let left = temp[index - 1];
+            temp.push(match *a_char == b[index - 1] {
+                true => list[index - 1],
+                false => list[index - 1].min(list[index]).min(left) + 1,
+            });
+        }
+        std::mem::swap(&mut list, &mut temp);
+    }
+    list[b.len()]
+}
+
+/// The result of a distance computation with an upper bound.
+///
+/// Distinct from `Option` so "the bound was exceeded" can't be mixed up with
+/// any other reason for a missing value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bounded {
+    /// The distance, which is at most the bound.
+    Within(usize),
+    /// The distance is more than the bound.
+    Exceeded,
+}
+
+impl Bounded {
+    /// `Within(distance)` if `distance` is at most `max_distance`, otherwise
+    /// `Exceeded`.
+    pub fn new(distance: usize, max_distance: usize) -> Self {
+        if distance <= max_distance {
+            Bounded::Within(distance)
+        } else {
+            Bounded::Exceeded
+        }
+    }
+
+    /// The distance, if it was within the bound.
+    pub fn within(self) -> Option<usize> {
+        match self {
+            Bounded::Within(distance) => Some(distance),
+            Bounded::Exceeded => None,
+        }
+    }
+
+    /// Whether the distance was within the bound.
+    pub fn is_within(self) -> bool {
+        matches!(self, Bounded::Within(_))
+    }
+
+    /// Whether the distance was more than the bound.
+    pub fn is_exceeded(self) -> bool {
+        !self.is_within()
+    }
+}
+
+impl From<Bounded> for Option<usize> {
+    fn from(bounded: Bounded) -> Self {
+        bounded.within()
+    }
+}
+
+/// How far suggestions can be from a word. One edit is a lot for a three
+/// letter word and little for a fifteen letter one, so
+/// [`Adaptive`](MaxDistance::Adaptive) scales with the word's length.
+/// ```
+/// use spelling::MaxDistance;
+/// let adaptive = MaxDistance::Adaptive { per_chars: 4, cap: 3 };
+/// assert_eq!(adaptive.for_word("teh"), 1);
+/// assert_eq!(adaptive.for_word("restaraunt"), 3);
+/// assert_eq!(adaptive.for_word("incomprehensible"), 3);
+/// assert_eq!(MaxDistance::from(2).for_word("teh"), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaxDistance {
+    /// The same distance for every word.
+    Fixed(usize),
+    /// One edit for every `per_chars` `char`s of the word, rounded up, but
+    /// never more than `cap`.
+    Adaptive {
+        /// How many `char`s each edit needs. 0 is treated as 1.
+        per_chars: usize,
+        /// The most edits, however long the word.
+        cap: usize,
+    },
+}
+
+impl Default for MaxDistance {
+    /// `Adaptive { per_chars: 4, cap: 3 }`.
+    fn default() -> Self {
+        MaxDistance::Adaptive { per_chars: 4, cap: 3 }
+    }
+}
+
+impl MaxDistance {
+    /// The distance for a word of `length` `char`s.
+    pub fn for_length(self, length: usize) -> usize {
+        match self {
+            MaxDistance::Fixed(distance) => distance,
+            MaxDistance::Adaptive { per_chars, cap } => length.div_ceil(per_chars.max(1)).min(cap),
+        }
+    }
+
+    /// The distance for `word`.
+    pub fn for_word(self, word: &str) -> usize {
+        self.for_length(word.chars().count())
+    }
+}
+
+impl From<usize> for MaxDistance {
+    fn from(distance: usize) -> Self {
+        MaxDistance::Fixed(distance)
+    }
+}
+
+/// Returns the Levenshtein distance between `a` and `b` if it is at most
+/// `max_distance`, otherwise [`Bounded::Exceeded`]. Counted in `char`s.
+///
+/// When the shorter string is at most 64 `char`s this uses
+/// [`levenshtein_distance_myers`]. Otherwise only the diagonal band of width
+/// `2 * max_distance + 1` of the table is computed. Both give up as soon as
+/// the limit can't be met, so this is much faster than
+/// [`levenshtein_distance`] for small limits.
+///
+/// A `max_distance` of 0 is an equality check. There are no invalid limits:
+/// anything over the length of the longer string is clamped to it, since no
+/// distance can be larger, so `usize::MAX` means no limit rather than an
+/// error or a huge allocation.
+/// ```
+/// use spelling::{levenshtein_distance_bounded, Bounded};
+/// assert_eq!(levenshtein_distance_bounded("kitten", "sitting", 3), Bounded::Within(3));
+/// assert_eq!(levenshtein_distance_bounded("kitten", "sitting", 2), Bounded::Exceeded);
+/// assert_eq!(levenshtein_distance_bounded("kitten", "kitten", 0), Bounded::Within(0));
+/// ```
+pub fn levenshtein_distance_bounded(a: &str, b: &str, max_distance: usize) -> Bounded {
+    levenshtein_distance_bounded_in(&mut DistanceScratch::new(), a, b, max_distance)
+}
+
+/// Buffers for [`levenshtein_distance_bounded_in`], so checking many words in
+/// a row doesn't allocate for each one. Keep one per thread and reuse it.
+/// ```
+/// use spelling::{levenshtein_distance_bounded_in, DistanceScratch};
+/// let mut scratch = DistanceScratch::new();
+/// for word in ["thin", "thing", "things"] {
+///     levenshtein_distance_bounded_in(&mut scratch, "thinga", word, 2);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DistanceScratch {
+    a: Vec<char>,
+    b: Vec<char>,
+    list: Vec<usize>,
+    temp: Vec<usize>,
+    masks: Vec<(char, u64)>,
+}
+
+impl DistanceScratch {
+    /// Empty buffers, they grow to fit the longest words seen.
+    pub fn new() -> Self {
+        DistanceScratch::default()
+    }
+}
+
+/// Works like [`levenshtein_distance_bounded`], but reuses the buffers in
+/// `scratch` instead of allocating.
+pub fn levenshtein_distance_bounded_in(
+    scratch: &mut DistanceScratch,
+    a: &str,
+    b: &str,
+    max_distance: usize,
+) -> Bounded {
+    if max_distance == 0 {
+        // the band would be a single diagonal, which is just comparing
+        return if a == b { Bounded::Within(0) } else { Bounded::Exceeded };
+    }
+
+    let DistanceScratch {
+        a: a_chars,
+        b: b_chars,
+        list,
+        temp,
+        masks,
+    } = scratch;
+    a_chars.clear();
+    a_chars.extend(a.chars());
+    b_chars.clear();
+    b_chars.extend(b.chars());
+    let (a, b) = (&a_chars[..], &b_chars[..]);
+    let length_difference = if a.len() > b.len() { a.len() - b.len() } else { b.len() - a.len() };
+    if length_difference > max_distance {
+        return Bounded::Exceeded;
+    }
+    if let Some(bounded) = myers_chars(a, b, max_distance, masks) {
+        return bounded;
+    }
+
+    // the distance is never more than the length of the longer string, so a
+    // wider band is wasted memory (and `usize::MAX` would overflow)
+    let max_distance = max_distance.min(a.len().max(b.len()));
+    if max_distance == 0 {
+        // both are empty
+        return Bounded::Within(0);
+    }
+
+    // cell (x, index) of the table lives at `index + max_distance - x` in the
+    // band. The clamp above keeps all of this far from overflowing, but check
+    // anyway so a mistake fails loudly instead of indexing with a wrapped value
+    let width = match max_distance.checked_mul(2).and_then(|width| width.checked_add(1)) {
+        Some(width) => width,
+        None => {
+            debug_assert!(false, "band width overflowed for {}", max_distance);
+            return Bounded::Exceeded;
+        }
+    };
+    let band_index = |x: usize, index: usize| -> Option<usize> {
+        let band = index.checked_add(max_distance)?.checked_sub(x)?;
+        debug_assert!(band < width, "cell ({}, {}) is outside the band", x, index);
+        if band < width {
+            Some(band)
+        } else {
+            None
+        }
+    };
+    let over = max_distance.saturating_add(1);
+    list.clear();
+    list.resize(width, over);
+    temp.clear();
+    temp.resize(width, over);
+    for index in 0..=b.len().min(max_distance) {
+        match band_index(0, index) {
+            Some(band) => list[band] = index,
+            None => return Bounded::Exceeded,
+        }
+    }
+
+    for x in 1..(a.len() + 1) {
+        for cell in temp.iter_mut() {
+            *cell = over;
+        }
+        let mut row_min = over;
+        if x <= max_distance {
+            match band_index(x, 0) {
+                Some(band) => temp[band] = x,
+                None => return Bounded::Exceeded,
+            }
+            row_min = x;
+        }
+        let start = x.saturating_sub(max_distance).max(1);
+        let end = b.len().min(x.saturating_add(max_distance));
+        for index in start..(end + 1) {
+            let band = match band_index(x, index) {
+                Some(band) => band,
+                None => return Bounded::Exceeded,
+            };
+            let diagonal = list[band];
+            let above = list.get(band + 1).copied().unwrap_or(over);
+            let left = band.checked_sub(1).map_or(over, |band| temp[band]);
+            let value = match a[x - 1] == b[index - 1] {
+                true => diagonal,
+                false => diagonal.min(above).min(left).saturating_add(1),
+            }
+            .min(over);
+            temp[band] = value;
+            row_min = row_min.min(value);
+        }
+
+        // shortcircuit out
+        if row_min > max_distance {
+            return Bounded::Exceeded;
+        }
+        std::mem::swap(list, temp);
+    }
+
+    let out = match band_index(a.len(), b.len()) {
+        Some(band) => list[band],
+        None => return Bounded::Exceeded,
+    };
+    Bounded::new(out, max_distance)
+}
+
+/// Returns the Levenshtein distance between `a` and `b` if it is at most
+/// `max_distance`, otherwise `None`.
+#[deprecated(since = "0.3.0", note = "use `levenshtein_distance_bounded`, which returns `Bounded`")]
+pub fn levenshtein_distance_with_max(a: &str, b: &str, max_distance: usize) -> Option<usize> {
+    levenshtein_distance_bounded(a, b, max_distance).within()
+}
+
+/// Returns the Damerau-Levenshtein distance between `a` and `b`, counted in
+/// `char`s. Like [`levenshtein_distance`], but swapping two characters costs
+/// one edit instead of two, even with edits in between ("ca" to "abc" is 2).
+/// ```
+/// use spelling::{damerau_levenshtein_distance, levenshtein_distance};
+/// assert_eq!(damerau_levenshtein_distance("teh", "the"), 1);
+/// assert_eq!(levenshtein_distance("teh", "the"), 2);
+/// ```
+pub fn damerau_levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let width = b.len() + 2;
+    // the table has an extra row and column of "infinity" in front, so
+    // `table[x * width + y]` is the distance between a[..x - 1] and b[..y - 1]
+    let infinity = a.len() + b.len();
+    let mut table = vec![0; (a.len() + 2) * width];
+    table[0] = infinity;
+    for x in 0..=a.len() {
+        table[(x + 1) * width] = infinity;
+        table[(x + 1) * width + 1] = x;
+    }
+    for y in 0..=b.len() {
+        table[y + 1] = infinity;
+        table[width + y + 1] = y;
+    }
+
+    // the last row each character was seen in
+    let mut last_row: std::collections::HashMap<char, usize> = std::collections::HashMap::new();
+    for x in 1..=a.len() {
+        // the last column in this row where the characters matched
+        let mut last_match = 0;
+        for y in 1..=b.len() {
+            let row = last_row.get(&b[y - 1]).copied().unwrap_or(0);
+            let column = last_match;
+            let cost = if a[x - 1] == b[y - 1] {
+                last_match = y;
+                0
+            } else {
+                1
+            };
+            let substitution = table[x * width + y] + cost;
+            let insertion = table[(x + 1) * width + y] + 1;
+            let deletion = table[x * width + y + 1] + 1;
+            let transposition = table[row * width + column] + (x - row - 1) + 1 + (y - column - 1);
+            table[(x + 1) * width + y + 1] = substitution.min(insertion).min(deletion).min(transposition);
+        }
+        last_row.insert(a[x - 1], x);
+    }
+    table[(a.len() + 1) * width + b.len() + 1]
+}
+
+/// Returns the optimal string alignment distance between `a` and `b`, counted
+/// in `char`s. Like [`damerau_levenshtein_distance`], swapping two adjacent
+/// characters is one edit, but no substring can be edited twice, so "ca" to
+/// "abc" is 3 instead of 2. Cheaper to compute, and the same for most typos.
+/// ```
+/// use spelling::{damerau_levenshtein_distance, osa_distance};
+/// assert_eq!(osa_distance("teh", "the"), 1);
+/// assert_eq!(osa_distance("ca", "abc"), 3);
+/// assert_eq!(damerau_levenshtein_distance("ca", "abc"), 2);
+/// ```
+pub fn osa_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // the row before last, the last row and the current row
+    let mut two_back: Vec<usize> = vec![0; b.len() + 1];
+    let mut list: Vec<usize> = (0..(b.len() + 1)).collect();
+    let mut temp: Vec<usize> = Vec::with_capacity(b.len() + 1);
+    for x in 1..(a.len() + 1) {
+        temp.clear();
+        temp.push(x);
+        for index in 1..(b.len() + 1) {
+            let cost = usize::from(a[x - 1] != b[index - 1]);
+            let mut value = (list[index - 1] + cost).min(list[index] + 1).min(temp[index - 1] + 1);
+            if x > 1 && index > 1 && a[x - 1] == b[index - 2] && a[x - 2] == b[index - 1] {
+                value = value.min(two_back[index - 2] + 1);
+            }
+            temp.push(value);
+        }
+        std::mem::swap(&mut two_back, &mut list);
+        std::mem::swap(&mut list, &mut temp);
+    }
+    list[b.len()]
+}
+
+/// [`osa_distance`] with an upper bound, like [`levenshtein_distance_bounded`].
+/// Strings whose lengths differ by more than `max_distance` are rejected
+/// without filling in the table, and the table is abandoned as soon as two
+/// rows in a row are entirely over `max_distance`.
+/// ```
+/// use spelling::{osa_distance_bounded, Bounded};
+/// assert_eq!(osa_distance_bounded("teh", "the", 1), Bounded::Within(1));
+/// assert_eq!(osa_distance_bounded("abcdef", "uvwxyz", 2), Bounded::Exceeded);
+/// ```
+pub fn osa_distance_bounded(a: &str, b: &str, max_distance: usize) -> Bounded {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().max(b.len()) - a.len().min(b.len()) > max_distance {
+        return Bounded::Exceeded;
+    }
+    let mut two_back: Vec<usize> = vec![0; b.len() + 1];
+    let mut list: Vec<usize> = (0..(b.len() + 1)).collect();
+    let mut temp: Vec<usize> = Vec::with_capacity(b.len() + 1);
+    let mut last_minimum = 0;
+    for x in 1..(a.len() + 1) {
+        temp.clear();
+        temp.push(x);
+        let mut minimum = x;
+        for index in 1..(b.len() + 1) {
+            let cost = usize::from(a[x - 1] != b[index - 1]);
+            let mut value = (list[index - 1] + cost).min(list[index] + 1).min(temp[index - 1] + 1);
+            if x > 1 && index > 1 && a[x - 1] == b[index - 2] && a[x - 2] == b[index - 1] {
+                value = value.min(two_back[index - 2] + 1);
+            }
+            minimum = minimum.min(value);
+            temp.push(value);
+        }
+        // a cell only ever comes from the last two rows, and never gets
+        // smaller than where it came from
+        if minimum > max_distance && last_minimum > max_distance {
+            return Bounded::Exceeded;
+        }
+        last_minimum = minimum;
+        std::mem::swap(&mut two_back, &mut list);
+        std::mem::swap(&mut list, &mut temp);
+    }
+    Bounded::new(list[b.len()], max_distance)
+}
+
+/// [`damerau_levenshtein_distance`] with an upper bound, like
+/// [`levenshtein_distance_bounded`]. Strings whose lengths differ by more
+/// than `max_distance` are rejected without filling in the table.
+pub fn damerau_levenshtein_distance_bounded(a: &str, b: &str, max_distance: usize) -> Bounded {
+    let (a_length, b_length) = (a.chars().count(), b.chars().count());
+    if a_length.max(b_length) - a_length.min(b_length) > max_distance {
+        return Bounded::Exceeded;
+    }
+    Bounded::new(damerau_levenshtein_distance(a, b), max_distance)
+}
+
+/// The longest string, in bytes, [`levenshtein_distance_const`] accepts.
+pub const CONST_DISTANCE_MAX_LEN: usize = 64;
+
+/// Returns the Levenshtein distance between `a` and `b` in a `const fn`, so
+/// "did you mean" tables for things like command line flags can be built at
+/// compile time.
+///
+/// This counts bytes, which is the same as [`levenshtein_distance`] for ASCII.
+/// Returns `None` if either string is longer than [`CONST_DISTANCE_MAX_LEN`].
+/// ```
+/// use spelling::levenshtein_distance_const;
+/// const DISTANCE: Option<usize> = levenshtein_distance_const("--verbose", "--verbos");
+/// assert_eq!(DISTANCE, Some(1));
+/// ```
+pub const fn levenshtein_distance_const(a: &str, b: &str) -> Option<usize> {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() > CONST_DISTANCE_MAX_LEN || b.len() > CONST_DISTANCE_MAX_LEN {
+        return None;
+    }
+
+    // iterator adapters and `min` aren't const, hence the while loops
+    let mut row = [0; CONST_DISTANCE_MAX_LEN + 1];
+    let mut index = 0;
+    while index <= b.len() {
+        row[index] = index;
+        index += 1;
+    }
+    let mut x = 0;
+    while x < a.len() {
+        let mut diagonal = row[0];
+        row[0] = x + 1;
+        let mut index = 1;
+        while index <= b.len() {
+            let above = row[index];
+            let mut best = if a[x] == b[index - 1] { diagonal } else { diagonal + 1 };
+            if above + 1 < best {
+                best = above + 1;
+            }
+            if row[index - 1] + 1 < best {
+                best = row[index - 1] + 1;
+            }
+            row[index] = best;
+            diagonal = above;
+            index += 1;
+        }
+        x += 1;
+    }
+    Some(row[b.len()])
+}
+
+/// How to treat characters that can be written more than one way, like "é"
+/// as U+00E9 or as "e" followed by the combining U+0301.
+#[cfg(feature = "unicode")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Normalization {
+    /// Compare `char`s as they are. Precomposed "é" and "e" + U+0301 are
+    /// distance 2 apart (a substitution and an insertion).
+    #[default]
+    None,
+    /// Compose both strings (NFC) first. The two forms of "é" are distance 0
+    /// apart, and "é" is one substitution from "e".
+    Nfc,
+    /// Decompose both strings (NFD) first. The two forms of "é" are distance
+    /// 0 apart, and "é" is one deletion (of the accent) from "e".
+    Nfd,
+}
+
+#[cfg(feature = "unicode")]
+impl Normalization {
+    /// Normalizes `string`, only allocating if it isn't already normalized.
+    pub fn apply<'a>(&self, string: &'a str) -> std::borrow::Cow<'a, str> {
+        use std::borrow::Cow;
+        use unicode_normalization::{is_nfc, is_nfd, UnicodeNormalization};
+        match self {
+            Normalization::Nfc if !is_nfc(string) => Cow::Owned(string.nfc().collect()),
+            Normalization::Nfd if !is_nfd(string) => Cow::Owned(string.nfd().collect()),
+            _ => Cow::Borrowed(string),
+        }
+    }
+}
+
+/// [`levenshtein_distance`] after applying `normalization` to both strings.
+/// ```
+/// use spelling::{levenshtein_distance_normalized, Normalization};
+/// assert_eq!(levenshtein_distance_normalized("\u{e9}", "e\u{301}", Normalization::None), 2);
+/// assert_eq!(levenshtein_distance_normalized("\u{e9}", "e\u{301}", Normalization::Nfc), 0);
+/// ```
+#[cfg(feature = "unicode")]
+pub fn levenshtein_distance_normalized(a: &str, b: &str, normalization: Normalization) -> usize {
+    levenshtein_distance(&normalization.apply(a), &normalization.apply(b))
+}
+
+/// [`levenshtein_distance_bounded`] after applying `normalization` to both
+/// strings.
+#[cfg(feature = "unicode")]
+pub fn levenshtein_distance_bounded_normalized(
+    a: &str,
+    b: &str,
+    max_distance: usize,
+    normalization: Normalization,
+) -> Bounded {
+    levenshtein_distance_bounded(&normalization.apply(a), &normalization.apply(b), max_distance)
+}
+
+/// Returns the Levenshtein distance between `a` and `b`, counted in extended
+/// grapheme clusters instead of `char`s. An emoji joined with zero-width
+/// joiners, or a letter followed by combining accents, is one edit to add,
+/// remove or replace.
+/// ```
+/// use spelling::{levenshtein_distance, levenshtein_distance_graphemes};
+/// let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+/// assert_eq!(levenshtein_distance(family, ""), 5);
+/// assert_eq!(levenshtein_distance_graphemes(family, ""), 1);
+/// assert_eq!(levenshtein_distance_graphemes("cafe\u{301}", "cafe"), 1);
+/// ```
+#[cfg(feature = "unicode")]
+pub fn levenshtein_distance_graphemes(a: &str, b: &str) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+    let a: Vec<&str> = a.graphemes(true).collect();
+    let b: Vec<&str> = b.graphemes(true).collect();
+    levenshtein_distance_slices(&a, &b)
+}
+
+/// [`levenshtein_distance_graphemes`] if it is at most `max_distance`.
+#[cfg(feature = "unicode")]
+pub fn levenshtein_distance_graphemes_bounded(a: &str, b: &str, max_distance: usize) -> Bounded {
+    use unicode_segmentation::UnicodeSegmentation;
+    let a: Vec<&str> = a.graphemes(true).collect();
+    let b: Vec<&str> = b.graphemes(true).collect();
+    if a.len().max(b.len()) - a.len().min(b.len()) > max_distance {
+        return Bounded::Exceeded;
+    }
+    Bounded::new(levenshtein_distance_slices(&a, &b), max_distance)
+}
+
+/// The Levenshtein distance over grapheme clusters, see
+/// [`levenshtein_distance_graphemes`].
+#[cfg(feature = "unicode")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphemeLevenshtein;
+
+#[cfg(feature = "unicode")]
+impl DistanceMetric for GraphemeLevenshtein {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        levenshtein_distance_graphemes(a, b)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        levenshtein_distance_graphemes_bounded(a, b, max_distance)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "grapheme_levenshtein".into()
+    }
+}
+
+/// A way of measuring how far apart two strings are.
+///
+/// Implement this to use your own metric with [`spellcheck_with_metric`](crate::spellcheck_with_metric) and
+/// [`SpellChecker::metric`](crate::SpellChecker::metric). Built in are
+/// [`Levenshtein`], [`DamerauLevenshtein`], [`OptimalStringAlignment`],
+/// [`WeightedLevenshtein`], [`SmallAlphabet`], `GraphemeLevenshtein` with the
+/// `unicode` feature, [`PhoneticLevenshtein`](crate::PhoneticLevenshtein)
+/// and [`Lcs`](crate::Lcs).
+pub trait DistanceMetric: Send + Sync {
+    /// Returns the distance between `a` and `b`.
+    fn distance(&self, a: &str, b: &str) -> usize;
+
+    /// Returns the distance between `a` and `b` if it is at most
+    /// `max_distance`, otherwise [`Bounded::Exceeded`]. The default computes
+    /// the full distance, override it if the metric can give up early.
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        Bounded::new(self.distance(a, b), max_distance)
+    }
+
+    /// A lower bound on the distance between any two strings that are `a` and
+    /// `b` `char`s long. [`SpellChecker`](crate::SpellChecker) skips words
+    /// this rules out without measuring them. The default, 0, rules out
+    /// nothing.
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        let _ = (a, b);
+        0
+    }
+
+    /// A name for the metric and its settings, stable across versions and
+    /// builds, which [`BkTree::save`](crate::BkTree::save) records so a file
+    /// isn't loaded with a different metric. The default is the type's name,
+    /// which can change between compiler versions and leaves out any
+    /// settings, so override it if you save indexes.
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        std::any::type_name::<Self>().into()
+    }
+}
+
+impl<M: DistanceMetric + ?Sized> DistanceMetric for &M {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        (**self).distance(a, b)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        (**self).distance_bounded(a, b, max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        (**self).length_lower_bound(a, b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        (**self).id()
+    }
+}
+
+impl<M: DistanceMetric + ?Sized> DistanceMetric for Box<M> {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        (**self).distance(a, b)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        (**self).distance_bounded(a, b, max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        (**self).length_lower_bound(a, b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        (**self).id()
+    }
+}
+
+/// The Levenshtein distance, see [`levenshtein_distance`] and
+/// [`levenshtein_distance_bounded`]. This is the default everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Levenshtein;
+
+impl DistanceMetric for Levenshtein {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        levenshtein_distance(a, b)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        levenshtein_distance_bounded(a, b, max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        a.max(b) - a.min(b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "levenshtein".into()
+    }
+}
+
+/// The optimal string alignment distance, see [`osa_distance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimalStringAlignment;
+
+impl DistanceMetric for OptimalStringAlignment {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        osa_distance(a, b)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        osa_distance_bounded(a, b, max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        a.max(b) - a.min(b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "optimal_string_alignment".into()
+    }
+}
+
+/// The Damerau-Levenshtein distance, see [`damerau_levenshtein_distance`].
+/// Pass it to [`spellcheck_with_metric`](crate::spellcheck_with_metric) or
+/// [`SpellChecker::metric`](crate::SpellChecker::metric) to rank
+/// transpositions higher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DamerauLevenshtein;
+
+impl DistanceMetric for DamerauLevenshtein {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        damerau_levenshtein_distance(a, b)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        damerau_levenshtein_distance_bounded(a, b, max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        a.max(b) - a.min(b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "damerau_levenshtein".into()
+    }
+}
+
+/// Levenshtein distance with configurable costs for each kind of edit, and
+/// optionally for substituting specific pairs of characters. Edits turn the
+/// first string (the query, in a [`spellcheck_with_metric`](crate::spellcheck_with_metric))
+/// into the second (the dictionary word), so an insertion adds a character
+/// of the second. With every cost at 1, the default, this is [`Levenshtein`].
+/// ```
+/// use spelling::{DistanceMetric, WeightedLevenshtein};
+/// // OCR tends to drop characters and confuse `l` for `1`
+/// let ocr = WeightedLevenshtein::new()
+///     .insertion_cost(1)
+///     .deletion_cost(3)
+///     .substitution_cost(3)
+///     .pair_cost('1', 'l', 1);
+/// assert_eq!(ocr.distance("he1o", "hello"), 2);
+/// assert_eq!(ocr.distance("hello", "he1o"), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightedLevenshtein {
+    insertion: usize,
+    deletion: usize,
+    substitution: usize,
+    pairs: std::collections::HashMap<(char, char), usize>,
+}
+
+impl Default for WeightedLevenshtein {
+    fn default() -> Self {
+        WeightedLevenshtein {
+            insertion: 1,
+            deletion: 1,
+            substitution: 1,
+            pairs: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl WeightedLevenshtein {
+    /// Every edit costs 1.
+    pub fn new() -> Self {
+        WeightedLevenshtein::default()
+    }
+
+    /// Sets the cost of inserting a character, builder style.
+    pub fn insertion_cost(mut self, cost: usize) -> Self {
+        self.insertion = cost;
+        self
+    }
+
+    /// Sets the cost of deleting a character, builder style.
+    pub fn deletion_cost(mut self, cost: usize) -> Self {
+        self.deletion = cost;
+        self
+    }
+
+    /// Sets the cost of substituting a character, builder style.
+    pub fn substitution_cost(mut self, cost: usize) -> Self {
+        self.substitution = cost;
+        self
+    }
+
+    /// Sets the cost of substituting `from` in the first string for `to` in
+    /// the second, overriding the substitution cost, builder style. Only
+    /// applies in that direction.
+    pub fn pair_cost(mut self, from: char, to: char, cost: usize) -> Self {
+        self.set_pair_cost(from, to, cost);
+        self
+    }
+
+    /// Sets the cost of inserting a character.
+    pub fn set_insertion_cost(&mut self, cost: usize) {
+        self.insertion = cost;
+    }
+
+    /// Sets the cost of deleting a character.
+    pub fn set_deletion_cost(&mut self, cost: usize) {
+        self.deletion = cost;
+    }
+
+    /// Sets the cost of substituting a character.
+    pub fn set_substitution_cost(&mut self, cost: usize) {
+        self.substitution = cost;
+    }
+
+    /// Sets the cost of substituting `from` in the first string for `to` in
+    /// the second.
+    pub fn set_pair_cost(&mut self, from: char, to: char, cost: usize) {
+        self.pairs.insert((from, to), cost);
+    }
+
+    /// The cost of substituting `from` for `to`.
+    fn substitution(&self, from: char, to: char) -> usize {
+        if from == to {
+            0
+        } else {
+            self.pairs.get(&(from, to)).copied().unwrap_or(self.substitution)
+        }
+    }
+}
+
+impl DistanceMetric for WeightedLevenshtein {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        // nothing exceeds usize::MAX
+        self.distance_bounded(a, b, usize::MAX).within().unwrap_or(usize::MAX)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut list: Vec<usize> = (0..(b.len() + 1)).map(|index| index.saturating_mul(self.insertion)).collect();
+        let mut temp: Vec<usize> = Vec::with_capacity(b.len() + 1);
+        for (x, &a_char) in a.iter().enumerate() {
+            temp.clear();
+            temp.push((x + 1).saturating_mul(self.deletion));
+            let mut row_min = temp[0];
+            for index in 1..(b.len() + 1) {
+                let value = list[index - 1]
+                    .saturating_add(self.substitution(a_char, b[index - 1]))
+                    .min(list[index].saturating_add(self.deletion))
+                    .min(temp[index - 1].saturating_add(self.insertion));
+                row_min = row_min.min(value);
+                temp.push(value);
+            }
+            // costs are never negative, so no later row can do better
+            if row_min > max_distance {
+                return Bounded::Exceeded;
+            }
+            std::mem::swap(&mut list, &mut temp);
+        }
+        Bounded::new(list[b.len()], max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        if a > b {
+            (a - b).saturating_mul(self.deletion)
+        } else {
+            (b - a).saturating_mul(self.insertion)
+        }
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        // sorted, since the map's order changes from run to run
+        let mut pairs: Vec<_> = self.pairs.iter().collect();
+        pairs.sort_unstable();
+        let mut id = format!("weighted_levenshtein:{},{},{}", self.insertion, self.deletion, self.substitution);
+        for ((from, to), cost) in pairs {
+            id += &format!(",{:x}>{:x}={}", *from as u32, *to as u32, cost);
+        }
+        id.into()
+    }
+}
+
+/// The most symbols a [`SmallAlphabet`] can have.
+pub const SMALL_ALPHABET_MAX_SYMBOLS: usize = 64;
+
+/// A faster Levenshtein distance for strings made of a small, fixed set of
+/// ASCII characters, like DNA (`ACGT`), digits or identifiers. It precomputes
+/// a bit mask of where each symbol occurs and works out a whole column of the
+/// edit distance table per step (Myers' bit-parallel algorithm).
+///
+/// Gives the same results as [`Levenshtein`] for every input. Strings with
+/// characters outside the alphabet, or where both are longer than 64, fall
+/// back to [`levenshtein_distance_bounded`].
+/// ```
+/// use spelling::{DistanceMetric, SmallAlphabet};
+/// let dna = SmallAlphabet::new("ACGT").unwrap();
+/// assert_eq!(dna.distance("GATTACA", "GCATGCT"), 4);
+/// assert!(dna.distance_bounded("GATTACA", "GCATGCT", 3).is_exceeded());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmallAlphabet {
+    /// The symbol index of every byte, `u8::MAX` if it isn't in the alphabet.
+    index: [u8; 256],
+    symbols: usize,
+}
+
+impl SmallAlphabet {
+    /// Creates an alphabet out of the characters in `alphabet`, repeats are
+    /// ignored. Returns `None` if any of them isn't ASCII or there are more
+    /// than [`SMALL_ALPHABET_MAX_SYMBOLS`].
+    pub fn new(alphabet: &str) -> Option<Self> {
+        let mut index = [u8::MAX; 256];
+        let mut symbols = 0;
+        for byte in alphabet.bytes() {
+            if !byte.is_ascii() {
+                return None;
+            }
+            if index[byte as usize] == u8::MAX {
+                if symbols == SMALL_ALPHABET_MAX_SYMBOLS {
+                    return None;
+                }
+                index[byte as usize] = symbols as u8;
+                symbols += 1;
+            }
+        }
+        Some(SmallAlphabet { index, symbols })
+    }
+
+    /// Whether every character of `text` is in the alphabet.
+    pub fn contains(&self, text: &str) -> bool {
+        text.bytes().all(|byte| self.index[byte as usize] != u8::MAX)
+    }
+
+    /// Myers' algorithm with the shorter string as the pattern, which has to
+    /// fit in a `u64`.
+    fn bit_parallel(&self, a: &[u8], b: &[u8], max_distance: usize) -> Bounded {
+        let (pattern, text) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        if pattern.is_empty() {
+            return Bounded::new(text.len(), max_distance);
+        }
+        let mut masks = [0u64; SMALL_ALPHABET_MAX_SYMBOLS];
+        for (bit, &byte) in pattern.iter().enumerate() {
+            masks[self.index[byte as usize] as usize] |= 1 << bit;
+        }
+        let text_masks = text.iter().map(|&byte| masks[self.index[byte as usize] as usize]);
+        myers_columns(pattern.len(), text_masks, max_distance)
+    }
+}
+
+/// The core of Myers' bit-parallel algorithm. `text_masks` has, for each
+/// character of the text, a mask of where it occurs in the pattern, which has
+/// to be between 1 and 64 characters long.
+fn myers_columns(
+    pattern_length: usize,
+    text_masks: impl ExactSizeIterator<Item = u64>,
+    max_distance: usize,
+) -> Bounded {
+    let text_length = text_masks.len();
+    // the vertical deltas of the current column, +1 and -1
+    let mut positive = !0u64;
+    let mut negative = 0u64;
+    let last = 1 << (pattern_length - 1);
+    let mut score = pattern_length;
+    for (column, matches) in text_masks.enumerate() {
+        let vertical = matches | negative;
+        let diagonal = ((matches & positive).wrapping_add(positive) ^ positive) | matches;
+        let mut horizontal_positive = negative | !(diagonal | positive);
+        let mut horizontal_negative = positive & diagonal;
+        if horizontal_positive & last != 0 {
+            score += 1;
+        } else if horizontal_negative & last != 0 {
+            score -= 1;
+        }
+        // each remaining column can lower the score by at most one
+        if score > max_distance.saturating_add(text_length - column - 1) {
+            return Bounded::Exceeded;
+        }
+        // the top row of the table counts up, so shift in a +1
+        horizontal_positive = (horizontal_positive << 1) | 1;
+        horizontal_negative <<= 1;
+        positive = horizontal_negative | !(vertical | horizontal_positive);
+        negative = horizontal_positive & vertical;
+    }
+    Bounded::new(score, max_distance)
+}
+
+/// Myers' algorithm over `char`s, with the shorter string as the pattern.
+/// `None` if both are longer than 64 `char`s. `other` is a buffer for the
+/// masks of non-ASCII characters.
+fn myers_chars(a: &[char], b: &[char], max_distance: usize, other: &mut Vec<(char, u64)>) -> Option<Bounded> {
+    let (pattern, text) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if pattern.len() > 64 {
+        return None;
+    }
+    if pattern.is_empty() {
+        return Some(Bounded::new(text.len(), max_distance));
+    }
+    // ASCII gets a table, anything else a short list, there are at most 64
+    let mut ascii = [0u64; 128];
+    other.clear();
+    for (bit, &c) in pattern.iter().enumerate() {
+        if c.is_ascii() {
+            ascii[c as usize] |= 1 << bit;
+        } else {
+            match other.iter_mut().find(|(symbol, _)| *symbol == c) {
+                Some((_, mask)) => *mask |= 1 << bit,
+                None => other.push((c, 1 << bit)),
+            }
+        }
+    }
+    let text_masks = text.iter().map(|&c| {
+        if c.is_ascii() {
+            ascii[c as usize]
+        } else {
+            other.iter().find(|(symbol, _)| *symbol == c).map_or(0, |(_, mask)| *mask)
+        }
+    });
+    Some(myers_columns(pattern.len(), text_masks, max_distance))
+}
+
+/// Returns the Levenshtein distance between `a` and `b` using Myers'
+/// bit-parallel algorithm, which works out a whole column of the table per
+/// step. `None` if both strings are longer than 64 `char`s, the size of a
+/// machine word. [`levenshtein_distance_bounded`], and so
+/// [`spellcheck`](crate::spellcheck), already uses this when it can.
+/// ```
+/// use spelling::levenshtein_distance_myers;
+/// assert_eq!(levenshtein_distance_myers("kitten", "sitting"), Some(3));
+/// assert_eq!(levenshtein_distance_myers(&"a".repeat(65), &"b".repeat(65)), None);
+/// ```
+pub fn levenshtein_distance_myers(a: &str, b: &str) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // nothing exceeds usize::MAX
+    myers_chars(&a, &b, usize::MAX, &mut Vec::new()).map(|bounded| bounded.within().unwrap_or(usize::MAX))
+}
+
+impl DistanceMetric for SmallAlphabet {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        // nothing exceeds usize::MAX
+        self.distance_bounded(a, b, usize::MAX).within().unwrap_or(usize::MAX)
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        if a.len().min(b.len()) > 64 || !self.contains(a) || !self.contains(b) {
+            return levenshtein_distance_bounded(a, b, max_distance);
+        }
+        self.bit_parallel(a.as_bytes(), b.as_bytes(), max_distance)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        a.max(b) - a.min(b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "small_alphabet".into()
+    }
+}
+
+/// Maps accented Latin letters to their base letter, leaving everything else
+/// alone.
+fn base_letter(c: char) -> char {
+    const LATIN_1: &str = "AAAAAAÆCEEEEIIIIDNOOOOO×OUUUUYÞßaaaaaaæceeeeiiiidnooooo÷ouuuuyþy";
+    const LATIN_EXTENDED_A: &str = "\
+AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIi\
+IiĲĳJjKkĸLlLlLlLlLlNnNnNnŉŊŋOoOoOoŒœRrRrRrSsSsSs\
+SsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzſ";
+    let code = c as u32;
+    let base = match code {
+        0xC0..=0xFF => LATIN_1.chars().nth((code - 0xC0) as usize),
+        0x100..=0x17F => LATIN_EXTENDED_A.chars().nth((code - 0x100) as usize),
+        _ => None,
+    };
+    base.unwrap_or(c)
+}
+
+/// Whether `c` is in the Combining Diacritical Marks block, like the U+0301
+/// in "e\u{301}".
+#[cfg(feature = "check")]
+fn is_combining_diacritic(c: char) -> bool {
+    matches!(c, '\u{300}'..='\u{36F}')
+}
+
+/// Maps accented Latin letters to their base letter and drops combining
+/// accents, only allocating if something changes.
+#[cfg(feature = "check")]
+pub(crate) fn strip_diacritics(string: &str) -> std::borrow::Cow<'_, str> {
+    if string.chars().all(|c| base_letter(c) == c && !is_combining_diacritic(c)) {
+        std::borrow::Cow::Borrowed(string)
+    } else {
+        std::borrow::Cow::Owned(
+            string
+                .chars()
+                .filter(|&c| !is_combining_diacritic(c))
+                .map(base_letter)
+                .collect(),
+        )
+    }
+}
+
+/// Lowercases `c` and strips its accents.
+pub(crate) fn fold_char(c: char) -> char {
+    let base = base_letter(c);
+    let mut lower = base.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => base,
+    }
+}
+
+/// Levenshtein distance where substituting a letter for the same letter in a
+/// different case or with different accents is a minor edit instead of a full
+/// one. Returns `(edits, minor_edits)`, minimizing edits first. Counted in
+/// `char`s.
+/// ```
+/// use spelling::levenshtein_distance_folded;
+/// assert_eq!(levenshtein_distance_folded("schrodinger", "Schrödinger"), (0, 2));
+/// assert_eq!(levenshtein_distance_folded("schrodinger", "schrodingers"), (1, 0));
+/// ```
+pub fn levenshtein_distance_folded(a: &str, b: &str) -> (usize, usize) {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let b_folded: Vec<char> = b.iter().map(|&c| fold_char(c)).collect();
+
+    let mut list: Vec<(usize, usize)> = (0..(b.len() + 1)).map(|index| (index, 0)).collect();
+    for (x, &a_char) in a.iter().enumerate() {
+        let a_folded = fold_char(a_char);
+        let mut temp = Vec::with_capacity(b.len() + 1);
+        temp.push((x + 1, 0));
+        for index in 1..(b.len() + 1) {
+            let diagonal = list[index - 1];
+            let substitution = if a_char == b[index - 1] {
+                diagonal
+            } else if a_folded == b_folded[index - 1] {
+                (diagonal.0, diagonal.1 + 1)
+            } else {
+                (diagonal.0 + 1, diagonal.1)
+            };
+            let insertion = (temp[index - 1].0 + 1, temp[index - 1].1);
+            let deletion = (list[index].0 + 1, list[index].1);
+            temp.push(substitution.min(insertion).min(deletion));
+        }
+        list = temp;
+    }
+    list[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn max_distance() {
+        use crate::MaxDistance;
+        let adaptive = MaxDistance::Adaptive { per_chars: 3, cap: 2 };
+        let distances: Vec<usize> = (0..8).map(|length| adaptive.for_length(length)).collect();
+        assert_eq!(distances, vec![0, 1, 1, 1, 2, 2, 2, 2]);
+        assert_eq!(adaptive.for_word("naïve"), 2);
+        assert_eq!(MaxDistance::Adaptive { per_chars: 0, cap: 5 }.for_length(3), 3);
+        assert_eq!(MaxDistance::Fixed(4).for_length(0), 4);
+        assert_eq!(MaxDistance::default().for_length(12), 3);
+    }
+
+    #[test]
+    fn folded_distance() {
+        assert_eq!(crate::levenshtein_distance_folded("", "abc"), (3, 0));
+        assert_eq!(crate::levenshtein_distance_folded("CRÈME", "creme"), (0, 5));
+        assert_eq!(crate::levenshtein_distance_folded("crème", "crime"), (1, 0));
+        assert_eq!(crate::levenshtein_distance_folded("ÿ", "Ÿ"), (0, 1));
+    }
+
+    #[test]
+    fn max_distance_zero() {
+        assert_eq!(crate::levenshtein_distance_bounded("thin", "thin", 0), crate::Bounded::Within(0));
+        assert_eq!(crate::levenshtein_distance_bounded("thin", "thing", 0), crate::Bounded::Exceeded);
+        assert_eq!(crate::levenshtein_distance_bounded("", "", 0), crate::Bounded::Within(0));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn option_shim() {
+        assert_eq!(crate::levenshtein_distance_with_max("kitten", "sitting", 3), Some(3));
+        assert_eq!(crate::levenshtein_distance_with_max("kitten", "sitting", 2), None);
+        assert_eq!(Option::<usize>::from(crate::Bounded::Within(1)), Some(1));
+        assert!(crate::Bounded::Exceeded.is_exceeded());
+    }
+
+    #[test]
+    fn bounded_matches_full() {
+        let words = ["", "a", "ab", "thin", "thing", "rumor", "rotten", "kitten", "sitting", "héllo", "hello"];
+        for a in &words {
+            for b in &words {
+                let full = crate::levenshtein_distance(a, b);
+                for max in 0..8 {
+                    let expected = if full <= max { Some(full) } else { None };
+                    assert_eq!(crate::levenshtein_distance_bounded(a, b, max).within(), expected, "{} {} {}", a, b, max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn huge_max_distance() {
+        assert_eq!(crate::levenshtein_distance_bounded("kitten", "sitting", usize::MAX), crate::Bounded::Within(3));
+        assert_eq!(crate::levenshtein_distance_bounded("", "", usize::MAX), crate::Bounded::Within(0));
+        assert_eq!(crate::levenshtein_distance_bounded("", "abc", usize::MAX), crate::Bounded::Within(3));
+        // too long for Myers', so these go through the clamped band
+        let (a, b) = ("ab".repeat(40), "ba".repeat(40));
+        let full = crate::levenshtein_distance(&a, &b);
+        for max in [full, full + 1, a.len(), usize::MAX / 2, usize::MAX] {
+            assert_eq!(crate::levenshtein_distance_bounded(&a, &b, max), crate::Bounded::Within(full));
+        }
+        assert_eq!(crate::levenshtein_distance_bounded(&a, "", usize::MAX), crate::Bounded::Within(80));
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn combining_characters() {
+        use crate::{levenshtein_distance_normalized as distance, Normalization};
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_eq!(distance(composed, decomposed, Normalization::None), 2);
+        assert_eq!(distance(composed, decomposed, Normalization::Nfc), 0);
+        assert_eq!(distance(composed, decomposed, Normalization::Nfd), 0);
+        for &normalization in &[Normalization::Nfc, Normalization::Nfd] {
+            assert_eq!(distance(composed, "cafe", normalization), 1);
+            assert_eq!(distance(decomposed, "cafe", normalization), 1);
+            assert_eq!(distance(decomposed, "caf\u{e8}", normalization), 1);
+        }
+        // two accents on one letter, NFC can't compose both
+        assert_eq!(distance("\u{1ec7}", "e\u{323}\u{302}", Normalization::Nfc), 0);
+        assert_eq!(distance("\u{1ec7}", "e", Normalization::Nfc), 1);
+        assert_eq!(distance("\u{1ec7}", "e", Normalization::Nfd), 2);
+        assert_eq!(
+            crate::levenshtein_distance_bounded_normalized(composed, decomposed, 0, Normalization::Nfc),
+            crate::Bounded::Within(0)
+        );
+    }
+
+    #[test]
+    fn const_distance() {
+        const FLAGS: [Option<usize>; 2] = [
+            crate::levenshtein_distance_const("", "help"),
+            crate::levenshtein_distance_const("hepl", "help"),
+        ];
+        assert_eq!(FLAGS, [Some(4), Some(2)]);
+        let long = "a".repeat(crate::CONST_DISTANCE_MAX_LEN);
+        assert_eq!(crate::levenshtein_distance_const(&long, ""), Some(long.len()));
+        assert_eq!(crate::levenshtein_distance_const("", &format!("{}a", long)), None);
+    }
+
+    #[test]
+    fn damerau_levenshtein() {
+        let distance = crate::damerau_levenshtein_distance;
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("ca", "abc"), 2);
+        assert_eq!(distance("abcdef", "badcfe"), 3);
+        assert_eq!(distance("naïve", "nvïae"), 2);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert!(crate::damerau_levenshtein_distance_bounded("a", "abcd", 2).is_exceeded());
+    }
+
+    #[test]
+    fn osa() {
+        let distance = crate::osa_distance;
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("abcdef", "badcfe"), 3);
+        assert_eq!(distance("ca", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert!(crate::osa_distance_bounded("a", "abcd", 2).is_exceeded());
+    }
+
+    #[test]
+    fn small_alphabet() {
+        use crate::{DistanceMetric, SmallAlphabet};
+        assert!(SmallAlphabet::new("aé").is_none());
+        assert!(SmallAlphabet::new(&"a".repeat(100)).is_some());
+        let digits = SmallAlphabet::new("0123456789").unwrap();
+        assert!(digits.contains("0451") && !digits.contains("04a"));
+        assert_eq!(digits.distance("", "123"), 3);
+        assert_eq!(digits.distance("1234", "1243"), 2);
+        let long = "1".repeat(64);
+        assert_eq!(digits.distance(&long, &format!("2{}", long)), 1);
+        assert_eq!(digits.distance(&long, &"2".repeat(64)), 64);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn const_equals_full(a in "[ -~]{0,12}", b in "[ -~]{0,12}") {
+            let full = crate::levenshtein_distance(&a, &b);
+            proptest::prop_assert_eq!(crate::levenshtein_distance_const(&a, &b), Some(full));
+        }
+
+        #[test]
+        fn bounded_equals_full(a in "\\PC{0,12}", b in "\\PC{0,12}", max in proptest::prop_oneof![0usize..16, proptest::num::usize::ANY]) {
+            let full = crate::levenshtein_distance(&a, &b);
+            let bounded = crate::levenshtein_distance_bounded(&a, &b, max).within();
+            if full <= max {
+                proptest::prop_assert_eq!(bounded, Some(full));
+            } else {
+                proptest::prop_assert_eq!(bounded, None);
+            }
+        }
+
+        // random unicode strings rarely share characters, this hits the
+        // matching paths of the band much more often
+        #[test]
+        fn bounded_equals_full_small_alphabet(a in "[ab]{0,10}", b in "[ab]{0,10}", max in 0usize..12) {
+            let full = crate::levenshtein_distance(&a, &b);
+            let expected = if full <= max { Some(full) } else { None };
+            proptest::prop_assert_eq!(crate::levenshtein_distance_bounded(&a, &b, max).within(), expected);
+        }
+
+        #[test]
+        fn myers_equals_full(a in "[abé]{0,70}", b in "[abé]{0,70}") {
+            let full = crate::levenshtein_distance(&a, &b);
+            let expected = if a.chars().count().min(b.chars().count()) <= 64 { Some(full) } else { None };
+            proptest::prop_assert_eq!(crate::levenshtein_distance_myers(&a, &b), expected);
+        }
+
+        #[test]
+        fn scratch_reuse_equals_fresh(words in proptest::collection::vec("[abé]{0,80}", 1..8), word in "[abé]{0,80}", max in 0usize..40) {
+            let mut scratch = crate::DistanceScratch::new();
+            for other in &words {
+                proptest::prop_assert_eq!(
+                    crate::levenshtein_distance_bounded_in(&mut scratch, &word, other, max),
+                    crate::Bounded::new(crate::levenshtein_distance(&word, other), max)
+                );
+            }
+        }
+
+        // both longer than 64, so these go through the banded table
+        #[test]
+        fn bounded_equals_full_long(a in "[abé]{65,80}", b in "[abé]{65,80}", max in 0usize..40) {
+            let full = crate::levenshtein_distance(&a, &b);
+            proptest::prop_assert_eq!(crate::levenshtein_distance_bounded(&a, &b, max), crate::Bounded::new(full, max));
+        }
+
+        #[test]
+        fn small_alphabet_equals_full(a in "[ACGT]{0,80}", b in "[ACGTN]{0,80}", max in 0usize..20) {
+            let dna = crate::SmallAlphabet::new("ACGT").unwrap();
+            let full = crate::levenshtein_distance(&a, &b);
+            proptest::prop_assert_eq!(crate::DistanceMetric::distance(&dna, &a, &b), full);
+            proptest::prop_assert_eq!(
+                crate::DistanceMetric::distance_bounded(&dna, &a, &b, max),
+                crate::Bounded::new(full, max)
+            );
+        }
+
+        #[test]
+        fn damerau_levenshtein_properties(a in "[abc]{0,8}", b in "[abc]{0,8}", c in "[abc]{0,8}", max in 0usize..10) {
+            check_metric(&crate::DamerauLevenshtein, &a, &b, &c)?;
+            let full = crate::damerau_levenshtein_distance(&a, &b);
+            proptest::prop_assert!(full <= crate::levenshtein_distance(&a, &b));
+            proptest::prop_assert_eq!(
+                crate::damerau_levenshtein_distance_bounded(&a, &b, max),
+                crate::Bounded::new(full, max)
+            );
+        }
+
+        #[test]
+        fn weighted_defaults_to_levenshtein(a in "[abé]{0,10}", b in "[abé]{0,10}", max in 0usize..12) {
+            let weighted = crate::WeightedLevenshtein::new();
+            let full = crate::levenshtein_distance(&a, &b);
+            proptest::prop_assert_eq!(crate::DistanceMetric::distance(&weighted, &a, &b), full);
+            proptest::prop_assert_eq!(crate::DistanceMetric::distance_bounded(&weighted, &a, &b, max), crate::Bounded::new(full, max));
+        }
+
+        #[test]
+        fn weighted_bounded_equals_full(a in "[abc]{0,8}", b in "[abc]{0,8}", costs in proptest::array::uniform4(0usize..4), max in 0usize..20) {
+            let weighted = crate::WeightedLevenshtein::new()
+                .insertion_cost(costs[0])
+                .deletion_cost(costs[1])
+                .substitution_cost(costs[2])
+                .pair_cost('a', 'b', costs[3]);
+            let full = crate::DistanceMetric::distance(&weighted, &a, &b);
+            proptest::prop_assert_eq!(crate::DistanceMetric::distance_bounded(&weighted, &a, &b, max), crate::Bounded::new(full, max));
+            let lower_bound = crate::DistanceMetric::length_lower_bound(&weighted, a.chars().count(), b.chars().count());
+            proptest::prop_assert!(lower_bound <= full);
+        }
+
+        // not a metric, "ca" -> "ac" -> "abc" is 2 but "ca" -> "abc" is 3
+        #[test]
+        fn osa_between_damerau_and_levenshtein(a in "[abc]{0,8}", b in "[abc]{0,8}", max in 0usize..10) {
+            let osa = crate::osa_distance(&a, &b);
+            proptest::prop_assert_eq!(osa, crate::osa_distance(&b, &a));
+            proptest::prop_assert_eq!(osa == 0, a == b);
+            proptest::prop_assert!(crate::damerau_levenshtein_distance(&a, &b) <= osa);
+            proptest::prop_assert!(osa <= crate::levenshtein_distance(&a, &b));
+            proptest::prop_assert_eq!(crate::osa_distance_bounded(&a, &b, max), crate::Bounded::new(osa, max));
+        }
+
+        #[test]
+        fn levenshtein_is_a_metric(a in "[abc]{0,8}", b in "[abc]{0,8}", c in "[abc]{0,8}") {
+            check_metric(&crate::Levenshtein, &a, &b, &c)?;
+        }
+
+        #[test]
+        fn levenshtein_is_a_metric_unicode(a in "\\PC{0,8}", b in "\\PC{0,8}", c in "\\PC{0,8}") {
+            check_metric(&crate::Levenshtein, &a, &b, &c)?;
+        }
+
+        #[test]
+        #[cfg(feature = "unicode")]
+        fn graphemes_are_a_metric(a in "[ae\u{301}\u{200d}]{0,8}", b in "[ae\u{301}\u{200d}]{0,8}", c in "[ae\u{301}\u{200d}]{0,8}") {
+            check_metric(&crate::GraphemeLevenshtein, &a, &b, &c)?;
+        }
+
+        #[test]
+        #[cfg(feature = "unicode")]
+        fn graphemes_match_chars_for_ascii(a in "[a-d]{0,8}", b in "[a-d]{0,8}") {
+            proptest::prop_assert_eq!(crate::levenshtein_distance_graphemes(&a, &b), crate::levenshtein_distance(&a, &b));
+        }
+
+        #[test]
+        fn folded_edits_are_a_metric(a in "[aAáb]{0,8}", b in "[aAáb]{0,8}", c in "[aAáb]{0,8}") {
+            let distance = |a: &str, b: &str| crate::levenshtein_distance_folded(a, b);
+            proptest::prop_assert_eq!(distance(&a, &a), (0, 0));
+            proptest::prop_assert_eq!(distance(&a, &b), distance(&b, &a));
+            proptest::prop_assert!(distance(&a, &c).0 <= distance(&a, &b).0 + distance(&b, &c).0);
+        }
+    }
+
+    /// Checks identity, symmetry and the triangle inequality, which the
+    /// bounded search and any tree based index rely on.
+    fn check_metric(
+        metric: &impl crate::DistanceMetric,
+        a: &str,
+        b: &str,
+        c: &str,
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        proptest::prop_assert_eq!(metric.distance(a, a), 0);
+        proptest::prop_assert_eq!(metric.distance(a, b) == 0, a == b);
+        proptest::prop_assert_eq!(metric.distance(a, b), metric.distance(b, a));
+        proptest::prop_assert!(metric.distance(a, c) <= metric.distance(a, b) + metric.distance(b, c));
+        Ok(())
+    }
+}
@@ -0,0 +1,686 @@
+//! Checking whole documents rather than single words.
+
+use crate::check::SpellChecker;
+use crate::dict::Dictionary;
+use crate::distance::DistanceMetric;
+use crate::rank::Candidate;
+use crate::tokenize::words;
+
+/// A word that isn't in the dictionary, with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling<'a> {
+    /// The word's byte range in its line or text.
+    pub span: std::ops::Range<usize>,
+    /// The misspelled word.
+    pub word: String,
+    /// Suggestions from the dictionary, best first.
+    pub suggestions: Vec<&'a str>,
+    /// Two dictionary words the misspelling is made of, if there are no
+    /// suggestions, like "hello" and "world" for "helloworld".
+    pub split: Option<(&'a str, &'a str)>,
+}
+
+impl Misspelling<'_> {
+    /// The display columns the misspelling covers in its line, counting wide
+    /// characters like CJK as 2 and combining marks as 0, which is what an
+    /// editor needs to underline it. `text` is what
+    /// [`span`](Misspelling::span) is relative to, a line or a whole text.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("thing");
+    /// let text = "漢字 thing\nnaïve thng";
+    /// let misspellings = checker.check_text(text, 1);
+    /// assert_eq!(misspellings[0].columns(text), 0..4);
+    /// assert_eq!(misspellings[2].columns(text), 6..10);
+    /// ```
+    #[cfg(feature = "unicode")]
+    pub fn columns(&self, text: &str) -> std::ops::Range<usize> {
+        use unicode_width::UnicodeWidthStr;
+        let line_start = text[..self.span.start].rfind('\n').map_or(0, |newline| newline + 1);
+        let start = text[line_start..self.span.start].width();
+        start..start + text[self.span.clone()].width()
+    }
+
+    /// The misspelling's position in its line in UTF-16 code units, which is
+    /// how the Language Server Protocol and JavaScript editors count
+    /// columns. `text` is what [`span`](Misspelling::span) is relative to.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("thing");
+    /// let text = "thing\n\u{1f600} thng";
+    /// let misspellings = checker.check_text(text, 1);
+    /// assert_eq!(misspellings[0].span, 11..15);
+    /// assert_eq!(misspellings[0].utf16_columns(text), 3..7);
+    /// ```
+    pub fn utf16_columns(&self, text: &str) -> std::ops::Range<usize> {
+        let line_start = text[..self.span.start].rfind('\n').map_or(0, |newline| newline + 1);
+        let start = text[line_start..self.span.start].encode_utf16().count();
+        start..start + text[self.span.clone()].encode_utf16().count()
+    }
+}
+
+/// A paragraph of a document and its misspellings, see
+/// [`SpellChecker::check_document`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The section's byte range in the document, without the newline that
+    /// ends it.
+    pub span: std::ops::Range<usize>,
+    /// The misspellings in the section in order, with spans relative to the
+    /// whole document.
+    pub misspellings: Vec<Misspelling<'a>>,
+}
+
+/// The byte ranges of the runs of non-blank lines in `text`.
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+fn sections(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut sections = Vec::new();
+    let mut current: Option<std::ops::Range<usize>> = None;
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let end = start + line.trim_end_matches(['\n', '\r']).len();
+        if line.trim().is_empty() {
+            sections.extend(current.take());
+        } else {
+            current = Some(current.map_or(start, |current| current.start)..end);
+        }
+        start += line.len();
+    }
+    sections.extend(current);
+    sections
+}
+
+/// The result of [`SpellChecker::correct_query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCorrection<'a> {
+    /// The query with every correction applied.
+    pub corrected: String,
+    /// The changes that were made, in order.
+    pub corrections: Vec<TokenCorrection<'a>>,
+}
+
+impl QueryCorrection<'_> {
+    /// Whether anything was corrected, i.e. whether to show "did you mean".
+    pub fn is_changed(&self) -> bool {
+        !self.corrections.is_empty()
+    }
+}
+
+/// Misspellings found by [`SpellChecker::check_text_in`], kept in a few flat
+/// buffers rather than a `String` and a `Vec` for each one, with repeats of a
+/// word sharing their suggestions. [`clear`](MisspellingArena::clear) it
+/// between documents to reuse the memory.
+#[derive(Debug, Clone, Default)]
+pub struct MisspellingArena<'a> {
+    entries: Vec<ArenaEntry<'a>>,
+    suggestions: Vec<&'a str>,
+    candidates: Vec<Candidate<'a>>,
+}
+
+#[derive(Debug, Clone)]
+struct ArenaEntry<'a> {
+    span: std::ops::Range<usize>,
+    /// The range of the arena's suggestions that are this word's.
+    suggestions: std::ops::Range<usize>,
+    split: Option<(&'a str, &'a str)>,
+}
+
+/// A word's range of suggestions in a [`MisspellingArena`] and its split, or
+/// `None` if it's spelled correctly.
+type ArenaFound<'a> = Option<(std::ops::Range<usize>, Option<(&'a str, &'a str)>)>;
+
+/// A misspelling in a [`MisspellingArena`], like a [`Misspelling`] without
+/// the word, which is the [`span`](ArenaMisspelling::span) of the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArenaMisspelling<'a, 'b> {
+    /// The word's byte range in the text.
+    pub span: std::ops::Range<usize>,
+    /// Suggestions from the dictionary, best first.
+    pub suggestions: &'b [&'a str],
+    /// Two dictionary words the misspelling is made of, if there are no
+    /// suggestions.
+    pub split: Option<(&'a str, &'a str)>,
+}
+
+impl<'a> MisspellingArena<'a> {
+    /// An empty arena.
+    pub fn new() -> Self {
+        MisspellingArena::default()
+    }
+
+    /// The number of misspellings.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no misspellings.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every misspelling, keeping the memory for the next document.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.suggestions.clear();
+    }
+
+    /// The misspelling at `index`, in the order they were found.
+    pub fn get(&self, index: usize) -> Option<ArenaMisspelling<'a, '_>> {
+        self.entries.get(index).map(|entry| self.view(entry))
+    }
+
+    /// The misspellings in the order they were found.
+    pub fn iter(&self) -> impl Iterator<Item = ArenaMisspelling<'a, '_>> {
+        self.entries.iter().map(move |entry| self.view(entry))
+    }
+
+    fn view(&self, entry: &ArenaEntry<'a>) -> ArenaMisspelling<'a, '_> {
+        ArenaMisspelling {
+            span: entry.span.clone(),
+            suggestions: &self.suggestions[entry.suggestions.clone()],
+            split: entry.split,
+        }
+    }
+}
+
+/// One change made by [`SpellChecker::correct_query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenCorrection<'a> {
+    /// The byte range in the query that was replaced. Covers both words when
+    /// two were merged.
+    pub span: std::ops::Range<usize>,
+    /// What the range was replaced with.
+    pub replacement: String,
+    /// Other suggestions for the first word in `span`, best first.
+    pub alternatives: Vec<&'a str>,
+}
+
+impl<M: DistanceMetric> SpellChecker<M> {
+    /// Checks `reader` one line at a time and yields each misspelling with the
+    /// index of its line, starting at 0. Spans are relative to the line. Only
+    /// one line is held in memory at a time and buffers are reused between
+    /// lines, so files of any size can be checked.
+    ///
+    /// A word is misspelled if nothing in the dictionary is 0 away from it
+    /// under the checker's [`DistanceMode`](crate::DistanceMode). Suggestions
+    /// are the same as [`suggest`](SpellChecker::suggest) with `distance`.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("a\nthin\nthing");
+    /// let text = "a thin thng\n\nthinn thing\n";
+    /// let misspellings: Vec<_> = checker
+    ///     .check_lines(text.as_bytes(), 1)
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(misspellings.len(), 2);
+    /// let (line, misspelling) = &misspellings[1];
+    /// assert_eq!((*line, &misspelling.word, misspelling.span.clone()), (2, &"thinn".to_owned(), 0..5));
+    /// assert_eq!(misspelling.suggestions, vec!["thin", "thing"]);
+    /// ```
+    pub fn check_lines<R: std::io::BufRead>(&self, reader: R, distance: usize) -> CheckLines<'_, M, R> {
+        CheckLines {
+            checker: self,
+            reader,
+            distance,
+            line: String::new(),
+            line_index: 0,
+            pending: std::collections::VecDeque::new(),
+            candidates: Vec::new(),
+        }
+    }
+
+    /// Checks every word in `text` and returns the misspellings in order, with
+    /// spans relative to `text`. Suggestions are worked out once per distinct
+    /// word and reused for every other occurrence, which is reported to the
+    /// checker's [`Metrics`](crate::Metrics) as a cache hit.
+    ///
+    /// A misspelling with no suggestions within `distance` gets a
+    /// [`split`](Misspelling::split) into two words instead, if it has one.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("the\nthin\nthing");
+    /// let misspellings = checker.check_text("the thng, the thinn thng thethin", 1);
+    /// let spans: Vec<_> = misspellings.iter().map(|m| m.span.clone()).collect();
+    /// assert_eq!(spans, vec![4..8, 14..19, 20..24, 25..32]);
+    /// assert_eq!(misspellings[2].suggestions, vec!["thing"]);
+    /// assert_eq!(misspellings[3].split, Some(("the", "thin")));
+    /// ```
+    pub fn check_text(&self, text: &str, distance: usize) -> Vec<Misspelling<'_>> {
+        let mut candidates = Vec::new();
+        let mut seen: std::collections::HashMap<&str, Option<Misspelling<'_>>> = std::collections::HashMap::new();
+        let mut misspellings = Vec::new();
+        for (start, word) in words(text) {
+            let misspelling = match seen.get(word) {
+                Some(misspelling) => {
+                    self.record_cache_hit();
+                    misspelling.clone().map(|misspelling| Misspelling {
+                        span: start..start + word.len(),
+                        ..misspelling
+                    })
+                }
+                None => {
+                    let misspelling = self.check_word(start, word, distance, &mut candidates);
+                    seen.insert(word, misspelling.clone());
+                    misspelling
+                }
+            };
+            misspellings.extend(misspelling);
+        }
+        misspellings
+    }
+
+    /// Like [`check_text`](SpellChecker::check_text), but adds the
+    /// misspellings to `arena` instead of allocating each one. Checking many
+    /// documents with one arena, cleared in between, allocates next to
+    /// nothing once it has grown.
+    /// ```
+    /// use spelling::{MisspellingArena, SpellChecker};
+    /// let checker = SpellChecker::new("the\nthin\nthing");
+    /// let mut arena = MisspellingArena::new();
+    /// checker.check_text_in("the thng, the thinn thng thethin", 1, &mut arena);
+    /// assert_eq!(arena.len(), 4);
+    /// assert_eq!(arena.get(2).unwrap().suggestions, ["thing"]);
+    /// assert_eq!(arena.get(3).unwrap().split, Some(("the", "thin")));
+    /// ```
+    pub fn check_text_in<'a>(&'a self, text: &str, distance: usize, arena: &mut MisspellingArena<'a>) {
+        let mut seen: std::collections::HashMap<&str, ArenaFound<'a>> = std::collections::HashMap::new();
+        for (start, word) in words(text) {
+            let found = match seen.get(word) {
+                Some(found) => {
+                    self.record_cache_hit();
+                    found.clone()
+                }
+                None => {
+                    let found = self.check_word_in(word, distance, arena);
+                    seen.insert(word, found.clone());
+                    found
+                }
+            };
+            if let Some((suggestions, split)) = found {
+                arena.entries.push(ArenaEntry {
+                    span: start..start + word.len(),
+                    suggestions,
+                    split,
+                });
+            }
+        }
+    }
+
+    /// Like [`check_word`](SpellChecker::check_word), adding the suggestions
+    /// to `arena` and returning their range.
+    fn check_word_in<'a>(
+        &'a self,
+        word: &str,
+        distance: usize,
+        arena: &mut MisspellingArena<'a>,
+    ) -> ArenaFound<'a> {
+        if self.contains(word) {
+            return None;
+        }
+        self.suggest_explained_into(word, distance, &mut arena.candidates);
+        if arena.candidates.iter().any(|candidate| candidate.breakdown.distance == 0) {
+            return None;
+        }
+        let start = arena.suggestions.len();
+        arena.suggestions.extend(arena.candidates.iter().map(|candidate| candidate.word));
+        let split = if arena.candidates.is_empty() { self.split_in_two(word) } else { None };
+        Some((start..arena.suggestions.len(), split))
+    }
+
+    /// Splits `text` into sections at blank lines and checks them in parallel
+    /// with rayon, like [`check_text`](SpellChecker::check_text) on each.
+    /// Sections come back in document order, including ones without
+    /// misspellings.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("thin\nthing");
+    /// let sections = checker.check_document("thin thng\n\n\nthing\nthinn\n", 1);
+    /// assert_eq!(sections.len(), 2);
+    /// assert_eq!(sections[1].span, 12..23);
+    /// assert_eq!(sections[1].misspellings[0].span, 18..23);
+    /// ```
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    pub fn check_document(&self, text: &str, distance: usize) -> Vec<Section<'_>> {
+        use rayon::prelude::*;
+        sections(text)
+            .into_par_iter()
+            .map(|span| {
+                let mut misspellings = self.check_text(&text[span.clone()], distance);
+                for misspelling in &mut misspellings {
+                    misspelling.span = misspelling.span.start + span.start..misspelling.span.end + span.start;
+                }
+                Section { span, misspellings }
+            })
+            .collect()
+    }
+
+    /// Corrects a search query, like a search engine's "did you mean". Each
+    /// word that isn't in the dictionary is, in order of preference:
+    ///
+    /// 1. merged with the next word, if together they make a dictionary word
+    ///    ("spell checker" → "spellchecker"),
+    /// 2. split in two, if both halves are dictionary words
+    ///    ("thinthing" → "thin thing"),
+    /// 3. replaced by its best suggestion within `distance`.
+    ///
+    /// Everything between words, punctuation included, is kept as is.
+    /// ```
+    /// use spelling::SpellChecker;
+    /// let checker = SpellChecker::new("a\nthin\nthing\nthings");
+    /// let correction = checker.correct_query("a thng, thinthing th ings", 2);
+    /// assert_eq!(correction.corrected, "a thing, thin thing things");
+    /// assert_eq!(correction.corrections[0].alternatives, vec!["thin", "things"]);
+    /// ```
+    pub fn correct_query(&self, text: &str, distance: usize) -> QueryCorrection<'_> {
+        let tokens: Vec<(usize, &str)> = words(text).collect();
+        let dictionary = self.dictionary();
+        let mut candidates = Vec::new();
+        let mut corrections = Vec::new();
+        let mut index = 0;
+        while index < tokens.len() {
+            let (start, word) = tokens[index];
+            let misspelling = self.check_word(start, word, distance, &mut candidates);
+            if let Some(&(next_start, next)) = tokens.get(index + 1) {
+                let joined = format!("{}{}", word, next);
+                let only_space = text[start + word.len()..next_start].trim().is_empty();
+                if only_space
+                    && (misspelling.is_some() || !dictionary.contains(next))
+                    && dictionary.contains(&joined)
+                {
+                    corrections.push(TokenCorrection {
+                        span: start..next_start + next.len(),
+                        replacement: joined,
+                        alternatives: misspelling.map_or_else(Vec::new, |misspelling| misspelling.suggestions),
+                    });
+                    index += 2;
+                    continue;
+                }
+            }
+            if let Some(misspelling) = misspelling {
+                let mut alternatives = misspelling.suggestions;
+                let split = word
+                    .char_indices()
+                    .skip(1)
+                    .map(|(at, _)| word.split_at(at))
+                    .find(|(first, second)| dictionary.contains(first) && dictionary.contains(second));
+                let replacement = match split {
+                    Some((first, second)) => Some(format!("{} {}", first, second)),
+                    None if !alternatives.is_empty() => Some(alternatives.remove(0).to_owned()),
+                    None => None,
+                };
+                if let Some(replacement) = replacement {
+                    corrections.push(TokenCorrection {
+                        span: misspelling.span,
+                        replacement,
+                        alternatives,
+                    });
+                }
+            }
+            index += 1;
+        }
+
+        let mut corrected = String::with_capacity(text.len());
+        let mut copied = 0;
+        for correction in &corrections {
+            corrected.push_str(&text[copied..correction.span.start]);
+            corrected.push_str(&correction.replacement);
+            copied = correction.span.end;
+        }
+        corrected.push_str(&text[copied..]);
+        QueryCorrection { corrected, corrections }
+    }
+
+    /// The misspelling for `word` at `start`, if it is one, using `candidates`
+    /// as scratch space.
+    pub(crate) fn check_word<'a>(
+        &'a self,
+        start: usize,
+        word: &str,
+        distance: usize,
+        candidates: &mut Vec<Candidate<'a>>,
+    ) -> Option<Misspelling<'a>> {
+        if self.contains(word) {
+            return None;
+        }
+        self.suggest_explained_into(word, distance, candidates);
+        if candidates.iter().any(|candidate| candidate.breakdown.distance == 0) {
+            return None;
+        }
+        Some(Misspelling {
+            span: start..start + word.len(),
+            word: word.to_owned(),
+            suggestions: candidates.iter().map(|candidate| candidate.word).collect(),
+            split: if candidates.is_empty() { self.split_in_two(word) } else { None },
+        })
+    }
+}
+
+/// The iterator returned by [`SpellChecker::check_lines`].
+#[derive(Debug)]
+pub struct CheckLines<'a, M, R> {
+    checker: &'a SpellChecker<M>,
+    reader: R,
+    distance: usize,
+    line: String,
+    line_index: usize,
+    pending: std::collections::VecDeque<Misspelling<'a>>,
+    candidates: Vec<Candidate<'a>>,
+}
+
+impl<'a, M: DistanceMetric, R: std::io::BufRead> Iterator for CheckLines<'a, M, R> {
+    type Item = std::io::Result<(usize, Misspelling<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(misspelling) = self.pending.pop_front() {
+                // the line index has already moved on to the next line
+                return Some(Ok((self.line_index - 1, misspelling)));
+            }
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(error) => return Some(Err(error)),
+            }
+            self.line_index += 1;
+            for (start, word) in words(&self.line) {
+                let misspelling = self.checker.check_word(start, word, self.distance, &mut self.candidates);
+                if let Some(misspelling) = misspelling {
+                    self.pending.push_back(misspelling);
+                }
+            }
+        }
+    }
+}
+
+/// Checks every word in `text` against `dictionary`, like
+/// [`SpellChecker::check_text`] on a checker built from it. Words are split
+/// with [`words`], so contractions stay whole and hyphenated words are
+/// checked part by part. Returns the words that aren't in the dictionary, in
+/// order with byte spans into `text`, and their suggestions within
+/// `max_distance`. Words without suggestions get the likeliest
+/// [`split`](Misspelling::split) into two dictionary words, if there is one.
+///
+/// This builds a [`SpellChecker`] every call, so keep one around instead when
+/// checking more than one text.
+/// ```
+/// use spelling::{check_text, Dictionary};
+/// let dictionary = Dictionary::from_newline_separated("don't\nthe\nthing\nwell\nknown");
+/// let misspellings = check_text("don't, the thng is well-knwon.", &dictionary, 1);
+/// let words: Vec<&str> = misspellings.iter().map(|m| m.word.as_str()).collect();
+/// assert_eq!(words, vec!["thng", "is", "knwon"]);
+/// assert_eq!(misspellings[0].span, 11..15);
+/// assert_eq!(misspellings[0].suggestions, vec!["thing"]);
+/// ```
+pub fn check_text<'a>(text: &str, dictionary: &'a Dictionary, max_distance: usize) -> Vec<Misspelling<'a>> {
+    let checker = SpellChecker::from_dictionary(dictionary.clone());
+    // the checker's words are copies, point suggestions back at `dictionary`
+    let entries: std::collections::HashSet<&'a str> = dictionary.iter().collect();
+    let entry = |word: &str| entries.get(word).copied();
+    checker
+        .check_text(text, max_distance)
+        .into_iter()
+        .map(|misspelling| Misspelling {
+            span: misspelling.span,
+            word: misspelling.word,
+            suggestions: misspelling.suggestions.into_iter().filter_map(entry).collect(),
+            split: misspelling.split.and_then(|(first, second)| Some((entry(first)?, entry(second)?))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    #[test]
+    fn arena_matches_check_text() {
+        let checker = crate::SpellChecker::new("a\nthe\nthin\nthing");
+        let mut arena = crate::MisspellingArena::new();
+        for text in ["thng a thinn thng thethin", "", "the thing", "thng thhing"] {
+            arena.clear();
+            checker.check_text_in(text, 1, &mut arena);
+            let misspellings = checker.check_text(text, 1);
+            assert_eq!(arena.len(), misspellings.len());
+            for (found, expected) in arena.iter().zip(&misspellings) {
+                assert_eq!(found.span, expected.span);
+                assert_eq!(found.suggestions, expected.suggestions.as_slice());
+                assert_eq!(found.split, expected.split);
+            }
+        }
+        assert!(arena.get(2).is_none());
+    }
+
+    /// A document with the same few typos over and over.
+    fn bench_document() -> (crate::SpellChecker, String) {
+        let checker = crate::SpellChecker::new(include_str!("golden/words.txt"));
+        let text = "the restaraunt was seperate, definately the thng we recieve\n".repeat(200);
+        (checker, text)
+    }
+
+    #[bench]
+    fn bench_check_text(bench: &mut test::Bencher) {
+        let (checker, text) = bench_document();
+        bench.iter(|| checker.check_text(&text, 2).len())
+    }
+
+    #[bench]
+    fn bench_check_text_in(bench: &mut test::Bencher) {
+        let (checker, text) = bench_document();
+        let mut arena = crate::MisspellingArena::new();
+        bench.iter(|| {
+            arena.clear();
+            checker.check_text_in(&text, 2, &mut arena);
+            arena.len()
+        })
+    }
+    #[test]
+    fn check_text_free_function() {
+        let dictionary = crate::Dictionary::from_newline_separated("aren't\nthin\nthing\nwell\nknown");
+        let text = "thng aren\u{2019}t well-known thng, aren't";
+        let misspellings = crate::check_text(text, &dictionary, 1);
+        let words: Vec<&str> = misspellings.iter().map(|m| &text[m.span.clone()]).collect();
+        assert_eq!(words, vec!["thng", "aren\u{2019}t", "thng"]);
+        assert_eq!(misspellings[0].suggestions, vec!["thing"]);
+        assert_eq!(misspellings[1].suggestions, vec!["aren't"]);
+        assert_eq!(misspellings[0].split, None);
+        let misspellings = crate::check_text("wellknown thinthing", &dictionary, 1);
+        assert_eq!(misspellings[0].split, Some(("well", "known")));
+        assert_eq!(misspellings[1].split, Some(("thin", "thing")));
+        assert!(crate::check_text("", &dictionary, 1).is_empty());
+        // the same as a checker, splits included
+        let checker = crate::SpellChecker::from_dictionary(dictionary.clone());
+        for text in ["wellknown thinthing", "thng aren\u{2019}t knwn", "thinthingwell"] {
+            assert_eq!(crate::check_text(text, &dictionary, 1), checker.check_text(text, 1));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn sections() {
+        let text = "\n  \none\r\ntwo\n \t\nthree\n\n\nfour";
+        let sections: Vec<&str> = super::sections(text).into_iter().map(|span| &text[span]).collect();
+        assert_eq!(sections, vec!["one\r\ntwo", "three", "four"]);
+        assert!(super::sections("").is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+    fn check_document() {
+        let checker = crate::SpellChecker::new("thin\nthing");
+        let text = "thng\n\n".repeat(50);
+        let sections = checker.check_document(&text, 1);
+        assert_eq!(sections.len(), 50);
+        for (index, section) in sections.iter().enumerate() {
+            assert_eq!(section.span, index * 6..index * 6 + 4);
+            assert_eq!(section.misspellings.len(), 1);
+            assert_eq!(&text[section.misspellings[0].span.clone()], "thng");
+        }
+    }
+
+    #[test]
+    fn correct_query() {
+        let checker = crate::SpellChecker::new("a\nthin\nthing\nthings");
+        let correction = checker.correct_query("thin thing", 1);
+        assert_eq!(correction.corrected, "thin thing");
+        assert!(!correction.is_changed());
+        // merging only happens across whitespace
+        assert_eq!(checker.correct_query("thing-s", 0).corrected, "thing-s");
+        assert_eq!(checker.correct_query("thing s", 0).corrected, "things");
+        let correction = checker.correct_query("qqqq thinthings", 1);
+        assert_eq!(correction.corrected, "qqqq thin things");
+        assert_eq!(correction.corrections.len(), 1);
+        assert_eq!(correction.corrections[0].span, 5..15);
+        assert_eq!(checker.correct_query("", 1).corrected, "");
+    }
+
+    #[test]
+    fn split_in_two() {
+        let mut checker = crate::SpellChecker::with_frequencies(vec![("a", 1), ("ant", 1), ("hill", 1), ("anthill", 1), ("thill", 9)]);
+        let misspellings = checker.check_text("anthills athill", 1);
+        assert_eq!(misspellings[0].suggestions, vec!["anthill"]);
+        assert_eq!(misspellings[0].split, None);
+        // "athill" has a suggestion, "thill", so isn't split
+        assert_eq!(misspellings[1].split, None);
+        assert_eq!(checker.check_text("athill", 0)[0].split, Some(("a", "thill")));
+        checker.add_word("hills");
+        assert_eq!(checker.check_text("anthills", 0)[0].split, Some(("ant", "hills")));
+        checker.ignore("xx");
+        assert_eq!(checker.check_text("xxxx", 1)[0].split, None);
+    }
+
+    #[test]
+    fn check_text_memoizes() {
+        let counters = std::sync::Arc::new(crate::MetricsCounters::default());
+        let checker = crate::SpellChecker::new("thin\nthing").metrics(counters.clone());
+        let text = "thng thin thng thng thinn";
+        let misspellings = checker.check_text(text, 1);
+        let words: Vec<&str> = misspellings.iter().map(|m| &text[m.span.clone()]).collect();
+        assert_eq!(words, vec!["thng", "thng", "thng", "thinn"]);
+        assert_eq!(misspellings[1].suggestions, misspellings[0].suggestions);
+        assert_eq!(misspellings[1].span, 10..14);
+        // "thin" is found by hash lookup, without a query
+        assert_eq!(counters.queries(), 4);
+        assert_eq!(counters.cache_hits(), 2);
+    }
+
+    #[test]
+    fn check_lines() {
+        let checker = crate::SpellChecker::new("thin\nthing");
+        let text = "thin thinng\r\nthng\n\nthing";
+        let found: Vec<_> = checker
+            .check_lines(text.as_bytes(), 1)
+            .map(|result| {
+                let (line, misspelling) = result?;
+                Ok::<_, std::io::Error>((line, misspelling.span, misspelling.suggestions))
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found, vec![(0, 5..11, vec!["thing"]), (1, 0..4, vec!["thing"])]);
+
+        // invalid UTF-8 is an error, not a panic
+        let mut lines = checker.check_lines(&b"thng\n\xff\n"[..], 1);
+        assert!(lines.next().unwrap().is_ok());
+        assert!(lines.next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! Measuring how good a [`SpellChecker`]'s suggestions are over a corpus of
+//! `(typo, correct)` pairs, to compare metrics, rankers and distances.
+
+use crate::check::SpellChecker;
+use crate::distance::DistanceMetric;
+
+/// Runs `checker` on every typo in `pairs` and measures where the correct word
+/// ends up in its suggestions, looking at the first `k` for the `_at_k`
+/// numbers. Pairs whose correct word isn't in the dictionary can't be found
+/// and are only counted in
+/// [`out_of_vocabulary`](Evaluation::out_of_vocabulary).
+/// ```
+/// use spelling::{evaluate, SpellChecker};
+/// let checker = SpellChecker::new("thin\nthing\nthings");
+/// let pairs = vec![("thng", "thing"), ("thinf", "thing"), ("tihngs", "things"), ("thnk", "think")];
+/// let evaluation = evaluate(&checker, pairs, 2, 1);
+/// assert_eq!(evaluation.queries, 3);
+/// assert_eq!(evaluation.out_of_vocabulary, 1);
+/// assert_eq!(evaluation.recall_at_k(), 2.0 / 3.0);
+/// assert_eq!(evaluation.recall(), 1.0);
+/// println!("{}", evaluation);
+/// ```
+pub fn evaluate<'a, M: DistanceMetric>(
+    checker: &SpellChecker<M>,
+    pairs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    distance: usize,
+    k: usize,
+) -> Evaluation {
+    let known: std::collections::HashSet<&str> = checker.dictionary().iter().collect();
+    let mut evaluation = Evaluation {
+        k,
+        ..Evaluation::default()
+    };
+    for (typo, correct) in pairs {
+        if !known.contains(correct) {
+            evaluation.out_of_vocabulary += 1;
+            continue;
+        }
+        evaluation.queries += 1;
+        let suggestions = checker.suggest(typo, distance);
+        let top = &suggestions[..suggestions.len().min(k)];
+        if !top.is_empty() {
+            let hits = top.iter().filter(|&&suggestion| suggestion == correct).count();
+            evaluation.precision_sum += hits as f64 / top.len() as f64;
+        }
+        if let Some(rank) = suggestions.iter().position(|&suggestion| suggestion == correct) {
+            evaluation.found += 1;
+            evaluation.reciprocal_rank_sum += 1.0 / (rank + 1) as f64;
+            if rank < k {
+                evaluation.found_at_k += 1;
+            }
+        }
+    }
+    evaluation
+}
+
+/// The result of [`evaluate`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    /// How many suggestions the `_at_k` numbers look at.
+    pub k: usize,
+    /// How many pairs were checked.
+    pub queries: usize,
+    /// How many pairs were skipped because the correct word isn't in the
+    /// dictionary.
+    pub out_of_vocabulary: usize,
+    /// How many times the correct word was in the first `k` suggestions.
+    pub found_at_k: usize,
+    /// How many times the correct word was suggested at all.
+    pub found: usize,
+    reciprocal_rank_sum: f64,
+    precision_sum: f64,
+}
+
+impl Evaluation {
+    /// The average fraction of the first `k` suggestions that were correct.
+    /// Queries without suggestions count as 0.
+    pub fn precision_at_k(&self) -> f64 {
+        self.average(self.precision_sum)
+    }
+
+    /// The fraction of queries with the correct word in the first `k`
+    /// suggestions.
+    pub fn recall_at_k(&self) -> f64 {
+        self.average(self.found_at_k as f64)
+    }
+
+    /// The fraction of queries with the correct word anywhere in the
+    /// suggestions.
+    pub fn recall(&self) -> f64 {
+        self.average(self.found as f64)
+    }
+
+    /// The mean of 1 / the rank of the correct word, counting 0 when it
+    /// wasn't suggested.
+    pub fn mean_reciprocal_rank(&self) -> f64 {
+        self.average(self.reciprocal_rank_sum)
+    }
+
+    fn average(&self, total: f64) -> f64 {
+        match self.queries {
+            0 => 0.0,
+            queries => total / queries as f64,
+        }
+    }
+}
+
+impl std::fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} queries ({} out of vocabulary): precision@{k} {:.3}, recall@{k} {:.3}, recall {:.3}, MRR {:.3}",
+            self.queries,
+            self.out_of_vocabulary,
+            self.precision_at_k(),
+            self.recall_at_k(),
+            self.recall(),
+            self.mean_reciprocal_rank(),
+            k = self.k,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn evaluate() {
+        let checker = crate::SpellChecker::new("thin\nthing\nthings");
+        let pairs = vec![("thng", "thing"), ("thinf", "thing"), ("qqqqqq", "thin")];
+        let evaluation = crate::evaluate(&checker, pairs, 2, 2);
+        // thng: thing, thin, things; thinf: thin, thing, things; qqqqqq: nothing
+        assert_eq!((evaluation.found_at_k, evaluation.found), (2, 2));
+        assert_eq!(evaluation.precision_at_k(), (0.5 + 0.5) / 3.0);
+        assert_eq!(evaluation.mean_reciprocal_rank(), (1.0 + 0.5) / 3.0);
+        assert_eq!(
+            evaluation.to_string(),
+            "3 queries (0 out of vocabulary): precision@2 0.333, recall@2 0.667, recall 0.667, MRR 0.500"
+        );
+
+        let empty = crate::evaluate(&checker, vec![], 2, 2);
+        assert_eq!(empty.recall(), 0.0);
+    }
+}
@@ -0,0 +1,149 @@
+//! A C API, so C, C++ and anything else with a C FFI can embed the checker.
+//! Build a `libspelling` cdylib with
+//! `cargo rustc --release --features ffi --crate-type cdylib`; the header is
+//! `include/spelling.h`, generated by
+//! [cbindgen](https://github.com/mozilla/cbindgen) with
+//! `cbindgen --config cbindgen.toml --output include/spelling.h`.
+//! ```c
+//! SpellingChecker *checker = spelling_create_checker("thin\nthing\n");
+//! SpellingResults results = spelling_suggest(checker, "thng", 2);
+//! for (size_t i = 0; i < results.len; i++) puts(results.words[i]);
+//! spelling_free_results(results);
+//! spelling_free_checker(checker);
+//! ```
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+/// A spellchecker, see [`SpellChecker`](crate::SpellChecker). Only ever
+/// handled through a pointer.
+#[derive(Debug)]
+pub struct SpellingChecker(crate::SpellChecker);
+
+/// Suggestions returned by [`spelling_suggest`], freed with
+/// [`spelling_free_results`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct SpellingResults {
+    /// `len` nul terminated UTF-8 words, best first, or `NULL` when `len` is 0.
+    pub words: *mut *mut c_char,
+    pub len: usize,
+}
+
+/// Creates a checker from a nul terminated, newline separated word list.
+/// Returns `NULL` if `dictionary` is `NULL` or isn't UTF-8.
+///
+/// # Safety
+/// `dictionary` has to be `NULL` or point to a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn spelling_create_checker(dictionary: *const c_char) -> *mut SpellingChecker {
+    match str_from_ptr(dictionary) {
+        Some(dictionary) => Box::into_raw(Box::new(SpellingChecker(crate::SpellChecker::new(dictionary)))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Frees a checker made by [`spelling_create_checker`]. Does nothing for
+/// `NULL`.
+///
+/// # Safety
+/// `checker` has to be `NULL` or come from [`spelling_create_checker`], and
+/// can't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spelling_free_checker(checker: *mut SpellingChecker) {
+    if !checker.is_null() {
+        drop(Box::from_raw(checker));
+    }
+}
+
+/// The words within `max_distance` of the nul terminated `word`, sorted like
+/// [`SpellChecker::suggest`](crate::SpellChecker::suggest). Empty if either
+/// pointer is `NULL` or `word` isn't UTF-8.
+///
+/// # Safety
+/// `checker` has to be `NULL` or come from [`spelling_create_checker`], and
+/// `word` has to be `NULL` or point to a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn spelling_suggest(
+    checker: *const SpellingChecker,
+    word: *const c_char,
+    max_distance: usize,
+) -> SpellingResults {
+    let suggestions = match (checker.as_ref(), str_from_ptr(word)) {
+        (Some(checker), Some(word)) => checker.0.suggest(word, max_distance),
+        _ => Vec::new(),
+    };
+    if suggestions.is_empty() {
+        return SpellingResults {
+            words: std::ptr::null_mut(),
+            len: 0,
+        };
+    }
+    // dictionary words come from a C string, so they can't have a nul in them
+    let words: Box<[*mut c_char]> = suggestions
+        .into_iter()
+        .filter_map(|word| CString::new(word).ok())
+        .map(CString::into_raw)
+        .collect();
+    let len = words.len();
+    SpellingResults {
+        words: Box::into_raw(words).cast(),
+        len,
+    }
+}
+
+/// Frees the words returned by [`spelling_suggest`].
+///
+/// # Safety
+/// `results` has to come from [`spelling_suggest`], and can't be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spelling_free_results(results: SpellingResults) {
+    if results.words.is_null() {
+        return;
+    }
+    let words = Box::from_raw(std::ptr::slice_from_raw_parts_mut(results.words, results.len));
+    for &word in words.iter() {
+        drop(CString::from_raw(word));
+    }
+}
+
+unsafe fn str_from_ptr<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn words(results: &SpellingResults) -> Vec<&str> {
+        (0..results.len)
+            .map(|index| CStr::from_ptr(*results.words.add(index)).to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ffi() {
+        unsafe {
+            let checker = spelling_create_checker(b"thin\nthing\nthings\0".as_ptr().cast());
+            assert!(!checker.is_null());
+            let results = spelling_suggest(checker, b"thng\0".as_ptr().cast(), 1);
+            assert_eq!(words(&results), vec!["thing"]);
+            spelling_free_results(results);
+
+            let results = spelling_suggest(checker, b"qqqqqq\0".as_ptr().cast(), 1);
+            assert!(results.words.is_null());
+            assert_eq!(results.len, 0);
+            spelling_free_results(results);
+            assert_eq!(spelling_suggest(checker, std::ptr::null(), 1).len, 0);
+            assert_eq!(spelling_suggest(checker, b"\xff\0".as_ptr().cast(), 1).len, 0);
+            spelling_free_checker(checker);
+
+            assert!(spelling_create_checker(std::ptr::null()).is_null());
+            spelling_free_checker(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! A dictionary stored as an [`fst::Set`], for word lists with millions of
+//! entries.
+
+use crate::automaton::LevenshteinAutomaton;
+use crate::dict::Dictionary;
+use crate::index_file::invalid;
+
+/// A dictionary stored as a finite state transducer from the
+/// [`fst`](https://docs.rs/fst) crate, searched with a
+/// [`LevenshteinAutomaton`]. The set is a flat byte buffer, so it can be
+/// saved once and opened from a memory map with
+/// [`from_bytes`](FstDictionary::from_bytes) instead of being rebuilt.
+///
+/// Repeated words are stored once, and suggestions come back sorted by
+/// distance and then by their UTF-8 bytes.
+/// ```
+/// use spelling::FstDictionary;
+/// let dictionary: FstDictionary = "thin\nthing\nthings\nhint".lines().collect();
+/// assert_eq!(dictionary.suggest("thinga", 1), vec!["thing", "things"]);
+/// let reopened = FstDictionary::from_bytes(dictionary.as_bytes().to_vec())?;
+/// assert!(reopened.contains("hint"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct FstDictionary<D = Vec<u8>> {
+    set: fst::Set<D>,
+}
+
+impl<D: AsRef<[u8]>> std::fmt::Debug for FstDictionary<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FstDictionary").field("len", &self.len()).finish()
+    }
+}
+
+impl FstDictionary {
+    /// Builds a dictionary out of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+
+    /// Loads a dictionary saved by [`save`](FstDictionary::save). Fails with
+    /// [`std::io::ErrorKind::InvalidData`] if the file isn't an fst set.
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        FstDictionary::from_bytes(std::fs::read(path)?)
+    }
+}
+
+impl<D: AsRef<[u8]>> FstDictionary<D> {
+    /// Opens a dictionary from the bytes of an fst set, like ones from
+    /// [`as_bytes`](FstDictionary::as_bytes) or a memory mapped file. Fails
+    /// with [`std::io::ErrorKind::InvalidData`] if they aren't one.
+    pub fn from_bytes(data: D) -> std::io::Result<Self> {
+        match fst::Set::new(data) {
+            Ok(set) => Ok(FstDictionary { set }),
+            Err(error) => Err(invalid(&error.to_string())),
+        }
+    }
+
+    /// The bytes of the fst set.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.set.as_fst().as_bytes()
+    }
+
+    /// Saves the fst set to `path`, see [`load`](FstDictionary::load).
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.as_bytes())
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Whether `word` is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.set.contains(word)
+    }
+
+    /// Returns the words within `distance` of `word`, sorted by distance and
+    /// then by their bytes.
+    pub fn suggest(&self, word: &str, distance: usize) -> Vec<String> {
+        use fst::{IntoStreamer, Streamer};
+        let automaton = LevenshteinAutomaton::new(word, distance);
+        let mut stream = self.set.search_with_state(&automaton).into_stream();
+        let mut found = Vec::new();
+        while let Some((key, state)) = stream.next() {
+            // the set only holds words built from `&str`s
+            if let (Ok(key), Some(state)) = (std::str::from_utf8(key), state) {
+                if let Some(distance) = automaton.row_distance(&state.row) {
+                    found.push((distance, key.to_owned()));
+                }
+            }
+        }
+        found.sort_unstable();
+        found.into_iter().map(|(_, word)| word).collect()
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does. The words don't have
+/// to be sorted.
+impl<'a> std::iter::FromIterator<&'a str> for FstDictionary {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut words: Vec<&str> = iter.into_iter().filter(|word| !word.is_empty()).collect();
+        words.sort_unstable();
+        words.dedup();
+        let set = fst::Set::from_iter(words).expect("sorted and deduplicated words can't fail to build");
+        FstDictionary { set }
+    }
+}
+
+/// The state of a [`LevenshteinAutomaton`] reading UTF-8 bytes from an fst,
+/// `None` once the input isn't UTF-8.
+#[derive(Debug, Clone)]
+pub struct FstState {
+    row: Vec<usize>,
+    /// The bytes of a `char` that hasn't been read whole yet.
+    partial: [u8; 4],
+    partial_len: usize,
+}
+
+impl fst::Automaton for LevenshteinAutomaton {
+    type State = Option<FstState>;
+
+    fn start(&self) -> Option<FstState> {
+        Some(FstState {
+            row: LevenshteinAutomaton::start(self).0,
+            partial: [0; 4],
+            partial_len: 0,
+        })
+    }
+
+    fn is_match(&self, state: &Option<FstState>) -> bool {
+        match state {
+            Some(state) => state.partial_len == 0 && self.row_distance(&state.row).is_some(),
+            None => false,
+        }
+    }
+
+    fn can_match(&self, state: &Option<FstState>) -> bool {
+        match state {
+            Some(state) => self.row_can_match(&state.row),
+            None => false,
+        }
+    }
+
+    fn accept(&self, state: &Option<FstState>, byte: u8) -> Option<FstState> {
+        let mut state = state.clone()?;
+        state.partial[state.partial_len] = byte;
+        state.partial_len += 1;
+        let char_len = match state.partial[0] {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return None,
+        };
+        if state.partial_len < char_len {
+            return Some(state);
+        }
+        let c = std::str::from_utf8(&state.partial[..char_len]).ok()?.chars().next()?;
+        let mut row = vec![0; state.row.len()];
+        self.step_into(&state.row, c, &mut row);
+        Some(FstState {
+            row,
+            partial: [0; 4],
+            partial_len: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn fst_dictionary() {
+        let dictionary = crate::FstDictionary::from_dictionary(&crate::Dictionary::from_newline_separated(
+            "thin\nthing\nthin\nnaïve\n\nthings\n漢字",
+        ));
+        assert_eq!(dictionary.len(), 5);
+        assert!(dictionary.contains("naïve"));
+        assert!(!dictionary.contains("thi"));
+        assert_eq!(dictionary.suggest("thin", 0), vec!["thin"]);
+        assert_eq!(dictionary.suggest("naive", 1), vec!["naïve"]);
+        assert_eq!(dictionary.suggest("漢", 1), vec!["漢字"]);
+        assert_eq!(dictionary.suggest("thinga", 2), vec!["thing", "things", "thin"]);
+        assert!(crate::FstDictionary::from_dictionary(&crate::Dictionary::default()).suggest("a", 5).is_empty());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("spelling-fst-{}", std::process::id()));
+        let dictionary: crate::FstDictionary = "thin\nthing".lines().collect();
+        dictionary.save(&path).unwrap();
+        assert_eq!(crate::FstDictionary::load(&path).unwrap().suggest("thinga", 2), vec!["thing", "thin"]);
+        std::fs::write(&path, b"not an fst").unwrap();
+        assert_eq!(crate::FstDictionary::load(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn fst_dictionary_matches_dawg_dictionary(
+            words in proptest::collection::vec("[abé]{1,6}", 1..30),
+            word in "[abé]{0,6}",
+            distance in 0usize..4,
+        ) {
+            let fst: crate::FstDictionary = words.iter().map(String::as_str).collect();
+            let dawg: crate::DawgDictionary = words.iter().map(String::as_str).collect();
+            proptest::prop_assert_eq!(fst.suggest(&word, distance), dawg.suggest(&word, distance));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Golden-file regression tests over `src/golden/words.txt`, a small word
+//! list kept next to the golden files so they don't change with `words.txt`.
+//!
+//! Each query's suggestions are compared to `src/golden/<query>.txt`, and a
+//! missing golden file fails. After an intentional ranking or algorithm
+//! change, or to add a query, rerun with `UPDATE_GOLDEN=1` and review the
+//! diff.
+
+use std::path::PathBuf;
+
+const QUERIES: &[&str] = &["restaraunt", "recieve", "seperate", "definately", "accomodate"];
+const DISTANCE: usize = 2;
+
+fn golden_path(query: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("golden")
+        .join(query)
+        .with_extension("txt")
+}
+
+/// One suggestion per line, followed by a tab and its distance.
+fn render(query: &str) -> String {
+    let dictionary_string = include_str!("golden/words.txt");
+    crate::spellcheck_explained(dictionary_string, query, DISTANCE)
+        .into_iter()
+        .map(|candidate| format!("{}\t{}\n", candidate.word, candidate.breakdown.distance))
+        .collect()
+}
+
+#[test]
+fn golden_suggestions() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    for query in QUERIES {
+        let actual = render(query);
+        let path = golden_path(query);
+        if update {
+            std::fs::write(&path, actual).expect("couldn't write the golden file");
+            continue;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|error| {
+            panic!("couldn't read {}: {}, run with UPDATE_GOLDEN=1 to record it", path.display(), error)
+        });
+        assert_eq!(
+            actual,
+            expected,
+            "suggestions for {:?} changed, rerun with UPDATE_GOLDEN=1 if that's intended",
+            query
+        );
+    }
+}
+
+#[test]
+#[cfg(all(feature = "use_rayon", not(target_arch = "wasm32")))]
+fn golden_suggestions_rayon() {
+    let dictionary_string = include_str!("golden/words.txt");
+    for query in QUERIES {
+        assert_eq!(
+            crate::spellcheck_rayon(dictionary_string, query, DISTANCE),
+            crate::spellcheck(dictionary_string, query, DISTANCE)
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Loading Hunspell dictionaries, a `.dic` word list with a `.aff` file of
+//! affix rules, by expanding every word into the forms its rules allow.
+//!
+//! Covers what most dictionaries use: `PFX` and `SFX` rules with conditions,
+//! cross products, one level of suffix continuation classes, flag aliases
+//! (`AF`), the `FLAG` types, `NEEDAFFIX`, `ONLYINCOMPOUND` and
+//! `FORBIDDENWORD`. Compounding rules are ignored, so compound words are only
+//! accepted if they're listed. Files have to be read as UTF-8, whatever their
+//! `SET` line says.
+
+use crate::dict::Dictionary;
+use std::collections::{HashMap, HashSet};
+
+/// How flags are written, from the `.aff` file's `FLAG` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagType {
+    /// One character each, the default (`UTF-8` is the same for a `&str`).
+    Single,
+    /// Two characters each (`long`).
+    Long,
+    /// Comma separated numbers (`num`).
+    Numeric,
+}
+
+impl FlagType {
+    fn parse(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Single => flags.chars().map(String::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagType::Numeric => flags
+                .split(',')
+                .map(str::trim)
+                .filter(|flag| !flag.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+/// One part of an affix condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    /// `.`
+    Any,
+    /// A character, or a `[...]` or `[^...]` class.
+    Set { negated: bool, chars: Vec<char> },
+}
+
+impl Pattern {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Pattern::Any => true,
+            Pattern::Set { negated, chars } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// Parses a condition like `[^aeiou]y`, `.` means none.
+fn parse_condition(condition: &str) -> Vec<Pattern> {
+    let mut patterns = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => patterns.push(Pattern::Any),
+            '[' => {
+                let mut set: Vec<char> = chars.by_ref().take_while(|&c| c != ']').collect();
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                patterns.push(Pattern::Set { negated, chars: set });
+            }
+            c => patterns.push(Pattern::Set {
+                negated: false,
+                chars: vec![c],
+            }),
+        }
+    }
+    // a lone `.` is "anything", not "at least one character"
+    if patterns == [Pattern::Any] {
+        patterns.clear();
+    }
+    patterns
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    /// Flags for further affixes that can follow this one.
+    continuation: Vec<String>,
+    condition: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AffixGroup {
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+/// A parsed Hunspell dictionary. Turn it into a [`Dictionary`] with
+/// [`dictionary`](Hunspell::dictionary) to use it with a
+/// [`SpellChecker`](crate::SpellChecker).
+/// ```
+/// use spelling::Hunspell;
+/// let aff = "SFX S Y 2\nSFX S 0 s [^y]\nSFX S y ies [^aeiou]y\nPFX U Y 1\nPFX U 0 un .\n";
+/// let dic = "2\ncry/S\nthing/SU\n";
+/// let hunspell = Hunspell::from_dic_aff(dic, aff);
+/// assert_eq!(
+///     hunspell.words(),
+///     vec!["cry", "cries", "thing", "things", "unthing", "unthings"]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hunspell {
+    stems: Vec<(String, Vec<String>)>,
+    prefixes: HashMap<String, AffixGroup>,
+    suffixes: HashMap<String, AffixGroup>,
+    need_affix: Option<String>,
+    only_in_compound: Option<String>,
+    forbidden: Option<String>,
+}
+
+impl Hunspell {
+    /// Parses the contents of a `.dic` and a `.aff` file. Lines that can't be
+    /// understood are skipped.
+    pub fn from_dic_aff(dic: &str, aff: &str) -> Self {
+        let mut hunspell = Hunspell::default();
+        let mut flag_type = FlagType::Single;
+        let mut aliases: Vec<Vec<String>> = Vec::new();
+        let mut aliases_declared = false;
+
+        // flags can only be split once `FLAG` is known, so rule lines are
+        // kept until the end
+        let mut rule_lines = Vec::new();
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", kind, ..] => {
+                    flag_type = match *kind {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Numeric,
+                        _ => FlagType::Single,
+                    }
+                }
+                // the first `AF` line is the count
+                ["AF", flags, ..] if aliases_declared => aliases.push(vec![flags.to_string()]),
+                ["AF", ..] => aliases_declared = true,
+                ["NEEDAFFIX", flag, ..] => hunspell.need_affix = Some(flag.to_string()),
+                ["ONLYINCOMPOUND", flag, ..] => hunspell.only_in_compound = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag, ..] => hunspell.forbidden = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), ..] => rule_lines.push((*kind == "PFX", fields)),
+                _ => {}
+            }
+        }
+        for alias in &mut aliases {
+            *alias = flag_type.parse(&alias[0]);
+        }
+
+        for (prefix, fields) in rule_lines {
+            let groups = if prefix {
+                &mut hunspell.prefixes
+            } else {
+                &mut hunspell.suffixes
+            };
+            match fields.as_slice() {
+                [_, flag, strip, add, rest @ ..] if groups.contains_key(*flag) => {
+                    let (add, continuation) = match add.split_once('/') {
+                        Some((add, flags)) => (add, flags),
+                        None => (*add, ""),
+                    };
+                    let continuation = match continuation.parse::<usize>() {
+                        Ok(index) if !aliases.is_empty() => aliases.get(index.wrapping_sub(1)).cloned().unwrap_or_default(),
+                        _ => flag_type.parse(continuation),
+                    };
+                    let rule = AffixRule {
+                        strip: if *strip == "0" { String::new() } else { strip.to_string() },
+                        add: if add == "0" { String::new() } else { add.to_string() },
+                        continuation,
+                        condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                    };
+                    if let Some(group) = groups.get_mut(*flag) {
+                        group.rules.push(rule);
+                    }
+                }
+                [_, flag, cross_product, ..] => {
+                    groups.insert(
+                        flag.to_string(),
+                        AffixGroup {
+                            cross_product: *cross_product == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let mut lines = dic.lines();
+        // the first line is the approximate word count
+        if let Some(first) = lines.next() {
+            if first.trim().parse::<usize>().is_err() {
+                lines = dic.lines();
+            }
+        }
+        for line in lines {
+            // morphological fields follow a tab or space
+            let entry = line.split(['\t', ' ']).next().unwrap_or("").trim_end_matches('\r');
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            let (word, flags) = split_entry(entry);
+            let flags = match flags.parse::<usize>() {
+                Ok(index) if !aliases.is_empty() => aliases.get(index.wrapping_sub(1)).cloned().unwrap_or_default(),
+                _ => flag_type.parse(flags),
+            };
+            hunspell.stems.push((word, flags));
+        }
+        hunspell
+    }
+
+    /// Reads a `.dic` and a `.aff` file, see
+    /// [`from_dic_aff`](Hunspell::from_dic_aff).
+    pub fn load(dic: impl AsRef<std::path::Path>, aff: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(Hunspell::from_dic_aff(
+            &std::fs::read_to_string(dic)?,
+            &std::fs::read_to_string(aff)?,
+        ))
+    }
+
+    /// The number of words in the `.dic` file, before expanding affixes.
+    pub fn len(&self) -> usize {
+        self.stems.len()
+    }
+
+    /// Whether the `.dic` file had no words.
+    pub fn is_empty(&self) -> bool {
+        self.stems.is_empty()
+    }
+
+    /// Every form of every word, in `.dic` order with each word's forms after
+    /// it. Duplicates are only listed the first time.
+    pub fn words(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        for (stem, flags) in &self.stems {
+            let has = |flag: &Option<String>| flag.as_ref().is_some_and(|flag| flags.contains(flag));
+            if has(&self.forbidden) {
+                continue;
+            }
+            let mut forms = Vec::new();
+            if !has(&self.need_affix) && !has(&self.only_in_compound) {
+                forms.push(stem.clone());
+            }
+            self.expand(stem, flags, &mut forms);
+            for form in forms {
+                if seen.insert(form.clone()) {
+                    out.push(form);
+                }
+            }
+        }
+        out
+    }
+
+    /// Every form of every word as a [`Dictionary`], see
+    /// [`words`](Hunspell::words).
+    pub fn dictionary(&self) -> Dictionary {
+        self.words().into_iter().collect()
+    }
+
+    /// Pushes the affixed forms of `stem` onto `out`.
+    fn expand(&self, stem: &str, flags: &[String], out: &mut Vec<String>) {
+        let mut suffixed = Vec::new();
+        for flag in flags {
+            let group = match self.suffixes.get(flag) {
+                Some(group) => group,
+                None => continue,
+            };
+            for rule in &group.rules {
+                let form = match apply_suffix(rule, stem) {
+                    Some(form) => form,
+                    None => continue,
+                };
+                if !self.needs_more(&rule.continuation) {
+                    out.push(form.clone());
+                }
+                // one more level of suffixes
+                for continuation in &rule.continuation {
+                    for next in self.suffixes.get(continuation).iter().flat_map(|group| &group.rules) {
+                        if let Some(next) = apply_suffix(next, &form) {
+                            out.push(next);
+                        }
+                    }
+                }
+                if group.cross_product {
+                    suffixed.push(form);
+                }
+            }
+        }
+
+        for flag in flags {
+            let group = match self.prefixes.get(flag) {
+                Some(group) => group,
+                None => continue,
+            };
+            for rule in &group.rules {
+                if let Some(form) = apply_prefix(rule, stem) {
+                    if !self.needs_more(&rule.continuation) {
+                        out.push(form);
+                    }
+                }
+                if group.cross_product {
+                    out.extend(suffixed.iter().filter_map(|form| apply_prefix(rule, form)));
+                }
+            }
+        }
+    }
+
+    /// Whether an affix with these continuation flags can't end a word.
+    fn needs_more(&self, continuation: &[String]) -> bool {
+        self.need_affix.as_ref().is_some_and(|flag| continuation.contains(flag))
+    }
+}
+
+/// Splits `word/flags` at the first `/` that isn't escaped as `\/`.
+fn split_entry(entry: &str) -> (String, &str) {
+    let mut word = String::new();
+    let mut chars = entry.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if entry[index + 1..].starts_with('/') => {
+                word.push('/');
+                chars.next();
+            }
+            '/' => return (word, &entry[index + 1..]),
+            c => word.push(c),
+        }
+    }
+    (word, "")
+}
+
+fn apply_suffix(rule: &AffixRule, word: &str) -> Option<String> {
+    let base = word.strip_suffix(rule.strip.as_str())?;
+    let chars: Vec<char> = word.chars().collect();
+    let start = chars.len().checked_sub(rule.condition.len())?;
+    let matches = rule.condition.iter().zip(&chars[start..]).all(|(pattern, &c)| pattern.matches(c));
+    if !matches || base.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", base, rule.add))
+}
+
+fn apply_prefix(rule: &AffixRule, word: &str) -> Option<String> {
+    let base = word.strip_prefix(rule.strip.as_str())?;
+    let matches = rule.condition.len() <= word.chars().count()
+        && rule.condition.iter().zip(word.chars()).all(|(pattern, c)| pattern.matches(c));
+    if !matches || base.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", rule.add, base))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn conditions() {
+        use super::{parse_condition, Pattern};
+        assert!(parse_condition(".").is_empty());
+        assert_eq!(
+            parse_condition("[^aeiou]y"),
+            vec![
+                Pattern::Set { negated: true, chars: vec!['a', 'e', 'i', 'o', 'u'] },
+                Pattern::Set { negated: false, chars: vec!['y'] },
+            ]
+        );
+        assert_eq!(parse_condition(".[ae]").len(), 2);
+    }
+
+    #[test]
+    fn expand() {
+        let aff = "\
+# comment
+FLAG long
+NEEDAFFIX nn
+FORBIDDENWORD !!
+SFX Dd Y 3
+SFX Dd 0 d e
+SFX Dd y ied [^aeiou]y
+SFX Dd 0 ed [^ey]
+SFX Ee N 1
+SFX Ee 0 er/Ss .
+SFX Ss N 1
+SFX Ss 0 s .
+PFX Rr Y 1
+PFX Rr 0 re .
+";
+        let dic = "5\ncreate/DdRr\ncry/Dd\nplay/Dd\nwork/nnEe\nbad/!!\n";
+        let hunspell = crate::Hunspell::from_dic_aff(dic, aff);
+        assert_eq!(hunspell.len(), 5);
+        assert_eq!(
+            hunspell.words(),
+            vec!["create", "created", "recreate", "recreated", "cry", "cried", "play", "worker", "workers"]
+        );
+        #[cfg(feature = "check")]
+        {
+            let checker = crate::SpellChecker::from_dictionary(hunspell.dictionary());
+            assert_eq!(checker.suggest("recreatd", 1), vec!["recreate", "recreated"]);
+        }
+    }
+
+    #[test]
+    fn aliases_and_numeric_flags() {
+        let aff = "FLAG num\nAF 2\nAF 1,2\nAF 2\nSFX 1 Y 1\nSFX 1 0 s .\nSFX 2 Y 1\nSFX 2 0 er .\n";
+        let dic = "2\nthing/1 st:thing\nwalk/2\tpo:verb\nescaped\\/slash\n";
+        let hunspell = crate::Hunspell::from_dic_aff(dic, aff);
+        assert_eq!(
+            hunspell.words(),
+            vec!["thing", "things", "thinger", "walk", "walker", "escaped/slash"]
+        );
+    }
+}
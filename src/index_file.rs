@@ -0,0 +1,161 @@
+//! The binary format [`BkTree`](crate::BkTree) and
+//! [`SymSpellIndex`](crate::SymSpellIndex) are saved in: a magic string, a
+//! format version, then little-endian integers and length-prefixed UTF-8.
+
+use std::convert::TryFrom;
+use std::io;
+
+/// Bumped whenever the layout of any index file changes.
+pub(crate) const VERSION: u32 = 1;
+
+/// Builds up an index file in memory.
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn new(magic: &[u8; 8]) -> Self {
+        let mut writer = Writer { bytes: magic.to_vec() };
+        writer.u32(VERSION);
+        writer
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Lengths and indices are stored as `u32`, these files aren't meant for
+    /// more than four billion words.
+    pub(crate) fn usize(&mut self, value: usize) -> io::Result<()> {
+        let value = u32::try_from(value).map_err(|_| invalid("too large for an index file"))?;
+        self.u32(value);
+        Ok(())
+    }
+
+    pub(crate) fn str(&mut self, value: &str) -> io::Result<()> {
+        self.usize(value.len())?;
+        self.bytes.extend_from_slice(value.as_bytes());
+        Ok(())
+    }
+
+    /// Writes the file next to `path` and renames it into place, so readers
+    /// never see half of it.
+    pub(crate) fn save(self, path: &std::path::Path) -> io::Result<()> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, self.bytes)?;
+        std::fs::rename(&temporary, path)
+    }
+}
+
+/// Reads an index file, failing with [`io::ErrorKind::InvalidData`] instead
+/// of panicking on anything malformed.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8], magic: &[u8; 8]) -> io::Result<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(magic.len())? != magic {
+            return Err(invalid("not an index file of this kind"));
+        }
+        if reader.u32()? != VERSION {
+            return Err(invalid("unsupported index file version"));
+        }
+        Ok(reader)
+    }
+
+    fn take(&mut self, length: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < length {
+            return Err(invalid("index file is truncated"));
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub(crate) fn u32(&mut self) -> io::Result<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn usize(&mut self) -> io::Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    /// A `usize` that has to be below `limit`, like an index into something
+    /// already read.
+    pub(crate) fn index(&mut self, limit: usize) -> io::Result<usize> {
+        let index = self.usize()?;
+        if index >= limit {
+            return Err(invalid("index out of range in index file"));
+        }
+        Ok(index)
+    }
+
+    /// A count of items that each take at least `item_size` bytes, checked
+    /// against what's left so a corrupt count can't cause a huge allocation.
+    pub(crate) fn count(&mut self, item_size: usize) -> io::Result<usize> {
+        let count = self.usize()?;
+        if count.saturating_mul(item_size) > self.bytes.len() {
+            return Err(invalid("index file is truncated"));
+        }
+        Ok(count)
+    }
+
+    pub(crate) fn str(&mut self) -> io::Result<&'a str> {
+        let length = self.usize()?;
+        std::str::from_utf8(self.take(length)?).map_err(|_| invalid("index file has invalid UTF-8"))
+    }
+
+    /// Fails unless everything has been read.
+    pub(crate) fn finish(self) -> io::Result<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid("trailing data in index file"))
+        }
+    }
+}
+
+pub(crate) fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn round_trip() {
+        use super::{Reader, Writer};
+        let mut writer = Writer::new(b"TESTTEST");
+        writer.u64(u64::MAX);
+        writer.str("thïng").unwrap();
+        writer.usize(3).unwrap();
+        let bytes = writer.bytes.clone();
+
+        let mut reader = Reader::new(&bytes, b"TESTTEST").unwrap();
+        assert_eq!(reader.u64().unwrap(), u64::MAX);
+        assert_eq!(reader.str().unwrap(), "thïng");
+        assert!(reader.index(3).is_err());
+        reader.finish().unwrap();
+
+        assert!(Reader::new(&bytes, b"OTHEROTH").is_err());
+        let mut reader = Reader::new(&bytes[..bytes.len() - 2], b"TESTTEST").unwrap();
+        reader.u64().unwrap();
+        assert!(reader.str().is_ok() && reader.usize().is_err());
+        let mut reader = Reader::new(&bytes, b"TESTTEST").unwrap();
+        assert!(reader.count(1).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Keyboard layouts, for ranking typos between neighbouring keys higher.
+
+use crate::check::Pipeline;
+use crate::rank::{Candidate, Ranker, ScoreRanker};
+
+/// How far each row is shifted right, in keys, on a staggered keyboard:
+/// the number row, then the top, home and bottom letter rows.
+const ROW_OFFSETS: [f64; 4] = [0.0, 0.5, 0.75, 1.25];
+
+/// How much cheaper a substitution between neighbouring keys is than any other
+/// substitution, see [`KeyboardRanker`].
+pub const KEYBOARD_ADJACENT_BONUS: f64 = 0.4;
+
+/// Where the keys are on a keyboard, to tell which ones are next to each
+/// other. Keys are compared in lowercase.
+/// ```
+/// use spelling::KeyboardLayout;
+/// let qwerty = KeyboardLayout::qwerty();
+/// assert!(qwerty.is_adjacent('s', 'a') && qwerty.is_adjacent('S', 'w'));
+/// assert!(!qwerty.is_adjacent('s', 'u') && !qwerty.is_adjacent('s', 's'));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardLayout {
+    /// The row of each key and how far along the row it is, stagger included.
+    keys: std::collections::HashMap<char, (usize, f64)>,
+}
+
+impl KeyboardLayout {
+    /// Reads a layout from its rows, top to bottom and newline separated,
+    /// starting with the number row. Rows are staggered like a standard
+    /// keyboard, rows after the fourth keep shifting by half a key.
+    /// ```
+    /// use spelling::KeyboardLayout;
+    /// let layout = KeyboardLayout::from_rows("1234567890\nqwertyuiop\nasdfghjkl\nzxcvbnm");
+    /// assert_eq!(layout, KeyboardLayout::qwerty().without_punctuation());
+    /// ```
+    pub fn from_rows(rows: &str) -> Self {
+        let mut keys = std::collections::HashMap::new();
+        for (row, line) in rows.lines().enumerate() {
+            let last = ROW_OFFSETS.len() - 1;
+            let offset = match ROW_OFFSETS.get(row) {
+                Some(&offset) => offset,
+                None => ROW_OFFSETS[last] + 0.5 * (row - last) as f64,
+            };
+            for (column, key) in line.trim_end_matches('\r').chars().enumerate() {
+                keys.entry(lowercase(key)).or_insert((row, offset + column as f64));
+            }
+        }
+        KeyboardLayout { keys }
+    }
+
+    /// US QWERTY.
+    pub fn qwerty() -> Self {
+        KeyboardLayout::from_rows("1234567890-=\nqwertyuiop[]\\\nasdfghjkl;'\nzxcvbnm,./")
+    }
+
+    /// French AZERTY.
+    pub fn azerty() -> Self {
+        KeyboardLayout::from_rows("&é\"'(-è_çà)=\nazertyuiop^$\nqsdfghjklmù*\nwxcvbn,;:!")
+    }
+
+    /// US Dvorak.
+    pub fn dvorak() -> Self {
+        KeyboardLayout::from_rows("1234567890[]\n',.pyfgcrl/=\\\naoeuidhtns-\n;qjkxbmwvz")
+    }
+
+    /// The same layout with only letters and digits.
+    pub fn without_punctuation(mut self) -> Self {
+        self.keys.retain(|key, _| key.is_alphanumeric());
+        self
+    }
+
+    /// Whether `a` and `b` are different keys that touch, on the same row or
+    /// the rows above and below.
+    pub fn is_adjacent(&self, a: char, b: char) -> bool {
+        match (self.keys.get(&lowercase(a)), self.keys.get(&lowercase(b))) {
+            (Some(&(a_row, a_position)), Some(&(b_row, b_position))) => {
+                let distance = (a_position - b_position).abs();
+                if a_row == b_row {
+                    distance == 1.0
+                } else {
+                    a_row.max(b_row) - a_row.min(b_row) == 1 && distance < 1.0
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// The Levenshtein distance between `a` and `b`, and among the cheapest
+    /// ways to get there, the most substitutions between neighbouring keys.
+    /// ```
+    /// use spelling::KeyboardLayout;
+    /// let qwerty = KeyboardLayout::qwerty();
+    /// assert_eq!(qwerty.adjacent_substitutions("cst", "cat"), (1, 1));
+    /// assert_eq!(qwerty.adjacent_substitutions("cst", "cut"), (1, 0));
+    /// ```
+    pub fn adjacent_substitutions(&self, a: &str, b: &str) -> (usize, usize) {
+        use std::cmp::Reverse;
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut list: Vec<(usize, Reverse<usize>)> = (0..(b.len() + 1)).map(|index| (index, Reverse(0))).collect();
+        for (x, &a_char) in a.iter().enumerate() {
+            let mut temp = Vec::with_capacity(b.len() + 1);
+            temp.push((x + 1, Reverse(0)));
+            for index in 1..(b.len() + 1) {
+                let (edits, Reverse(adjacent)) = list[index - 1];
+                let substitution = if a_char == b[index - 1] {
+                    (edits, Reverse(adjacent))
+                } else if self.is_adjacent(a_char, b[index - 1]) {
+                    (edits + 1, Reverse(adjacent + 1))
+                } else {
+                    (edits + 1, Reverse(adjacent))
+                };
+                let insertion = (temp[index - 1].0 + 1, temp[index - 1].1);
+                let deletion = (list[index].0 + 1, list[index].1);
+                temp.push(substitution.min(insertion).min(deletion));
+            }
+            list = temp;
+        }
+        let (edits, Reverse(adjacent)) = list[b.len()];
+        (edits, adjacent)
+    }
+}
+
+/// Lowercases `c` if that gives a single `char`.
+fn lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
+/// Sets [`ScoreBreakdown::keyboard_bonus`](crate::ScoreBreakdown::keyboard_bonus)
+/// to [`KEYBOARD_ADJACENT_BONUS`] for each substitution between neighbouring
+/// keys, then sorts by score. A typo on the next key over ranks above other
+/// typos at the same distance.
+#[derive(Debug, Clone)]
+pub struct KeyboardRanker(pub KeyboardLayout);
+
+impl Ranker for KeyboardRanker {
+    fn rank(&self, word: &str, candidates: &mut [Candidate]) {
+        for candidate in candidates.iter_mut() {
+            let (_, adjacent) = self.0.adjacent_substitutions(word, candidate.word);
+            candidate.breakdown.keyboard_bonus = Some(adjacent as f64 * KEYBOARD_ADJACENT_BONUS);
+        }
+        ScoreRanker.rank(word, candidates);
+    }
+}
+
+/// Works like [`spellcheck`](crate::spellcheck), but ranks suggestions with a
+/// substitution between neighbouring keys on `layout` above others at the
+/// same distance, see [`KeyboardRanker`].
+/// ```
+/// use spelling::{spellcheck_keyboard_aware, KeyboardLayout};
+/// let dictionary_string = "cut\ncat\ncot";
+/// assert_eq!(spellcheck_keyboard_aware(dictionary_string, "cst", 1, &KeyboardLayout::qwerty()), vec!["cat", "cut", "cot"]);
+/// ```
+pub fn spellcheck_keyboard_aware<'a>(
+    dictionary_string: &'a str,
+    word: &str,
+    distance: usize,
+    layout: &KeyboardLayout,
+) -> Vec<&'a str> {
+    Pipeline::builder()
+        .ranker(KeyboardRanker(layout.clone()))
+        .build()
+        .run(dictionary_string.split('\n'), word, distance)
+        .into_iter()
+        .map(|candidate| candidate.word)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn layouts() {
+        use crate::KeyboardLayout;
+        let qwerty = KeyboardLayout::qwerty();
+        for (a, b) in [('q', '1'), ('q', '2'), ('q', 'a'), ('a', 'z'), ('z', 'x'), ('m', 'k'), ('p', '[')] {
+            assert!(qwerty.is_adjacent(a, b), "{} {}", a, b);
+            assert!(qwerty.is_adjacent(b, a), "{} {}", b, a);
+        }
+        for (a, b) in [('q', 's'), ('a', 'x'), ('z', 'q'), ('é', 'e'), ('1', 'a')] {
+            assert!(!qwerty.is_adjacent(a, b), "{} {}", a, b);
+        }
+        let azerty = KeyboardLayout::azerty();
+        assert!(azerty.is_adjacent('a', 'q') && azerty.is_adjacent('é', 'z') && !azerty.is_adjacent('a', 's'));
+        let dvorak = KeyboardLayout::dvorak();
+        assert!(dvorak.is_adjacent('a', 'o') && !dvorak.is_adjacent('a', 's'));
+        let custom = KeyboardLayout::from_rows("\nab\r\ncd\n\nef");
+        assert!(custom.is_adjacent('a', 'c') && custom.is_adjacent('b', 'd') && custom.is_adjacent('B', 'C'));
+        assert!(!custom.is_adjacent('c', 'e'));
+    }
+
+    #[test]
+    fn keyboard_aware() {
+        let qwerty = crate::KeyboardLayout::qwerty();
+        assert_eq!(qwerty.adjacent_substitutions("", "cat"), (3, 0));
+        assert_eq!(qwerty.adjacent_substitutions("xsy", "cat"), (3, 3));
+        let dictionary_string = "cut\ncats\ncat";
+        assert_eq!(crate::spellcheck_keyboard_aware(dictionary_string, "cst", 2, &qwerty), vec!["cat", "cut", "cats"]);
+        assert_eq!(crate::spellcheck(dictionary_string, "cst", 2), vec!["cut", "cat", "cats"]);
+    }
+}
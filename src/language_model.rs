@@ -0,0 +1,277 @@
+//! A bigram language model, for catching real-word errors: words that are in
+//! the dictionary but wrong where they are, like "their" in "over their".
+
+use crate::check::SpellChecker;
+use crate::distance::DistanceMetric;
+use crate::document::Misspelling;
+use crate::tokenize::words;
+
+/// How much of a bigram probability comes from the bigram counts, the rest
+/// coming from the unigram counts.
+const BIGRAM_WEIGHT: f64 = 0.9;
+
+/// Counts of words and of pairs of words next to each other, compared
+/// without case. Probabilities interpolate the bigram counts with add-one
+/// smoothed unigram counts, so unseen pairs and words are unlikely but never
+/// impossible.
+/// ```
+/// use spelling::BigramModel;
+/// let model = BigramModel::from_counts("over there 20\nover their 1\nthere 100\ntheir 100");
+/// assert!(model.probability(Some("over"), "there") > model.probability(Some("over"), "their"));
+/// assert_eq!(model.probability(None, "there"), model.probability(None, "their"));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BigramModel {
+    unigrams: std::collections::HashMap<String, u64>,
+    unigram_total: u64,
+    /// The words following each word, and how often.
+    bigrams: std::collections::HashMap<String, std::collections::HashMap<String, u64>>,
+    /// How often each word is followed by anything.
+    contexts: std::collections::HashMap<String, u64>,
+    min_ratio: f64,
+}
+
+impl Default for BigramModel {
+    fn default() -> Self {
+        BigramModel {
+            unigrams: Default::default(),
+            unigram_total: 0,
+            bigrams: Default::default(),
+            contexts: Default::default(),
+            min_ratio: 10.0,
+        }
+    }
+}
+
+impl BigramModel {
+    /// An empty model.
+    pub fn new() -> Self {
+        BigramModel::default()
+    }
+
+    /// Parses lines of `first second count` for bigrams and `word count` for
+    /// unigrams, separated by whitespace. Blank lines and lines whose count
+    /// isn't a number are skipped.
+    pub fn from_counts(counts: &str) -> Self {
+        let mut model = BigramModel::new();
+        for line in counts.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [word, count] => {
+                    if let Ok(count) = count.parse() {
+                        model.add_unigram(word, count);
+                    }
+                }
+                [first, second, count] => {
+                    if let Ok(count) = count.parse() {
+                        model.add_bigram(first, second, count);
+                    }
+                }
+                _ => {}
+            }
+        }
+        model
+    }
+
+    /// Reads the file at `path`, see [`from_counts`](BigramModel::from_counts).
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(BigramModel::from_counts(&std::fs::read_to_string(path)?))
+    }
+
+    /// Counts the words in `text` and the pairs of them in the same sentence,
+    /// adding to the counts so far.
+    /// ```
+    /// use spelling::BigramModel;
+    /// let mut model = BigramModel::new();
+    /// model.train("It's over there. There it is.");
+    /// assert_eq!(model.bigram_count("over", "there"), 1);
+    /// assert_eq!(model.bigram_count("there", "there"), 0);
+    /// assert_eq!(model.unigram_count("THERE"), 2);
+    /// ```
+    pub fn train(&mut self, text: &str) {
+        let mut previous: Option<(usize, &str)> = None;
+        for (start, word) in words(text) {
+            self.add_unigram(word, 1);
+            if let Some((end, previous)) = previous {
+                if !ends_sentence(&text[end..start]) {
+                    self.add_bigram(previous, word, 1);
+                }
+            }
+            previous = Some((start + word.len(), word));
+        }
+    }
+
+    /// Adds `count` occurrences of `word`.
+    pub fn add_unigram(&mut self, word: &str, count: u64) {
+        *self.unigrams.entry(word.to_lowercase()).or_default() += count;
+        self.unigram_total += count;
+    }
+
+    /// Adds `count` occurrences of `first` followed by `second`.
+    pub fn add_bigram(&mut self, first: &str, second: &str, count: u64) {
+        let first = first.to_lowercase();
+        *self
+            .bigrams
+            .entry(first.clone())
+            .or_default()
+            .entry(second.to_lowercase())
+            .or_default() += count;
+        *self.contexts.entry(first).or_default() += count;
+    }
+
+    /// How often `word` was seen.
+    pub fn unigram_count(&self, word: &str) -> u64 {
+        self.unigrams.get(&word.to_lowercase()).copied().unwrap_or(0)
+    }
+
+    /// How often `first` was seen followed by `second`.
+    pub fn bigram_count(&self, first: &str, second: &str) -> u64 {
+        self.bigrams
+            .get(&first.to_lowercase())
+            .and_then(|following| following.get(&second.to_lowercase()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The probability of `word` after `previous`, or on its own without one.
+    pub fn probability(&self, previous: Option<&str>, word: &str) -> f64 {
+        let vocabulary = self.unigrams.len() as f64 + 1.0;
+        let unigram = (self.unigram_count(word) as f64 + 1.0) / (self.unigram_total as f64 + vocabulary);
+        let previous = match previous {
+            Some(previous) => previous,
+            None => return unigram,
+        };
+        let context = self.contexts.get(&previous.to_lowercase()).copied().unwrap_or(0);
+        if context == 0 {
+            return unigram;
+        }
+        let bigram = self.bigram_count(previous, word) as f64 / context as f64;
+        BIGRAM_WEIGHT * bigram + (1.0 - BIGRAM_WEIGHT) * unigram
+    }
+
+    /// How much likelier an alternative has to be for
+    /// [`SpellChecker::check_context`] to flag a word, builder style.
+    /// Defaults to 10.
+    pub fn min_ratio(mut self, min_ratio: f64) -> Self {
+        self.min_ratio = min_ratio;
+        self
+    }
+
+    /// Sets how much likelier an alternative has to be, see
+    /// [`min_ratio`](BigramModel::min_ratio).
+    pub fn set_min_ratio(&mut self, min_ratio: f64) {
+        self.min_ratio = min_ratio;
+    }
+
+    /// The log probability of `word` between `previous` and `next`.
+    fn log_score(&self, previous: Option<&str>, word: &str, next: Option<&str>) -> f64 {
+        let mut score = self.probability(previous, word).ln();
+        if let Some(next) = next {
+            score += self.probability(Some(word), next).ln();
+        }
+        score
+    }
+}
+
+/// Whether the text between two words ends a sentence.
+fn ends_sentence(between: &str) -> bool {
+    between.contains(['.', '!', '?', '\n'])
+}
+
+impl<M: DistanceMetric> SpellChecker<M> {
+    /// Finds words that are in the dictionary but unlikely next to their
+    /// neighbours under `model`, like "their" in "over their". A word is
+    /// flagged if one of its [`suggest`](SpellChecker::suggest)ions within
+    /// `distance` is at least [`min_ratio`](BigramModel::min_ratio) times as
+    /// likely in its place, and the likelier ones are suggested, likeliest
+    /// first. Words not in the dictionary are left to
+    /// [`check_text`](SpellChecker::check_text), and sentences are checked
+    /// separately.
+    /// ```
+    /// use spelling::{BigramModel, SpellChecker};
+    /// let checker = SpellChecker::new("it\nis\nover\nthere\ntheir\nhouse");
+    /// let mut model = BigramModel::new();
+    /// model.train("It is over there. Their house is over there. There is their house.");
+    /// let errors = checker.check_context("Their house is over their.", &model, 2);
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!((errors[0].span.clone(), errors[0].suggestions.clone()), (20..25, vec!["there"]));
+    /// ```
+    pub fn check_context(&self, text: &str, model: &BigramModel, distance: usize) -> Vec<Misspelling<'_>> {
+        let tokens: Vec<(usize, &str)> = words(text).collect();
+        let neighbour = |index: usize, other: Option<usize>| {
+            let other = other?;
+            let &(other_start, other_word) = tokens.get(other)?;
+            let &(start, word) = &tokens[index];
+            let between = if other < index {
+                &text[other_start + other_word.len()..start]
+            } else {
+                &text[start + word.len()..other_start]
+            };
+            (!ends_sentence(between)).then_some(other_word)
+        };
+        let mut errors = Vec::new();
+        for (index, &(start, word)) in tokens.iter().enumerate() {
+            if !self.contains(word) {
+                continue;
+            }
+            let previous = neighbour(index, index.checked_sub(1));
+            let next = neighbour(index, Some(index + 1));
+            let score = model.log_score(previous, word, next);
+            let min_score = score + model.min_ratio.ln();
+            let mut better: Vec<(f64, &str)> = self
+                .suggest(word, distance)
+                .into_iter()
+                .filter(|alternative| !alternative.eq_ignore_ascii_case(word))
+                .map(|alternative| (model.log_score(previous, alternative, next), alternative))
+                .filter(|&(alternative_score, _)| alternative_score >= min_score)
+                .collect();
+            if better.is_empty() {
+                continue;
+            }
+            // stable, so ties keep the checker's ranking
+            better.sort_by(|a, b| b.0.total_cmp(&a.0));
+            errors.push(Misspelling {
+                span: start..start + word.len(),
+                word: word.to_owned(),
+                suggestions: better.into_iter().map(|(_, alternative)| alternative).collect(),
+                split: None,
+            });
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bigram_model() {
+        let model = crate::BigramModel::from_counts("a b 3\n\nA c 1\nb 4\nc x\nnot a line at all");
+        assert_eq!(model.bigram_count("a", "B"), 3);
+        assert_eq!(model.bigram_count("a", "c"), 1);
+        assert_eq!(model.unigram_count("b"), 4);
+        assert_eq!(model.unigram_count("c"), 0);
+        let b = model.probability(Some("a"), "b");
+        assert!((b - (0.9 * 0.75 + 0.1 * 5.0 / 6.0)).abs() < 1e-12);
+        // an unseen context falls back to the unigrams
+        assert_eq!(model.probability(Some("z"), "b"), model.probability(None, "b"));
+        assert!(crate::BigramModel::new().probability(None, "a") > 0.0);
+    }
+
+    #[test]
+    fn check_context() {
+        let checker = crate::SpellChecker::new("i\nwant\nto\ntoo\ntwo\ngo\nthere\nteh");
+        let mut model = crate::BigramModel::new();
+        model.train("I want to go. I want two. Want to go there.");
+        let model = model.min_ratio(5.0);
+        let text = "I want too go. I two go there! I want two. Teh";
+        let errors = checker.check_context(text, &model, 1);
+        let words: Vec<&str> = errors.iter().map(|error| &text[error.span.clone()]).collect();
+        assert_eq!(words, vec!["too", "two"]);
+        assert_eq!(errors[0].suggestions, vec!["to", "two"]);
+        assert_eq!(errors[1].suggestions, vec!["to"]);
+        // a high enough ratio flags nothing
+        assert!(checker.check_context(text, &model.min_ratio(1e4), 1).is_empty());
+        assert!(checker.check_context("", &crate::BigramModel::new(), 2).is_empty());
+    }
+}
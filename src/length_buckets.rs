@@ -0,0 +1,114 @@
+//! A dictionary grouped by word length, so queries skip words too long or too
+//! short to be close.
+
+use crate::dict::Dictionary;
+use crate::distance::{levenshtein_distance_bounded_in, DistanceScratch};
+
+/// Words grouped by how many characters they have. Inserting or deleting a
+/// character changes the length by one, so a word within `distance` of the
+/// query is at most `distance` characters longer or shorter, and
+/// [`suggest`](LengthBucketedDictionary::suggest) only measures the words in
+/// those buckets.
+/// ```
+/// use spelling::LengthBucketedDictionary;
+/// let dictionary: LengthBucketedDictionary = "a\nthin\nthing\nsomething".lines().collect();
+/// assert_eq!(dictionary.suggest("thinga", 2), vec!["thing", "thin"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LengthBucketedDictionary {
+    words: Vec<String>,
+    /// The indices of the words with each length in characters, in order.
+    buckets: Vec<Vec<usize>>,
+}
+
+impl LengthBucketedDictionary {
+    /// Groups the words of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+
+    /// The number of words, repeats included.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Adds `word` after every word already in the dictionary.
+    pub fn insert(&mut self, word: &str) {
+        let length = word.chars().count();
+        if self.buckets.len() <= length {
+            self.buckets.resize_with(length + 1, Vec::new);
+        }
+        self.buckets[length].push(self.words.len());
+        self.words.push(word.to_owned());
+    }
+
+    /// Returns the words within `distance` of `word`, sorted by distance with
+    /// ties in insertion order, the same as [`spellcheck`](crate::spellcheck)
+    /// over the same words.
+    pub fn suggest(&self, word: &str, distance: usize) -> Vec<&str> {
+        let length = word.chars().count();
+        let shortest = length.saturating_sub(distance);
+        let longest = length.saturating_add(distance).min(self.buckets.len().saturating_sub(1));
+        let mut scratch = DistanceScratch::new();
+        let mut found: Vec<(usize, usize)> = Vec::new();
+        for bucket in self.buckets.iter().take(longest + 1).skip(shortest) {
+            found.extend(bucket.iter().filter_map(|&index| {
+                levenshtein_distance_bounded_in(&mut scratch, word, &self.words[index], distance)
+                    .within()
+                    .map(|distance| (distance, index))
+            }));
+        }
+        found.sort_unstable();
+        found.into_iter().map(|(_, index)| self.words[index].as_str()).collect()
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does.
+impl<'a> std::iter::FromIterator<&'a str> for LengthBucketedDictionary {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut dictionary = LengthBucketedDictionary::default();
+        for word in iter.into_iter().filter(|word| !word.is_empty()) {
+            dictionary.insert(word);
+        }
+        dictionary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn length_buckets() {
+        let dictionary = crate::LengthBucketedDictionary::from_dictionary(&crate::Dictionary::from_newline_separated(
+            "thin\nthing\nthin\nnaïve\n\nthings",
+        ));
+        assert_eq!(dictionary.len(), 5);
+        assert_eq!(dictionary.suggest("thin", 0), vec!["thin", "thin"]);
+        assert_eq!(dictionary.suggest("naive", 1), vec!["naïve"]);
+        assert_eq!(dictionary.suggest("thinga", 2), vec!["thing", "things", "thin", "thin"]);
+        assert!(dictionary.suggest("thinthingthings", 3).is_empty());
+        assert!(crate::LengthBucketedDictionary::default().suggest("a", 5).is_empty());
+    }
+
+    #[cfg(feature = "check")]
+    proptest::proptest! {
+        #[test]
+        fn length_buckets_match_spellcheck(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            word in "[abc]{0,8}",
+            distance in 0usize..4,
+        ) {
+            let dictionary: crate::LengthBucketedDictionary = words.iter().map(String::as_str).collect();
+            let dictionary_string = words.join("\n");
+            proptest::prop_assert_eq!(
+                dictionary.suggest(&word, distance),
+                crate::spellcheck(&dictionary_string, &word, distance)
+            );
+        }
+    }
+}
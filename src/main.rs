@@ -0,0 +1,178 @@
+use std::io::Write;
+
+const USAGE: &str = "\
+usage: spelling [-d FILE] [--max-distance N] [--max-word-length N] [--json] [--ignore-file FILE]
+                [--cache FILE] FILE...
+       spelling -a [-d FILE] [--max-distance N] [--max-word-length N]
+
+  -d FILE              newline separated word list to check against
+  --max-distance N     suggest words up to N edits away, defaults to 2
+  --max-word-length N  don't suggest anything for words longer than N chars,
+                       defaults to 64
+  --json               print the misspellings as a JSON array
+  --ignore-file FILE   newline separated words to never report
+  --cache FILE         keep suggestions in FILE between runs
+  -a                   run in Ispell/Aspell pipe mode on stdin and stdout
+
+Exits with 1 if any file has a misspelling.";
+
+/// How many suggestions are printed for each misspelling.
+const TOP_SUGGESTIONS: usize = 3;
+
+fn main() {
+    match run() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(message) => {
+            let _ = writeln!(std::io::stderr(), "spelling: {}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Returns whether every file was spelled correctly.
+fn run() -> Result<bool, String> {
+    let mut pipe = false;
+    let mut json = false;
+    let mut dictionary = None;
+    let mut ignore_file = None;
+    let mut cache = None;
+    let mut distance = 2;
+    let mut max_word_length = spelling::DEFAULT_MAX_WORD_LENGTH;
+    let mut files = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" => pipe = true,
+            "--json" => json = true,
+            "-d" => dictionary = Some(args.next().ok_or("-d needs a file")?),
+            "--ignore-file" => ignore_file = Some(args.next().ok_or("--ignore-file needs a file")?),
+            "--cache" => cache = Some(args.next().ok_or("--cache needs a file")?),
+            // `--distance` is what older versions called it
+            "--max-distance" | "--distance" => {
+                let value = args.next().ok_or_else(|| format!("{} needs a number", arg))?;
+                distance = value.parse().map_err(|_| format!("invalid distance {:?}", value))?;
+            }
+            "--max-word-length" => {
+                let value = args.next().ok_or("--max-word-length needs a number")?;
+                max_word_length = value.parse().map_err(|_| format!("invalid word length {:?}", value))?;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown argument {:?}", arg)),
+            _ => files.push(arg),
+        }
+    }
+    if pipe && !files.is_empty() {
+        return Err("-a reads stdin, it doesn't take files".to_owned());
+    }
+    if pipe && cache.is_some() {
+        return Err("--cache only works when checking files".to_owned());
+    }
+    if !pipe && files.is_empty() {
+        return Err("nothing to do".to_owned());
+    }
+
+    let dictionary = match dictionary {
+        Some(path) => spelling::Dictionary::from_newline_separated(&read(&path)?),
+        #[cfg(feature = "bundled_words")]
+        None => spelling::Dictionary::bundled(),
+        #[cfg(not(feature = "bundled_words"))]
+        None => return Err("no word list, pass -d FILE".to_owned()),
+    };
+    let checker = spelling::SpellChecker::from_dictionary(dictionary).max_word_length(max_word_length);
+    let stdout = std::io::stdout();
+    if pipe {
+        let stdin = std::io::stdin();
+        return spelling::PipeSession::new(&checker, distance)
+            .run(stdin.lock(), stdout.lock())
+            .map(|()| true)
+            .map_err(|error| error.to_string());
+    }
+
+    let ignored = match ignore_file {
+        Some(path) => read(&path)?.lines().map(|line| line.trim().to_owned()).collect(),
+        None => std::collections::HashSet::new(),
+    };
+    let mut cache = match cache {
+        Some(path) => {
+            Some(spelling::SuggestionCache::open(&path, &checker).map_err(|error| format!("{}: {}", path, error))?)
+        }
+        None => None,
+    };
+    // with a cache, checking only has to find the misspellings, 0 away is
+    // enough for that
+    let check_distance = if cache.is_some() { 0 } else { distance };
+    let mut out = stdout.lock();
+    let mut clean = true;
+    let mut first = true;
+    if json {
+        write!(out, "[").map_err(|error| error.to_string())?;
+    }
+    for path in &files {
+        let text = read(path)?;
+        for (line, text) in text.lines().enumerate() {
+            for misspelling in checker.check_text(text, check_distance) {
+                // numbers and the like aren't words, the same as `-a`
+                if !misspelling.word.chars().any(char::is_alphabetic) || ignored.contains(&misspelling.word) {
+                    continue;
+                }
+                clean = false;
+                let column = misspelling.columns(text).start + 1;
+                let suggestions: Vec<&str> = match &mut cache {
+                    Some(cache) => {
+                        cache.suggest(&checker, &misspelling.word, distance).iter().map(String::as_str).collect()
+                    }
+                    None => misspelling.suggestions,
+                };
+                let suggestions = &suggestions[..suggestions.len().min(TOP_SUGGESTIONS)];
+                let written = if json {
+                    let separator = if first { "" } else { "," };
+                    let suggestions: Vec<String> = suggestions.iter().map(|word| json_string(word)).collect();
+                    write!(
+                        out,
+                        "{}\n  {{\"file\": {}, \"line\": {}, \"column\": {}, \"word\": {}, \"suggestions\": [{}]}}",
+                        separator,
+                        json_string(path),
+                        line + 1,
+                        column,
+                        json_string(&misspelling.word),
+                        suggestions.join(", "),
+                    )
+                } else {
+                    writeln!(out, "{}:{}:{}: {} ({})", path, line + 1, column, misspelling.word, suggestions.join(", "))
+                };
+                written.map_err(|error| error.to_string())?;
+                first = false;
+            }
+        }
+    }
+    if json {
+        writeln!(out, "{}]", if first { "" } else { "\n" }).map_err(|error| error.to_string())?;
+    }
+    if let Some(cache) = &mut cache {
+        cache.save().map_err(|error| error.to_string())?;
+    }
+    Ok(clean)
+}
+
+fn read(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))
+}
+
+/// Quotes `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
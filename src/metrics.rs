@@ -0,0 +1,332 @@
+//! Similarity measures, where 1.0 is identical and 0.0 is nothing in common,
+//! including the Levenshtein distance as one with [`normalized_levenshtein`],
+//! and the [`Similarity`] trait for ranking suggestions by them. Also
+//! [`hamming_distance`] and [`common_prefix_len`], cheap signals for breaking
+//! ties, and [`lcs_length`] and [`lcs_distance`], which ignore substitutions.
+
+use crate::{levenshtein_distance, DistanceMetric};
+
+/// Returns the Jaro similarity of `a` and `b`, counted in `char`s. Based on
+/// how many characters match within a window and how many of those are out of
+/// order. Two empty strings are identical.
+/// ```
+/// use spelling::jaro_similarity;
+/// assert!((jaro_similarity("martha", "marhta") - 0.944).abs() < 0.001);
+/// assert_eq!(jaro_similarity("abc", "xyz"), 0.0);
+/// ```
+pub fn jaro_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // characters only match if they're at most this far apart
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (index, &a_char) in a.iter().enumerate() {
+        let start = index.saturating_sub(window);
+        let end = (index + window + 1).min(b.len());
+        for other in start..end {
+            if !b_matched[other] && b[other] == a_char {
+                b_matched[other] = true;
+                a_matches.push(a_char);
+                break;
+            }
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+
+    let b_matches = b.iter().zip(&b_matched).filter(|(_, &matched)| matched).map(|(&c, _)| c);
+    let transpositions = a_matches.iter().zip(b_matches).filter(|(&a, b)| a != *b).count() / 2;
+    let matches = a_matches.len() as f64;
+    (matches / a.len() as f64 + matches / b.len() as f64 + (matches - transpositions as f64) / matches) / 3.0
+}
+
+/// How much [`jaro_winkler_similarity`] boosts each `char` of common prefix.
+pub const JARO_WINKLER_PREFIX_SCALE: f64 = 0.1;
+
+/// The longest common prefix [`jaro_winkler_similarity`] counts.
+pub const JARO_WINKLER_MAX_PREFIX: usize = 4;
+
+/// Returns the Jaro-Winkler similarity of `a` and `b`: the
+/// [`jaro_similarity`], boosted for a common prefix of up to
+/// [`JARO_WINKLER_MAX_PREFIX`] `char`s. Works well for names, where typos
+/// are rarer at the start.
+/// ```
+/// use spelling::jaro_winkler_similarity;
+/// assert!((jaro_winkler_similarity("martha", "marhta") - 0.961).abs() < 0.001);
+/// assert!(jaro_winkler_similarity("dixon", "dicksonx") > spelling::jaro_similarity("dixon", "dicksonx"));
+/// ```
+pub fn jaro_winkler_similarity(a: &str, b: &str) -> f64 {
+    let jaro = jaro_similarity(a, b);
+    let prefix = common_prefix_len(a, b).min(JARO_WINKLER_MAX_PREFIX);
+    jaro + prefix as f64 * JARO_WINKLER_PREFIX_SCALE * (1.0 - jaro)
+}
+
+/// Returns the Levenshtein distance between `a` and `b` as a similarity: 1.0
+/// minus the distance over the length of the longer one, in `char`s. So
+/// "kitten" and "sitting", 3 edits apart in 7 `char`s, are 57% similar. Two
+/// empty strings are identical.
+/// ```
+/// use spelling::normalized_levenshtein;
+/// assert!((normalized_levenshtein("kitten", "sitting") - 0.571).abs() < 0.001);
+/// assert_eq!(normalized_levenshtein("abc", "xyz"), 0.0);
+/// assert_eq!(normalized_levenshtein("", ""), 1.0);
+/// ```
+pub fn normalized_levenshtein(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    distance_ratio(levenshtein_distance(a, b), longest)
+}
+
+/// `distance` edits between words of at most `longest` `char`s as a
+/// similarity, see [`normalized_levenshtein`].
+pub(crate) fn distance_ratio(distance: usize, longest: usize) -> f64 {
+    match longest {
+        0 => 1.0,
+        longest => 1.0 - distance as f64 / longest as f64,
+    }
+}
+
+/// Returns how many `char`s differ between `a` and `b`, position by
+/// position, or `None` if they aren't the same length. Meant for fixed
+/// length codes and IDs, or as a tie-breaker between suggestions.
+/// ```
+/// use spelling::hamming_distance;
+/// assert_eq!(hamming_distance("karolin", "kathrin"), Some(3));
+/// assert_eq!(hamming_distance("naïve", "naive"), Some(1));
+/// assert_eq!(hamming_distance("thin", "thing"), None);
+/// ```
+pub fn hamming_distance(a: &str, b: &str) -> Option<usize> {
+    let mut a = a.chars();
+    let mut b = b.chars();
+    let mut distance = 0;
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) => distance += usize::from(a != b),
+            (None, None) => return Some(distance),
+            _ => return None,
+        }
+    }
+}
+
+/// Returns how many `char`s `a` and `b` share at the start. Most typos keep
+/// the start of a word, so this makes a cheap secondary ranking signal.
+/// ```
+/// use spelling::common_prefix_len;
+/// assert_eq!(common_prefix_len("restaurant", "restaraunt"), 5);
+/// assert_eq!(common_prefix_len("thing", ""), 0);
+/// ```
+pub fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a == b).count()
+}
+
+/// Returns the length of the longest common subsequence of `a` and `b`: the
+/// most `char`s that appear in both in the same order, not necessarily next
+/// to each other.
+/// ```
+/// use spelling::lcs_length;
+/// assert_eq!(lcs_length("restaurant", "restaraunt"), 9);
+/// assert_eq!(lcs_length("abc", "xyz"), 0);
+/// ```
+pub fn lcs_length(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut list = vec![0; b.len() + 1];
+    let mut temp = vec![0; b.len() + 1];
+    for a_char in &a {
+        for (index, b_char) in b.iter().enumerate() {
+            temp[index + 1] = match a_char == b_char {
+                true => list[index] + 1,
+                false => list[index + 1].max(temp[index]),
+            };
+        }
+        std::mem::swap(&mut list, &mut temp);
+    }
+    list[b.len()]
+}
+
+/// Returns how many `char`s have to be inserted or deleted to turn `a` into
+/// `b`, like a diff: everything outside their [`lcs_length`]. With no
+/// substitutions a changed `char` costs 2, so this is at least the
+/// Levenshtein distance and at most twice it.
+/// ```
+/// use spelling::lcs_distance;
+/// assert_eq!(lcs_distance("kitten", "sitting"), 5);
+/// assert_eq!(lcs_distance("thing", "things"), 1);
+/// ```
+pub fn lcs_distance(a: &str, b: &str) -> usize {
+    a.chars().count() + b.chars().count() - 2 * lcs_length(a, b)
+}
+
+/// The insert and delete only distance, see [`lcs_distance`]. Pass it to
+/// [`spellcheck_with_metric`](crate::spellcheck_with_metric) or
+/// [`SpellChecker::metric`](crate::SpellChecker::metric) to rank by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcs;
+
+impl DistanceMetric for Lcs {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        lcs_distance(a, b)
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        a.max(b) - a.min(b)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "lcs".into()
+    }
+}
+
+/// A way of measuring how alike two strings are, from 0.0 (nothing in common)
+/// to 1.0 (identical).
+///
+/// Pass one to [`spellcheck_with_similarity`](crate::spellcheck_with_similarity)
+/// to rank suggestions by it instead of by edit distance.
+pub trait Similarity: Send + Sync {
+    /// Returns the similarity of `a` and `b`.
+    fn similarity(&self, a: &str, b: &str) -> f64;
+}
+
+impl<S: Similarity + ?Sized> Similarity for &S {
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        (**self).similarity(a, b)
+    }
+}
+
+impl<S: Similarity + ?Sized> Similarity for Box<S> {
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        (**self).similarity(a, b)
+    }
+}
+
+/// The Jaro similarity, see [`jaro_similarity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Jaro;
+
+impl Similarity for Jaro {
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        jaro_similarity(a, b)
+    }
+}
+
+/// The Jaro-Winkler similarity, see [`jaro_winkler_similarity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct JaroWinkler;
+
+impl Similarity for JaroWinkler {
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        jaro_winkler_similarity(a, b)
+    }
+}
+
+/// The Levenshtein distance as a similarity, see [`normalized_levenshtein`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NormalizedLevenshtein;
+
+impl Similarity for NormalizedLevenshtein {
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        normalized_levenshtein(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 0.001
+    }
+
+    #[test]
+    fn jaro() {
+        use crate::jaro_similarity;
+        assert_eq!(jaro_similarity("", ""), 1.0);
+        assert_eq!(jaro_similarity("a", ""), 0.0);
+        assert_eq!(jaro_similarity("thing", "thing"), 1.0);
+        assert!(close(jaro_similarity("dixon", "dicksonx"), 0.767));
+        assert!(close(jaro_similarity("jellyfish", "smellyfish"), 0.896));
+        assert!(close(jaro_similarity("crate", "trace"), 0.733));
+        assert!(close(jaro_similarity("naïve", "naive"), 0.867));
+    }
+
+    #[test]
+    fn jaro_winkler() {
+        use crate::jaro_winkler_similarity;
+        assert!(close(jaro_winkler_similarity("dixon", "dicksonx"), 0.813));
+        assert!(close(jaro_winkler_similarity("dwayne", "duane"), 0.84));
+        assert_eq!(jaro_winkler_similarity("", ""), 1.0);
+    }
+
+    #[test]
+    fn normalized_levenshtein() {
+        use crate::normalized_levenshtein;
+        assert_eq!(normalized_levenshtein("thing", "thing"), 1.0);
+        assert_eq!(normalized_levenshtein("", "abcd"), 0.0);
+        assert_eq!(normalized_levenshtein("naïve", "naive"), 0.8);
+        assert_eq!(normalized_levenshtein("abcd", "ab"), 0.5);
+    }
+
+    #[test]
+    fn lcs() {
+        use crate::{lcs_distance, lcs_length};
+        assert_eq!(lcs_length("", ""), 0);
+        assert_eq!(lcs_length("thing", "thing"), 5);
+        assert_eq!(lcs_length("AGGTAB", "GXTXAYB"), 4);
+        assert_eq!(lcs_length("naïve", "naive"), 4);
+        assert_eq!(lcs_distance("", "abc"), 3);
+        assert_eq!(lcs_distance("ab", "ba"), 2);
+    }
+
+    #[test]
+    fn hamming_and_prefix() {
+        use crate::{common_prefix_len, hamming_distance};
+        assert_eq!(hamming_distance("", ""), Some(0));
+        assert_eq!(hamming_distance("1011101", "1001001"), Some(2));
+        assert_eq!(hamming_distance("ab", ""), None);
+        assert_eq!(common_prefix_len("thin", "thin"), 4);
+        assert_eq!(common_prefix_len("éa", "éb"), 1);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn similarity_in_range(a in "\\PC{0,10}", b in "\\PC{0,10}") {
+            for similarity in [
+                crate::jaro_similarity(&a, &b),
+                crate::jaro_winkler_similarity(&a, &b),
+                crate::normalized_levenshtein(&a, &b),
+            ] {
+                proptest::prop_assert!((0.0..=1.0).contains(&similarity));
+            }
+            proptest::prop_assert!(close(crate::jaro_similarity(&a, &b), crate::jaro_similarity(&b, &a)));
+            proptest::prop_assert!(close(crate::jaro_winkler_similarity(&a, &b), crate::jaro_winkler_similarity(&b, &a)));
+            proptest::prop_assert_eq!(crate::normalized_levenshtein(&a, &b), crate::normalized_levenshtein(&b, &a));
+            proptest::prop_assert_eq!(crate::jaro_winkler_similarity(&a, &a), 1.0);
+        }
+
+        #[test]
+        fn hamming_bounds_levenshtein(a in "[abc]{0,6}", b in "[abc]{0,6}") {
+            let prefix = crate::common_prefix_len(&a, &b);
+            proptest::prop_assert_eq!(&a[..prefix], &b[..prefix]);
+            match crate::hamming_distance(&a, &b) {
+                Some(hamming) => proptest::prop_assert!(crate::levenshtein_distance(&a, &b) <= hamming),
+                None => proptest::prop_assert_ne!(a.len(), b.len()),
+            }
+        }
+
+        #[test]
+        fn lcs_bounds_levenshtein(a in "[abé]{0,6}", b in "[abé]{0,6}") {
+            let levenshtein = crate::levenshtein_distance(&a, &b);
+            let lcs = crate::lcs_distance(&a, &b);
+            proptest::prop_assert!(levenshtein <= lcs && lcs <= 2 * levenshtein);
+            proptest::prop_assert_eq!(lcs, crate::lcs_distance(&b, &a));
+            proptest::prop_assert!(crate::lcs_length(&a, &b) <= a.chars().count().min(b.chars().count()));
+        }
+    }
+}
@@ -0,0 +1,951 @@
+//! Phonetic codes, which give words that sound alike the same code whatever
+//! their spelling: Soundex, refined Soundex and Double Metaphone. The
+//! [`Phonetic`] trait ranks suggestions by them.
+
+use crate::distance::{levenshtein_distance, levenshtein_distance_bounded, Bounded, DistanceMetric};
+
+/// The American Soundex code of `word`: its first letter followed by three
+/// digits for the consonant sounds after it. Only ASCII letters are looked
+/// at, and a word without any has the empty code.
+/// ```
+/// use spelling::soundex;
+/// assert_eq!(soundex("Robert"), "R163");
+/// assert_eq!(soundex("Rupert"), "R163");
+/// assert_eq!(soundex("Ashcraft"), "A261");
+/// assert_eq!(soundex("Lee"), "L000");
+/// ```
+pub fn soundex(word: &str) -> String {
+    fn digit(letter: u8) -> u8 {
+        match letter {
+            b'B' | b'F' | b'P' | b'V' => b'1',
+            b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => b'2',
+            b'D' | b'T' => b'3',
+            b'L' => b'4',
+            b'M' | b'N' => b'5',
+            b'R' => b'6',
+            // vowels separate repeated digits, H and W don't
+            b'H' | b'W' => b'-',
+            _ => b'0',
+        }
+    }
+    let mut letters = word.bytes().filter(u8::is_ascii_alphabetic).map(|letter| letter.to_ascii_uppercase());
+    let first = match letters.next() {
+        Some(first) => first,
+        None => return String::new(),
+    };
+    let mut code = vec![first];
+    let mut last = digit(first);
+    for letter in letters {
+        match digit(letter) {
+            b'-' => {}
+            digit if digit != last => {
+                if digit != b'0' {
+                    code.push(digit);
+                }
+                last = digit;
+            }
+            _ => {}
+        }
+        if code.len() == 4 {
+            break;
+        }
+    }
+    code.resize(4, b'0');
+    String::from_utf8(code).unwrap_or_default()
+}
+
+/// The refined Soundex code of `word`, which splits the letters into more
+/// groups than [`soundex`] and isn't cut off at four characters, so fewer
+/// unrelated words share a code. Only ASCII letters are looked at, and a word
+/// without any has the empty code.
+/// ```
+/// use spelling::refined_soundex;
+/// assert_eq!(refined_soundex("Braz"), "B1905");
+/// assert_eq!(refined_soundex("Caren"), "C30908");
+/// assert_eq!(refined_soundex("Lambert"), "L7081096");
+/// ```
+pub fn refined_soundex(word: &str) -> String {
+    // A to Z
+    const DIGITS: &[u8; 26] = b"01360240043788015936020505";
+    let mut letters = word
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|letter| letter.to_ascii_uppercase())
+        .peekable();
+    let mut code = match letters.peek() {
+        Some(&first) => vec![first],
+        None => return String::new(),
+    };
+    let mut last = None;
+    for letter in letters {
+        let digit = DIGITS[usize::from(letter - b'A')];
+        if last != Some(digit) {
+            code.push(digit);
+            last = Some(digit);
+        }
+    }
+    String::from_utf8(code).unwrap_or_default()
+}
+
+/// How long [`double_metaphone`] codes are at most.
+pub const DOUBLE_METAPHONE_MAX_LEN: usize = 4;
+
+/// The Double Metaphone codes of `word`: a primary code, and an alternate
+/// one for words with another common pronunciation, often of foreign origin.
+/// When there's no other pronunciation the two are the same. Unlike
+/// [`soundex`] it understands spellings like "ph", "gh" and silent letters.
+/// Codes are at most [`DOUBLE_METAPHONE_MAX_LEN`] characters, and `0`
+/// stands for "th".
+/// ```
+/// use spelling::double_metaphone;
+/// assert_eq!(double_metaphone("phone"), double_metaphone("fone"));
+/// assert_eq!(double_metaphone("Smith"), ("SM0".to_owned(), "XMT".to_owned()));
+/// assert_eq!(double_metaphone("knight"), ("NT".to_owned(), "NT".to_owned()));
+/// ```
+pub fn double_metaphone(word: &str) -> (String, String) {
+    let mut metaphone = MetaphoneEncoder {
+        word: word.trim().chars().flat_map(char::to_uppercase).collect(),
+        primary: String::new(),
+        alternate: String::new(),
+    };
+    metaphone.encode();
+    metaphone.primary.truncate(DOUBLE_METAPHONE_MAX_LEN);
+    metaphone.alternate.truncate(DOUBLE_METAPHONE_MAX_LEN);
+    (metaphone.primary, metaphone.alternate)
+}
+
+/// Double Metaphone, see [`double_metaphone`]. Both codes count, so words
+/// sound alike if either of their codes match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DoubleMetaphone;
+
+impl Phonetic for DoubleMetaphone {
+    fn codes(&self, word: &str) -> Vec<String> {
+        let (primary, alternate) = double_metaphone(word);
+        if primary == alternate {
+            vec![primary]
+        } else {
+            vec![primary, alternate]
+        }
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "double_metaphone".into()
+    }
+}
+
+/// The uppercased word being encoded by [`double_metaphone`] and its codes
+/// so far.
+struct MetaphoneEncoder {
+    word: Vec<char>,
+    primary: String,
+    alternate: String,
+}
+
+/// The rules follow Lawrence Philips' original, by way of the Apache Commons
+/// Codec port. Positions are `isize` since many rules look behind the
+/// current letter.
+impl MetaphoneEncoder {
+    fn len(&self) -> isize {
+        self.word.len() as isize
+    }
+
+    /// The letter at `index`, or `'\0'` outside the word.
+    fn at(&self, index: isize) -> char {
+        if index < 0 {
+            return '\0';
+        }
+        self.word.get(index as usize).copied().unwrap_or('\0')
+    }
+
+    /// Whether the `length` letters from `start` are one of `options`.
+    fn matches(&self, start: isize, length: isize, options: &[&str]) -> bool {
+        if start < 0 || start + length > self.len() {
+            return false;
+        }
+        let found = &self.word[start as usize..(start + length) as usize];
+        options.iter().any(|option| option.chars().eq(found.iter().copied()))
+    }
+
+    fn is_vowel(&self, index: isize) -> bool {
+        matches!(self.at(index), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    fn is_slavo_germanic(&self) -> bool {
+        let word: String = self.word.iter().collect();
+        word.contains('W') || word.contains('K') || word.contains("CZ") || word.contains("WITZ")
+    }
+
+    fn is_germanic(&self) -> bool {
+        self.matches(0, 4, &["VAN ", "VON "]) || self.matches(0, 3, &["SCH"])
+    }
+
+    fn add(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn add_both(&mut self, code: &str) {
+        self.add(code, code);
+    }
+
+    fn encode(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+        let slavo_germanic = self.is_slavo_germanic();
+        let mut index = if self.matches(0, 2, &["GN", "KN", "PN", "WR", "PS"]) { 1 } else { 0 };
+        let done = |metaphone: &Self| {
+            metaphone.primary.len() >= DOUBLE_METAPHONE_MAX_LEN
+                && metaphone.alternate.len() >= DOUBLE_METAPHONE_MAX_LEN
+        };
+        while index < self.len() && !done(self) {
+            index = match self.at(index) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if index == 0 {
+                        self.add_both("A");
+                    }
+                    index + 1
+                }
+                'B' => self.single("P", index, 'B'),
+                'Ç' => {
+                    self.add_both("S");
+                    index + 1
+                }
+                'C' => self.c(index),
+                'D' => self.d(index),
+                'F' => self.single("F", index, 'F'),
+                'G' => self.g(index, slavo_germanic),
+                'H' => {
+                    if (index == 0 || self.is_vowel(index - 1)) && self.is_vowel(index + 1) {
+                        self.add_both("H");
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'J' => self.j(index, slavo_germanic),
+                'K' => self.single("K", index, 'K'),
+                'L' => self.l(index),
+                'M' => {
+                    self.add_both("M");
+                    let umb = self.matches(index - 1, 3, &["UMB"])
+                        && (index + 1 == self.len() - 1 || self.matches(index + 2, 2, &["ER"]));
+                    if self.at(index + 1) == 'M' || umb {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'N' => self.single("N", index, 'N'),
+                'Ñ' => {
+                    self.add_both("N");
+                    index + 1
+                }
+                'P' => {
+                    if self.at(index + 1) == 'H' {
+                        self.add_both("F");
+                        index + 2
+                    } else {
+                        self.add_both("P");
+                        if self.matches(index + 1, 1, &["P", "B"]) {
+                            index + 2
+                        } else {
+                            index + 1
+                        }
+                    }
+                }
+                'Q' => self.single("K", index, 'Q'),
+                'R' => {
+                    // French words ending in "ier"
+                    if index == self.len() - 1
+                        && !slavo_germanic
+                        && self.matches(index - 2, 2, &["IE"])
+                        && !self.matches(index - 4, 2, &["ME", "MA"])
+                    {
+                        self.add("", "R");
+                    } else {
+                        self.add_both("R");
+                    }
+                    if self.at(index + 1) == 'R' {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'S' => self.s(index, slavo_germanic),
+                'T' => self.t(index),
+                'V' => self.single("F", index, 'V'),
+                'W' => self.w(index),
+                'X' => self.x(index),
+                'Z' => self.z(index, slavo_germanic),
+                _ => index + 1,
+            };
+        }
+    }
+
+    /// Adds `code` for the letter at `index`, skipping a doubled `letter`.
+    fn single(&mut self, code: &str, index: isize, letter: char) -> isize {
+        self.add_both(code);
+        if self.at(index + 1) == letter {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn c(&mut self, index: isize) -> isize {
+        if self.c_is_k(index) {
+            self.add_both("K");
+            index + 2
+        } else if index == 0 && self.matches(index, 6, &["CAESAR"]) {
+            self.add_both("S");
+            index + 2
+        } else if self.matches(index, 2, &["CH"]) {
+            self.ch(index)
+        } else if self.matches(index, 2, &["CZ"]) && !self.matches(index - 2, 4, &["WICZ"]) {
+            self.add("S", "X");
+            index + 2
+        } else if self.matches(index + 1, 3, &["CIA"]) {
+            self.add_both("X");
+            index + 3
+        } else if self.matches(index, 2, &["CC"]) && !(index == 1 && self.at(0) == 'M') {
+            if self.matches(index + 2, 1, &["I", "E", "H"]) && !self.matches(index + 2, 2, &["HU"]) {
+                if (index == 1 && self.at(index - 1) == 'A') || self.matches(index - 1, 5, &["UCCEE", "UCCES"]) {
+                    self.add_both("KS");
+                } else {
+                    self.add_both("X");
+                }
+                index + 3
+            } else {
+                self.add_both("K");
+                index + 2
+            }
+        } else if self.matches(index, 2, &["CK", "CG", "CQ"]) {
+            self.add_both("K");
+            index + 2
+        } else if self.matches(index, 2, &["CI", "CE", "CY"]) {
+            if self.matches(index, 3, &["CIO", "CIE", "CIA"]) {
+                self.add("S", "X");
+            } else {
+                self.add_both("S");
+            }
+            index + 2
+        } else {
+            self.add_both("K");
+            if self.matches(index + 1, 2, &[" C", " Q", " G"]) {
+                index + 3
+            } else if self.matches(index + 1, 1, &["C", "K", "Q"]) && !self.matches(index + 1, 2, &["CE", "CI"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    /// "chianti", and Germanic "ach" like "bacher" and "macher".
+    fn c_is_k(&self, index: isize) -> bool {
+        if self.matches(index, 4, &["CHIA"]) {
+            true
+        } else if index <= 1 || self.is_vowel(index - 2) || !self.matches(index - 1, 3, &["ACH"]) {
+            false
+        } else {
+            let next = self.at(index + 2);
+            (next != 'I' && next != 'E') || self.matches(index - 2, 6, &["BACHER", "MACHER"])
+        }
+    }
+
+    fn ch(&mut self, index: isize) -> isize {
+        if index > 0 && self.matches(index, 4, &["CHAE"]) {
+            self.add("K", "X");
+        } else if self.ch_is_greek(index) || self.ch_is_k(index) {
+            self.add_both("K");
+        } else if index > 0 {
+            if self.matches(0, 2, &["MC"]) {
+                self.add_both("K");
+            } else {
+                self.add("X", "K");
+            }
+        } else {
+            self.add_both("X");
+        }
+        index + 2
+    }
+
+    /// Greek roots like "chemistry" and "chorus".
+    fn ch_is_greek(&self, index: isize) -> bool {
+        index == 0
+            && (self.matches(index + 1, 5, &["HARAC", "HARIS"])
+                || self.matches(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.matches(0, 5, &["CHORE"])
+    }
+
+    /// Germanic words, "orchestra", "architect" and "ch" before consonants.
+    fn ch_is_k(&self, index: isize) -> bool {
+        self.is_germanic()
+            || self.matches(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.matches(index + 2, 1, &["T", "S"])
+            || ((self.matches(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+                && (self.matches(index + 2, 1, &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "])
+                    || index + 1 == self.len() - 1))
+    }
+
+    fn d(&mut self, index: isize) -> isize {
+        if self.matches(index, 2, &["DG"]) {
+            if self.matches(index + 2, 1, &["I", "E", "Y"]) {
+                self.add_both("J");
+                index + 3
+            } else {
+                self.add_both("TK");
+                index + 2
+            }
+        } else if self.matches(index, 2, &["DT", "DD"]) {
+            self.add_both("T");
+            index + 2
+        } else {
+            self.add_both("T");
+            index + 1
+        }
+    }
+
+    fn g(&mut self, index: isize, slavo_germanic: bool) -> isize {
+        if self.at(index + 1) == 'H' {
+            self.gh(index)
+        } else if self.at(index + 1) == 'N' {
+            if index == 1 && self.is_vowel(0) && !slavo_germanic {
+                self.add("KN", "N");
+            } else if !self.matches(index + 2, 2, &["EY"]) && !slavo_germanic {
+                self.add("N", "KN");
+            } else {
+                self.add_both("KN");
+            }
+            index + 2
+        } else if self.matches(index + 1, 2, &["LI"]) && !slavo_germanic {
+            self.add("KL", "L");
+            index + 2
+        } else if (index == 0
+            && (self.at(index + 1) == 'Y'
+                || self.matches(index + 1, 2, &["ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER"])))
+            || ((self.matches(index + 1, 2, &["ER"]) || self.at(index + 1) == 'Y')
+                && !self.matches(0, 6, &["DANGER", "RANGER", "MANGER"])
+                && !self.matches(index - 1, 1, &["E", "I"])
+                && !self.matches(index - 1, 3, &["RGY", "OGY"]))
+        {
+            self.add("K", "J");
+            index + 2
+        } else if self.matches(index + 1, 1, &["E", "I", "Y"]) || self.matches(index - 1, 4, &["AGGI", "OGGI"]) {
+            if self.is_germanic() || self.matches(index + 1, 2, &["ET"]) {
+                self.add_both("K");
+            } else if self.matches(index + 1, 3, &["IER"]) {
+                self.add_both("J");
+            } else {
+                self.add("J", "K");
+            }
+            index + 2
+        } else if self.at(index + 1) == 'G' {
+            self.add_both("K");
+            index + 2
+        } else {
+            self.add_both("K");
+            index + 1
+        }
+    }
+
+    fn gh(&mut self, index: isize) -> isize {
+        if index > 0 && !self.is_vowel(index - 1) {
+            self.add_both("K");
+        } else if index == 0 {
+            if self.at(index + 2) == 'I' {
+                self.add_both("J");
+            } else {
+                self.add_both("K");
+            }
+        } else if (index > 1 && self.matches(index - 2, 1, &["B", "H", "D"]))
+            || (index > 2 && self.matches(index - 3, 1, &["B", "H", "D"]))
+            || (index > 3 && self.matches(index - 4, 1, &["B", "H"]))
+        {
+            // silent, like "bough" and "hugh"
+        } else if index > 2 && self.at(index - 1) == 'U' && self.matches(index - 3, 1, &["C", "G", "L", "R", "T"]) {
+            // "laugh", "cough"
+            self.add_both("F");
+        } else if index > 0 && self.at(index - 1) != 'I' {
+            self.add_both("K");
+        }
+        index + 2
+    }
+
+    fn j(&mut self, index: isize, slavo_germanic: bool) -> isize {
+        if self.matches(index, 4, &["JOSE"]) || self.matches(0, 4, &["SAN "]) {
+            if (index == 0 && self.at(index + 4) == ' ') || self.len() == 4 || self.matches(0, 4, &["SAN "]) {
+                self.add_both("H");
+            } else {
+                self.add("J", "H");
+            }
+            return index + 1;
+        }
+        if index == 0 {
+            self.add("J", "A");
+        } else if self.is_vowel(index - 1)
+            && !slavo_germanic
+            && (self.at(index + 1) == 'A' || self.at(index + 1) == 'O')
+        {
+            self.add("J", "H");
+        } else if index == self.len() - 1 {
+            self.add("J", "");
+        } else if !self.matches(index + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.matches(index - 1, 1, &["S", "K", "L"])
+        {
+            self.add_both("J");
+        }
+        if self.at(index + 1) == 'J' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn l(&mut self, index: isize) -> isize {
+        if self.at(index + 1) != 'L' {
+            self.add_both("L");
+            return index + 1;
+        }
+        // Spanish "ll", like "cabrillo" and "gallegos"
+        let last = self.len() - 1;
+        let spanish = (index == self.len() - 3 && self.matches(index - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+            || ((self.matches(last - 1, 2, &["AS", "OS"]) || self.matches(last, 1, &["A", "O"]))
+                && self.matches(index - 1, 4, &["ALLE"]));
+        if spanish {
+            self.add("L", "");
+        } else {
+            self.add_both("L");
+        }
+        index + 2
+    }
+
+    fn s(&mut self, index: isize, slavo_germanic: bool) -> isize {
+        if self.matches(index - 1, 3, &["ISL", "YSL"]) {
+            // silent, like "island" and "carlisle"
+            index + 1
+        } else if index == 0 && self.matches(index, 5, &["SUGAR"]) {
+            self.add("X", "S");
+            index + 1
+        } else if self.matches(index, 2, &["SH"]) {
+            if self.matches(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add_both("S");
+            } else {
+                self.add_both("X");
+            }
+            index + 2
+        } else if self.matches(index, 3, &["SIO", "SIA"]) || self.matches(index, 4, &["SIAN"]) {
+            if slavo_germanic {
+                self.add_both("S");
+            } else {
+                self.add("S", "X");
+            }
+            index + 3
+        } else if (index == 0 && self.matches(index + 1, 1, &["M", "N", "L", "W"])) || self.matches(index + 1, 1, &["Z"]) {
+            self.add("S", "X");
+            if self.matches(index + 1, 1, &["Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        } else if self.matches(index, 2, &["SC"]) {
+            if self.at(index + 2) == 'H' {
+                if self.matches(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                    if self.matches(index + 3, 2, &["ER", "EN"]) {
+                        self.add("X", "SK");
+                    } else {
+                        self.add_both("SK");
+                    }
+                } else if index == 0 && !self.is_vowel(3) && self.at(3) != 'W' {
+                    self.add("X", "S");
+                } else {
+                    self.add_both("X");
+                }
+            } else if self.matches(index + 2, 1, &["I", "E", "Y"]) {
+                self.add_both("S");
+            } else {
+                self.add_both("SK");
+            }
+            index + 3
+        } else {
+            // French endings, like "resnais" and "artois"
+            if index == self.len() - 1 && self.matches(index - 2, 2, &["AI", "OI"]) {
+                self.add("", "S");
+            } else {
+                self.add_both("S");
+            }
+            if self.matches(index + 1, 1, &["S", "Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn t(&mut self, index: isize) -> isize {
+        if self.matches(index, 4, &["TION"]) || self.matches(index, 3, &["TIA", "TCH"]) {
+            self.add_both("X");
+            index + 3
+        } else if self.matches(index, 2, &["TH"]) || self.matches(index, 3, &["TTH"]) {
+            if self.matches(index + 2, 2, &["OM", "AM"]) || self.is_germanic() {
+                self.add_both("T");
+            } else {
+                self.add("0", "T");
+            }
+            index + 2
+        } else {
+            self.add_both("T");
+            if self.matches(index + 1, 1, &["T", "D"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn w(&mut self, index: isize) -> isize {
+        if self.matches(index, 2, &["WR"]) {
+            self.add_both("R");
+            index + 2
+        } else if index == 0 && (self.is_vowel(index + 1) || self.matches(index, 2, &["WH"])) {
+            if self.is_vowel(index + 1) {
+                self.add("A", "F");
+            } else {
+                self.add_both("A");
+            }
+            index + 1
+        } else if (index == self.len() - 1 && self.is_vowel(index - 1))
+            || self.matches(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.matches(0, 3, &["SCH"])
+        {
+            self.add("", "F");
+            index + 1
+        } else if self.matches(index, 4, &["WICZ", "WITZ"]) {
+            self.add("TS", "FX");
+            index + 4
+        } else {
+            index + 1
+        }
+    }
+
+    fn x(&mut self, index: isize) -> isize {
+        if index == 0 {
+            self.add_both("S");
+            return index + 1;
+        }
+        // silent in French endings, like "breaux"
+        let french = index == self.len() - 1
+            && (self.matches(index - 3, 3, &["IAU", "EAU"]) || self.matches(index - 2, 2, &["AU", "OU"]));
+        if !french {
+            self.add_both("KS");
+        }
+        if self.matches(index + 1, 1, &["C", "X"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn z(&mut self, index: isize, slavo_germanic: bool) -> isize {
+        if self.at(index + 1) == 'H' {
+            self.add_both("J");
+            return index + 2;
+        }
+        if self.matches(index + 1, 2, &["ZO", "ZI", "ZA"]) || (slavo_germanic && index > 0 && self.at(index - 1) != 'T') {
+            self.add("S", "TS");
+        } else {
+            self.add_both("S");
+        }
+        if self.at(index + 1) == 'Z' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+/// A way of encoding how a word sounds.
+///
+/// Pass one to [`spellcheck_phonetic`](crate::spellcheck_phonetic) to rank
+/// suggestions that sound like the word first.
+pub trait Phonetic: Send + Sync {
+    /// The codes of `word`. Most encodings give one code, some give
+    /// alternatives for words with more than one pronunciation.
+    fn codes(&self, word: &str) -> Vec<String>;
+
+    /// Whether `a` and `b` share a code. The empty code, for words with no
+    /// letters the encoding understands, never matches.
+    fn sounds_alike(&self, a: &str, b: &str) -> bool {
+        let b = self.codes(b);
+        self.codes(a).iter().any(|code| !code.is_empty() && b.contains(code))
+    }
+
+    /// A name for the encoding, stable across versions and builds, which
+    /// [`PhoneticLevenshtein`] puts in its [`DistanceMetric::id`]. The default
+    /// is the type's name, which can change between compiler versions.
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        std::any::type_name::<Self>().into()
+    }
+}
+
+impl<P: Phonetic + ?Sized> Phonetic for &P {
+    fn codes(&self, word: &str) -> Vec<String> {
+        (**self).codes(word)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        (**self).id()
+    }
+}
+
+impl<P: Phonetic + ?Sized> Phonetic for Box<P> {
+    fn codes(&self, word: &str) -> Vec<String> {
+        (**self).codes(word)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        (**self).id()
+    }
+}
+
+/// American Soundex, see [`soundex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Soundex;
+
+impl Phonetic for Soundex {
+    fn codes(&self, word: &str) -> Vec<String> {
+        vec![soundex(word)]
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "soundex".into()
+    }
+}
+
+/// Refined Soundex, see [`refined_soundex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RefinedSoundex;
+
+impl Phonetic for RefinedSoundex {
+    fn codes(&self, word: &str) -> Vec<String> {
+        vec![refined_soundex(word)]
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        "refined_soundex".into()
+    }
+}
+
+/// The Levenshtein distance, less `discount` for words that sound alike
+/// according to `phonetic`, though never less than 1 for different words.
+/// A hybrid of spelling and sound for
+/// [`spellcheck_with_metric`](crate::spellcheck_with_metric) and
+/// [`SpellChecker::metric`](crate::SpellChecker::metric). It isn't a true
+/// metric, so don't use it with a [`BkTree`](crate::BkTree).
+/// ```
+/// use spelling::{levenshtein_distance, DistanceMetric, DoubleMetaphone, PhoneticLevenshtein};
+/// assert_eq!(levenshtein_distance("fone", "phone"), 2);
+/// let metric = PhoneticLevenshtein::new(DoubleMetaphone, 1);
+/// assert_eq!(metric.distance("fone", "phone"), 1);
+/// assert_eq!(metric.distance("fone", "cone"), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PhoneticLevenshtein<P> {
+    /// How words are encoded.
+    pub phonetic: P,
+    /// How many edits sounding alike makes up for.
+    pub discount: usize,
+}
+
+impl<P: Phonetic> PhoneticLevenshtein<P> {
+    /// Measures with `phonetic`, taking `discount` off the distance of words
+    /// that sound alike.
+    pub fn new(phonetic: P, discount: usize) -> Self {
+        PhoneticLevenshtein { phonetic, discount }
+    }
+
+    /// The discount for `a` and `b`.
+    fn discount(&self, a: &str, b: &str) -> usize {
+        if self.discount > 0 && self.phonetic.sounds_alike(a, b) {
+            self.discount
+        } else {
+            0
+        }
+    }
+}
+
+/// Takes `discount` off `distance`, leaving different words at least 1 apart.
+fn discounted(distance: usize, discount: usize) -> usize {
+    match distance {
+        0 => 0,
+        distance => distance.saturating_sub(discount).max(1),
+    }
+}
+
+impl<P: Phonetic> DistanceMetric for PhoneticLevenshtein<P> {
+    fn distance(&self, a: &str, b: &str) -> usize {
+        discounted(levenshtein_distance(a, b), self.discount(a, b))
+    }
+
+    fn distance_bounded(&self, a: &str, b: &str, max_distance: usize) -> Bounded {
+        let discount = self.discount(a, b);
+        match levenshtein_distance_bounded(a, b, max_distance.saturating_add(discount)).within() {
+            Some(distance) => Bounded::new(discounted(distance, discount), max_distance),
+            None => Bounded::Exceeded,
+        }
+    }
+
+    fn length_lower_bound(&self, a: usize, b: usize) -> usize {
+        (a.max(b) - a.min(b)).saturating_sub(self.discount)
+    }
+
+    fn id(&self) -> std::borrow::Cow<'_, str> {
+        format!("phonetic_levenshtein:{}:{}", self.discount, self.phonetic.id()).into()
+    }
+}
+
+/// Sets [`ScoreBreakdown::phonetic_bonus`](crate::ScoreBreakdown::phonetic_bonus)
+/// to `weight` for suggestions that sound like the query, then sorts by
+/// score. With a weight of 1.0 sounding alike makes up for one edit, so it
+/// trades off against the distance instead of overriding it like
+/// [`spellcheck_phonetic`](crate::spellcheck_phonetic) does.
+/// ```
+/// # #[cfg(feature = "check")] {
+/// use spelling::{DoubleMetaphone, PhoneticRanker, Pipeline};
+/// let pipeline = Pipeline::builder().ranker(PhoneticRanker::new(DoubleMetaphone, 1.5)).build();
+/// let words: Vec<&str> = pipeline.run("cone\nphone".lines(), "fone", 2).iter().map(|candidate| candidate.word).collect();
+/// assert_eq!(words, vec!["phone", "cone"]);
+/// # }
+/// ```
+#[cfg(feature = "rank")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhoneticRanker<P> {
+    /// How words are encoded.
+    pub phonetic: P,
+    /// What sounding alike takes off the score.
+    pub weight: f64,
+}
+
+#[cfg(feature = "rank")]
+impl<P: Phonetic> PhoneticRanker<P> {
+    /// Ranks with `phonetic`, taking `weight` off the score of words that
+    /// sound like the query.
+    pub fn new(phonetic: P, weight: f64) -> Self {
+        PhoneticRanker { phonetic, weight }
+    }
+}
+
+#[cfg(feature = "rank")]
+impl<P: Phonetic> crate::rank::Ranker for PhoneticRanker<P> {
+    fn rank(&self, word: &str, candidates: &mut [crate::rank::Candidate]) {
+        let codes: Vec<String> = self.phonetic.codes(word).into_iter().filter(|code| !code.is_empty()).collect();
+        for candidate in candidates.iter_mut() {
+            let alike = self.phonetic.codes(candidate.word).iter().any(|code| codes.contains(code));
+            candidate.breakdown.phonetic_bonus = Some(if alike { self.weight } else { 0.0 });
+        }
+        crate::rank::ScoreRanker.rank(word, candidates);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn soundex() {
+        use crate::soundex;
+        assert_eq!(soundex("Rubin"), "R150");
+        assert_eq!(soundex("Tymczak"), "T522");
+        assert_eq!(soundex("Pfister"), "P236");
+        assert_eq!(soundex("Honeyman"), "H555");
+        assert_eq!(soundex("  o'hara "), "O600");
+        assert_eq!(soundex("ünïcode"), "N230");
+        assert_eq!(soundex("42"), "");
+    }
+
+    #[test]
+    fn refined_soundex() {
+        use crate::refined_soundex;
+        assert_eq!(refined_soundex("Hayers"), "H093");
+        assert_eq!(refined_soundex("a"), "A0");
+        assert_eq!(refined_soundex(""), "");
+    }
+
+    #[test]
+    fn sounds_alike() {
+        use crate::{Phonetic, RefinedSoundex, Soundex};
+        assert!(Soundex.sounds_alike("Smith", "Smyth"));
+        assert!(!Soundex.sounds_alike("cell", "sell"));
+        assert!(!Soundex.sounds_alike("", "..."));
+        assert!(RefinedSoundex.sounds_alike("sell", "seal"));
+        let boxed: Box<dyn Phonetic> = Box::new(RefinedSoundex);
+        assert!(!boxed.sounds_alike("cell", "sell"));
+    }
+
+    #[test]
+    fn double_metaphone() {
+        let codes = |word| {
+            let (primary, alternate) = crate::double_metaphone(word);
+            format!("{} {}", primary, alternate)
+        };
+        assert_eq!(codes("jumped"), "JMPT AMPT");
+        assert_eq!(codes("Schmidt"), "XMT SMT");
+        assert_eq!(codes("Xavier"), "SF SFR");
+        assert_eq!(codes("Michael"), "MKL MXL");
+        assert_eq!(codes("Caesar"), "SSR SSR");
+        assert_eq!(codes("Chianti"), "KNT KNT");
+        assert_eq!(codes("Gallegos"), "KLKS KKS");
+        assert_eq!(codes("Filipowicz"), "FLPT FLPF");
+        assert_eq!(codes("laugh"), "LF LF");
+        assert_eq!(codes("Hugh"), "H H");
+        assert_eq!(codes("thumb"), "0M TM");
+        assert_eq!(codes("façade"), "FST FST");
+        assert_eq!(codes(" "), " ");
+        use crate::{DoubleMetaphone, Phonetic};
+        assert_eq!(DoubleMetaphone.codes("fox"), vec!["FKS"]);
+        assert!(DoubleMetaphone.sounds_alike("Smith", "Schmidt"));
+    }
+
+    #[test]
+    fn phonetic_levenshtein() {
+        use crate::{DistanceMetric, PhoneticLevenshtein, Soundex};
+        let metric = PhoneticLevenshtein::new(Soundex, 2);
+        assert_eq!(metric.distance("Smith", "Smyth"), 1);
+        assert_eq!(metric.distance("Smith", "Smith"), 0);
+        assert_eq!(metric.distance("Robert", "Rupert"), 1);
+        assert_eq!(metric.distance("cell", "sell"), 1);
+        assert_eq!(metric.distance_bounded("Robert", "Rupert", 1).within(), Some(1));
+        assert!(metric.distance_bounded("Robert", "Rubin", 1).within().is_none());
+        assert_eq!(metric.length_lower_bound(2, 7), 3);
+        assert_eq!(PhoneticLevenshtein::new(Soundex, 0).distance("Robert", "Rupert"), 2);
+        // the id tells settings apart
+        assert_eq!(metric.id(), "phonetic_levenshtein:2:soundex");
+        assert_ne!(PhoneticLevenshtein::new(Soundex, 1).id(), metric.id());
+        assert_ne!(PhoneticLevenshtein::new(crate::RefinedSoundex, 2).id(), metric.id());
+    }
+
+    #[test]
+    #[cfg(feature = "rank")]
+    fn phonetic_ranker() {
+        use crate::{Candidate, PhoneticRanker, Ranker, ScoreBreakdown, Soundex};
+        let mut candidates: Vec<Candidate> = [("cite", 1), ("site", 2), ("sight", 2)]
+            .iter()
+            .map(|&(word, distance)| Candidate { word, breakdown: ScoreBreakdown::from_distance(distance) })
+            .collect();
+        PhoneticRanker::new(Soundex, 0.5).rank("syte", &mut candidates);
+        assert_eq!(candidates.iter().map(|candidate| candidate.word).collect::<Vec<_>>(), vec!["cite", "site", "sight"]);
+        assert_eq!(candidates[1].breakdown.phonetic_bonus, Some(0.5));
+        assert_eq!(candidates[2].breakdown.phonetic_bonus, Some(0.0));
+        PhoneticRanker::new(Soundex, 1.5).rank("syte", &mut candidates);
+        assert_eq!(candidates.iter().map(|candidate| candidate.word).collect::<Vec<_>>(), vec!["site", "cite", "sight"]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn soundex_shape(word in "\\PC{0,10}") {
+            let code = crate::soundex(&word);
+            proptest::prop_assert!(code.is_empty() || (code.len() == 4 && code[1..].bytes().all(|digit| digit.is_ascii_digit())));
+            proptest::prop_assert_eq!(code.is_empty(), crate::refined_soundex(&word).is_empty());
+        }
+    }
+}
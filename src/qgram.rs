@@ -0,0 +1,233 @@
+//! A dictionary indexed by character trigrams, so queries only measure the
+//! distance to words that share enough of them with the word.
+
+use crate::dict::Dictionary;
+use crate::distance::{levenshtein_distance_bounded_in, DistanceScratch};
+use crate::metrics::Similarity;
+
+/// Pads words on both sides so every character, even in one or two character
+/// words, is part of three trigrams.
+const PADDING: char = '\0';
+
+/// The distinct trigrams of `word`, sorted.
+fn trigrams(word: &str) -> Vec<[char; 3]> {
+    let chars: Vec<char> = [PADDING, PADDING]
+        .iter()
+        .copied()
+        .chain(word.chars())
+        .chain([PADDING, PADDING].iter().copied())
+        .collect();
+    let mut grams: Vec<[char; 3]> = chars.windows(3).map(|gram| [gram[0], gram[1], gram[2]]).collect();
+    grams.sort_unstable();
+    grams.dedup();
+    grams
+}
+
+/// How the trigrams two words share are turned into a similarity from 0.0 to
+/// 1.0. Also usable as a [`Similarity`] on its own.
+/// ```
+/// use spelling::{QGramMeasure, Similarity};
+/// assert_eq!(QGramMeasure::Jaccard.similarity("thing", "thing"), 1.0);
+/// assert!(QGramMeasure::Dice.similarity("thing", "thinga") > QGramMeasure::Jaccard.similarity("thing", "thinga"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QGramMeasure {
+    /// Shared trigrams over all the trigrams of either word.
+    #[default]
+    Jaccard,
+    /// Twice the shared trigrams over the trigrams of both words, the
+    /// Sørensen–Dice coefficient. Never lower than Jaccard.
+    Dice,
+}
+
+impl QGramMeasure {
+    /// The similarity of words with `a` and `b` distinct trigrams, `shared`
+    /// of them in common.
+    fn score(self, shared: usize, a: usize, b: usize) -> f64 {
+        match self {
+            QGramMeasure::Jaccard => shared as f64 / (a + b - shared) as f64,
+            QGramMeasure::Dice => (2 * shared) as f64 / (a + b) as f64,
+        }
+    }
+}
+
+impl Similarity for QGramMeasure {
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        let (a, b) = (trigrams(a), trigrams(b));
+        let shared = a.iter().filter(|gram| b.binary_search(gram).is_ok()).count();
+        self.score(shared, a.len(), b.len())
+    }
+}
+
+/// Words indexed by their trigrams. [`suggest`](QGramIndex::suggest) counts
+/// the trigrams each word shares with the query from the index, skips the
+/// words whose [`QGramMeasure`] is below the threshold, and only measures the
+/// Levenshtein distance to the rest.
+///
+/// The default threshold of 0.0 skips nothing, so suggestions are exactly
+/// [`spellcheck`](crate::spellcheck)'s. A higher threshold is faster but can
+/// miss matches: every edit changes up to three trigrams, so short words and
+/// large distances lose the most.
+/// ```
+/// use spelling::{QGramIndex, QGramMeasure};
+/// let index: QGramIndex = "thin\nthing\nthings\nzebra".lines().collect();
+/// let index = index.measure(QGramMeasure::Dice).threshold(0.4);
+/// assert_eq!(index.suggest("thinga", 2), vec!["thing", "things", "thin"]);
+/// assert!(index.suggest("zzz", 5).is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QGramIndex {
+    words: Vec<String>,
+    /// How many distinct trigrams each word has.
+    gram_counts: Vec<usize>,
+    /// The indices of the words with each trigram, in order.
+    postings: std::collections::HashMap<[char; 3], Vec<usize>>,
+    measure: QGramMeasure,
+    threshold: f64,
+}
+
+impl QGramIndex {
+    /// Indexes the words of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+
+    /// Sets how shared trigrams are scored, builder style. Defaults to
+    /// [`QGramMeasure::Jaccard`].
+    pub fn measure(mut self, measure: QGramMeasure) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    /// Sets how shared trigrams are scored.
+    pub fn set_measure(&mut self, measure: QGramMeasure) {
+        self.measure = measure;
+    }
+
+    /// Only measures the distance to words at least `threshold` similar to
+    /// the query, builder style.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the similarity words need to be measured, see
+    /// [`threshold`](QGramIndex::threshold).
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+    }
+
+    /// The number of words, repeats included.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Adds `word` after every word already in the index.
+    pub fn insert(&mut self, word: &str) {
+        let index = self.words.len();
+        let grams = trigrams(word);
+        self.gram_counts.push(grams.len());
+        for gram in grams {
+            self.postings.entry(gram).or_default().push(index);
+        }
+        self.words.push(word.to_owned());
+    }
+
+    /// The words at least [`threshold`](QGramIndex::threshold) similar to
+    /// `word`, in insertion order.
+    pub fn candidates(&self, word: &str) -> Vec<&str> {
+        self.candidate_indices(word)
+            .map(|index| self.words[index].as_str())
+            .collect()
+    }
+
+    fn candidate_indices(&self, word: &str) -> impl Iterator<Item = usize> + '_ {
+        let grams = trigrams(word);
+        let mut shared = vec![0; self.words.len()];
+        for gram in &grams {
+            for &index in self.postings.get(gram).into_iter().flatten() {
+                shared[index] += 1;
+            }
+        }
+        let query_grams = grams.len();
+        shared.into_iter().enumerate().filter_map(move |(index, shared)| {
+            let similarity = self.measure.score(shared, query_grams, self.gram_counts[index]);
+            (similarity >= self.threshold).then_some(index)
+        })
+    }
+
+    /// Returns the candidates within `distance` of `word`, sorted by distance
+    /// with ties in insertion order.
+    pub fn suggest(&self, word: &str, distance: usize) -> Vec<&str> {
+        let mut scratch = DistanceScratch::new();
+        let mut found: Vec<(usize, usize)> = self
+            .candidate_indices(word)
+            .filter_map(|index| {
+                levenshtein_distance_bounded_in(&mut scratch, word, &self.words[index], distance)
+                    .within()
+                    .map(|distance| (distance, index))
+            })
+            .collect();
+        found.sort_unstable();
+        found.into_iter().map(|(_, index)| self.words[index].as_str()).collect()
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does.
+impl<'a> std::iter::FromIterator<&'a str> for QGramIndex {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut index = QGramIndex::default();
+        for word in iter.into_iter().filter(|word| !word.is_empty()) {
+            index.insert(word);
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn qgram() {
+        use crate::{QGramIndex, QGramMeasure, Similarity};
+        assert_eq!(super::trigrams("ab").len(), 4);
+        assert_eq!(QGramMeasure::Jaccard.similarity("ab", "ba"), 0.0);
+        assert_eq!(QGramMeasure::Jaccard.similarity("", ""), 1.0);
+        assert_eq!(QGramMeasure::Dice.similarity("abab", "ab"), 0.8);
+
+        let index = QGramIndex::from_dictionary(&crate::Dictionary::from_newline_separated("thin\nthing\nnaïve\nzebra"));
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.candidates("thinga"), vec!["thin", "thing", "naïve", "zebra"]);
+        let index = index.threshold(0.3);
+        assert_eq!(index.candidates("thinga"), vec!["thin", "thing"]);
+        assert_eq!(index.suggest("naive", 1), vec!["naïve"]);
+        // pruning can lose real matches
+        assert!(index.threshold(0.5).suggest("naive", 1).is_empty());
+        assert!(QGramIndex::default().suggest("a", 5).is_empty());
+    }
+
+    #[cfg(feature = "check")]
+    proptest::proptest! {
+        #[test]
+        fn qgram_matches_spellcheck(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            word in "[abc]{0,8}",
+            distance in 0usize..4,
+            threshold in 0.0..1.0,
+        ) {
+            let index: crate::QGramIndex = words.iter().map(String::as_str).collect();
+            let dictionary_string = words.join("\n");
+            let expected = crate::spellcheck(&dictionary_string, &word, distance);
+            proptest::prop_assert_eq!(index.suggest(&word, distance), expected.clone());
+            let index = index.threshold(threshold);
+            let pruned = index.suggest(&word, distance);
+            let mut rest = expected.into_iter();
+            proptest::prop_assert!(pruned.iter().all(|word| rest.any(|expected| expected == *word)));
+        }
+    }
+}
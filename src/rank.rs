@@ -0,0 +1,289 @@
+//! Scoring and ranking suggestions.
+
+/// The individual components that went into ranking a suggestion.
+///
+/// Signals that weren't used for a query are `None`, so a breakdown only ever
+/// shows what actually contributed to the final [`score`](ScoreBreakdown::score).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreBreakdown {
+    /// The edit distance between the query and the suggestion.
+    pub distance: usize,
+    /// Substitutions that only changed case or accents, see
+    /// [`DistanceMode::CaseAndAccentTolerant`](crate::DistanceMode::CaseAndAccentTolerant). These aren't counted in
+    /// `distance`.
+    pub minor_edits: usize,
+    /// Bonus from how common the suggestion is.
+    pub frequency_weight: Option<f64>,
+    /// Bonus for substitutions between physically adjacent keys.
+    pub keyboard_bonus: Option<f64>,
+    /// Bonus for sounding like the query, see
+    /// [`PhoneticRanker`](crate::PhoneticRanker).
+    pub phonetic_bonus: Option<f64>,
+    /// The combined score from a [`Scorer`], which replaces everything
+    /// above in [`score`](ScoreBreakdown::score).
+    pub weighted_score: Option<f64>,
+}
+
+impl ScoreBreakdown {
+    /// Creates a breakdown where the only signal is the edit distance.
+    pub fn from_distance(distance: usize) -> Self {
+        ScoreBreakdown {
+            distance,
+            minor_edits: 0,
+            frequency_weight: None,
+            keyboard_bonus: None,
+            phonetic_bonus: None,
+            weighted_score: None,
+        }
+    }
+
+    /// The combined score, lower is better. This is the distance plus
+    /// [`MINOR_EDIT_COST`] for every minor edit, minus every bonus that was
+    /// applied, unless a [`Scorer`] set
+    /// [`weighted_score`](ScoreBreakdown::weighted_score).
+    pub fn score(&self) -> f64 {
+        if let Some(score) = self.weighted_score {
+            return score;
+        }
+        self.distance as f64 + self.minor_edits as f64 * MINOR_EDIT_COST
+            - self.frequency_weight.unwrap_or(0.0)
+            - self.keyboard_bonus.unwrap_or(0.0)
+            - self.phonetic_bonus.unwrap_or(0.0)
+    }
+}
+
+/// A suggestion along with the breakdown of how it was scored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate<'a> {
+    /// The word from the dictionary.
+    pub word: &'a str,
+    /// Why it ranked where it did.
+    pub breakdown: ScoreBreakdown,
+}
+
+/// A ranked suggestion with what went into ranking it, so callers can show
+/// or re-rank suggestions without measuring distances again. Returned by
+/// [`spellcheck_ranked`](crate::spellcheck_ranked) and
+/// [`SpellChecker::suggest_ranked`](crate::SpellChecker::suggest_ranked).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion<'a> {
+    /// The word from the dictionary.
+    pub word: &'a str,
+    /// The edit distance between the query and the word.
+    pub distance: usize,
+    /// How often the word occurs, 0 if unknown.
+    pub frequency: u64,
+    /// The combined score the suggestions were sorted by, lower is better.
+    pub score: f64,
+}
+
+/// Orders (and may rescore) candidates in a [`Pipeline`](crate::Pipeline). Implemented for any
+/// `Fn(&str, &mut [Candidate])` where the first argument is the query.
+pub trait Ranker: Send + Sync {
+    /// Reorders `candidates` so the best suggestion for `word` comes first.
+    fn rank(&self, word: &str, candidates: &mut [Candidate]);
+}
+
+impl<F> Ranker for F
+where
+    F: Fn(&str, &mut [Candidate]) + Send + Sync,
+{
+    fn rank(&self, word: &str, candidates: &mut [Candidate]) {
+        self(word, candidates)
+    }
+}
+
+/// Sorts candidates by [`ScoreBreakdown::score`]. The sort is stable, so ties
+/// keep the order they came in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoreRanker;
+
+impl Ranker for ScoreRanker {
+    fn rank(&self, _word: &str, candidates: &mut [Candidate]) {
+        candidates.sort_by(|a, b| {
+            a.breakdown
+                .score()
+                .partial_cmp(&b.breakdown.score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+}
+
+/// What a case-only or accent-only substitution adds to
+/// [`ScoreBreakdown::score`]. Small enough that a handful of them still rank
+/// above a single real edit.
+pub const MINOR_EDIT_COST: f64 = 0.1;
+
+/// How a [`SpellChecker`](crate::SpellChecker) orders its suggestions.
+#[derive(Default)]
+pub enum RankingStrategy {
+    /// By edit distance only, ties keep dictionary order. This is the default
+    /// and matches [`spellcheck`](crate::spellcheck).
+    #[default]
+    DistanceOnly,
+    /// By edit distance, ties broken by how common the word is.
+    Frequency,
+    /// By the noisy channel model, `P(word) * P(typo | word)`, where every
+    /// edit is assumed to be [`NOISY_CHANNEL_EDIT_PROBABILITY`] likely. Unlike
+    /// [`Frequency`](RankingStrategy::Frequency) a very common word can beat a
+    /// rare one that is closer.
+    NoisyChannel,
+    /// By a weighted sum of several signals, see [`Scorer`].
+    Scored(Scorer),
+    /// Your own ranker.
+    Custom(Box<dyn Ranker>),
+}
+
+impl std::fmt::Debug for RankingStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RankingStrategy::DistanceOnly => f.write_str("DistanceOnly"),
+            RankingStrategy::Frequency => f.write_str("Frequency"),
+            RankingStrategy::NoisyChannel => f.write_str("NoisyChannel"),
+            RankingStrategy::Scored(scorer) => f.debug_tuple("Scored").field(scorer).finish(),
+            RankingStrategy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Merges the edit distance, whether a suggestion sounds like the query
+/// and how common it is into one score, lower is better:
+///
+/// `distance_weight * distance / length - phonetic_weight * sounds_alike - frequency_weight * frequency`
+///
+/// where `length` is the longer word's length in `char`s, `sounds_alike` is
+/// 1 or 0 according to the [`Phonetic`](crate::Phonetic) encoding, and
+/// `frequency` is `ln(1 + count) / ln(1 + max_count)`, so every signal is
+/// between 0 and 1 before weighting. Use it with
+/// [`RankingStrategy::Scored`].
+/// ```
+/// # #[cfg(feature = "check")] {
+/// use spelling::{RankingStrategy, Scorer, SpellChecker};
+/// let scorer = Scorer::new().phonetic_weight(0.5);
+/// let checker = SpellChecker::with_frequencies(vec![("cone", 50), ("phone", 10)])
+///     .ranking(RankingStrategy::Scored(scorer));
+/// let suggestions = checker.suggest_ranked("fone", 2);
+/// assert_eq!(suggestions[0].word, "phone");
+/// assert!(suggestions[0].score < suggestions[1].score);
+/// # }
+/// ```
+pub struct Scorer {
+    distance_weight: f64,
+    phonetic_weight: f64,
+    frequency_weight: f64,
+    phonetic: Box<dyn crate::phonetic::Phonetic>,
+}
+
+impl Scorer {
+    /// Weighs the distance 1.0, phonetics 0.25 and frequency 0.25, with
+    /// [`DoubleMetaphone`](crate::DoubleMetaphone).
+    pub fn new() -> Self {
+        Scorer {
+            distance_weight: 1.0,
+            phonetic_weight: 0.25,
+            frequency_weight: 0.25,
+            phonetic: Box::new(crate::phonetic::DoubleMetaphone),
+        }
+    }
+
+    /// Sets the weight of the normalized edit distance, builder style.
+    pub fn distance_weight(mut self, weight: f64) -> Self {
+        self.distance_weight = weight;
+        self
+    }
+
+    /// Sets the weight of the normalized edit distance.
+    pub fn set_distance_weight(&mut self, weight: f64) {
+        self.distance_weight = weight;
+    }
+
+    /// Sets the weight of sounding alike, builder style.
+    pub fn phonetic_weight(mut self, weight: f64) -> Self {
+        self.phonetic_weight = weight;
+        self
+    }
+
+    /// Sets the weight of sounding alike.
+    pub fn set_phonetic_weight(&mut self, weight: f64) {
+        self.phonetic_weight = weight;
+    }
+
+    /// Sets the weight of the normalized frequency, builder style.
+    pub fn frequency_weight(mut self, weight: f64) -> Self {
+        self.frequency_weight = weight;
+        self
+    }
+
+    /// Sets the weight of the normalized frequency.
+    pub fn set_frequency_weight(&mut self, weight: f64) {
+        self.frequency_weight = weight;
+    }
+
+    /// Sets how words are encoded to tell if they sound alike, builder style.
+    pub fn phonetic(mut self, phonetic: impl crate::phonetic::Phonetic + 'static) -> Self {
+        self.set_phonetic(phonetic);
+        self
+    }
+
+    /// Sets how words are encoded to tell if they sound alike.
+    pub fn set_phonetic(&mut self, phonetic: impl crate::phonetic::Phonetic + 'static) {
+        self.phonetic = Box::new(phonetic);
+    }
+
+    /// The score of suggesting `candidate` `distance` edits away from `word`,
+    /// occurring `count` times where the most common word occurs
+    /// `max_count` times.
+    /// ```
+    /// use spelling::Scorer;
+    /// let scorer = Scorer::new().phonetic_weight(0.0).frequency_weight(0.0);
+    /// assert_eq!(scorer.score("thinga", "thing", 1, 0, 0), 1.0 / 6.0);
+    /// ```
+    pub fn score(&self, word: &str, candidate: &str, distance: usize, count: u64, max_count: u64) -> f64 {
+        let length = word.chars().count().max(candidate.chars().count()).max(1);
+        let mut score = self.distance_weight * distance as f64 / length as f64;
+        if self.phonetic_weight != 0.0 && self.phonetic.sounds_alike(word, candidate) {
+            score -= self.phonetic_weight;
+        }
+        if max_count > 0 {
+            score -= self.frequency_weight * (count as f64).ln_1p() / (max_count as f64).ln_1p();
+        }
+        score
+    }
+}
+
+impl Default for Scorer {
+    fn default() -> Self {
+        Scorer::new()
+    }
+}
+
+impl std::fmt::Debug for Scorer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scorer")
+            .field("distance_weight", &self.distance_weight)
+            .field("phonetic_weight", &self.phonetic_weight)
+            .field("frequency_weight", &self.frequency_weight)
+            .field("phonetic", &self.phonetic.id())
+            .finish()
+    }
+}
+
+/// How a [`SpellChecker`](crate::SpellChecker) orders suggestions its
+/// [`RankingStrategy`] scores the same. Every policy is deterministic, so the
+/// same dictionary and query always give the same order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortPolicy {
+    /// Ties keep dictionary order. This is the default and matches
+    /// [`spellcheck`](crate::spellcheck).
+    #[default]
+    DictionaryOrder,
+    /// Ties are sorted alphabetically, by `char`, so the order doesn't depend
+    /// on how the dictionary was ordered.
+    Lexicographic,
+    /// Ties are sorted by how common the word is, most common first, then in
+    /// dictionary order.
+    Frequency,
+}
+
+/// The probability of a single edit used by [`RankingStrategy::NoisyChannel`].
+pub const NOISY_CHANNEL_EDIT_PROBABILITY: f64 = 0.01;
@@ -0,0 +1,233 @@
+//! A [SymSpell](https://github.com/wolfgarbe/SymSpell) style index, which
+//! trades memory for very fast lookups.
+
+use crate::dict::Dictionary;
+use crate::distance::levenshtein_distance_bounded;
+use crate::index_file::{Reader, Writer};
+
+const MAGIC: &[u8; 8] = b"SPELLSYM";
+
+/// Precomputes every string each word can become with up to `max_distance`
+/// deletions. Two words within `max_distance` of each other always share one
+/// of those, so a query only has to look up its own deletions and check the
+/// few words they point to.
+///
+/// Memory grows quickly with `max_distance`, 1 or 2 is typical.
+/// ```
+/// use spelling::SymSpellIndex;
+/// let index = SymSpellIndex::new(["thin", "thing", "things"], 2);
+/// assert_eq!(index.suggest("thinga", 1), vec!["thing", "things"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SymSpellIndex {
+    words: Vec<String>,
+    /// Every deletion of every word, to the words it came from in order.
+    deletes: std::collections::HashMap<String, Vec<usize>>,
+    max_distance: usize,
+}
+
+/// Every string `word` can become with up to `max_distance` deletions,
+/// including itself.
+fn deletes(word: &str, max_distance: usize) -> std::collections::HashSet<String> {
+    let mut all = std::collections::HashSet::new();
+    all.insert(word.to_owned());
+    let mut current = vec![word.to_owned()];
+    for _ in 0..max_distance {
+        let mut next = Vec::new();
+        for string in &current {
+            for (index, c) in string.char_indices() {
+                let mut deleted = string.clone();
+                deleted.replace_range(index..index + c.len_utf8(), "");
+                if all.insert(deleted.clone()) {
+                    next.push(deleted);
+                }
+            }
+        }
+        current = next;
+    }
+    all
+}
+
+impl SymSpellIndex {
+    /// Builds an index over `words` for queries up to `max_distance`. Empty
+    /// words are skipped.
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>, max_distance: usize) -> Self {
+        let mut index = SymSpellIndex {
+            max_distance,
+            ..SymSpellIndex::default()
+        };
+        for word in words {
+            let word = word.as_ref();
+            if word.is_empty() {
+                continue;
+            }
+            let position = index.words.len();
+            for delete in deletes(word, max_distance) {
+                index.deletes.entry(delete).or_default().push(position);
+            }
+            index.words.push(word.to_owned());
+        }
+        index
+    }
+
+    /// Builds an index over a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary, max_distance: usize) -> Self {
+        SymSpellIndex::new(dictionary.iter(), max_distance)
+    }
+
+    /// The largest distance the index answers quickly.
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// The number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Saves the index in a compact binary format, deletions included, so it
+    /// can be built once and [`load`](SymSpellIndex::load)ed at startup.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let mut writer = Writer::new(MAGIC);
+        writer.u64(self.max_distance as u64);
+        writer.usize(self.words.len())?;
+        for word in &self.words {
+            writer.str(word)?;
+        }
+        writer.usize(self.deletes.len())?;
+        for (delete, indices) in &self.deletes {
+            writer.str(delete)?;
+            writer.usize(indices.len())?;
+            for &index in indices {
+                writer.usize(index)?;
+            }
+        }
+        writer.save(path.as_ref())
+    }
+
+    /// Loads an index saved by [`save`](SymSpellIndex::save). Fails with
+    /// [`std::io::ErrorKind::InvalidData`] if the file is corrupt.
+    /// ```no_run
+    /// use spelling::SymSpellIndex;
+    /// let index = SymSpellIndex::load("words.symspell")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let mut reader = Reader::new(&bytes, MAGIC)?;
+        let max_distance = std::convert::TryFrom::try_from(reader.u64()?).unwrap_or(usize::MAX);
+        let word_count = reader.count(4)?;
+        let mut words = Vec::with_capacity(word_count);
+        for _ in 0..word_count {
+            words.push(reader.str()?.to_owned());
+        }
+        let delete_count = reader.count(8)?;
+        let mut deletes = std::collections::HashMap::with_capacity(delete_count);
+        for _ in 0..delete_count {
+            let delete = reader.str()?.to_owned();
+            let mut indices = Vec::with_capacity(reader.count(4)?);
+            for _ in 0..indices.capacity() {
+                indices.push(reader.index(word_count)?);
+            }
+            deletes.insert(delete, indices);
+        }
+        reader.finish()?;
+        Ok(SymSpellIndex {
+            words,
+            deletes,
+            max_distance,
+        })
+    }
+
+    /// Returns the words within `distance` of `word`, sorted by distance with
+    /// ties in word order, the same as [`spellcheck`](crate::spellcheck). A
+    /// `distance` above [`max_distance`](SymSpellIndex::max_distance) falls
+    /// back to checking every word.
+    pub fn suggest(&self, word: &str, distance: usize) -> Vec<&str> {
+        let candidates: Vec<usize> = if distance > self.max_distance {
+            (0..self.words.len()).collect()
+        } else {
+            let mut candidates: Vec<usize> = deletes(word, distance)
+                .iter()
+                .filter_map(|delete| self.deletes.get(delete))
+                .flatten()
+                .copied()
+                .collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+            candidates
+        };
+        let mut found: Vec<(usize, &str)> = candidates
+            .into_iter()
+            .filter_map(|index| {
+                let candidate = self.words[index].as_str();
+                levenshtein_distance_bounded(word, candidate, distance)
+                    .within()
+                    .map(|distance| (distance, candidate))
+            })
+            .collect();
+        // stable, candidates are in word order
+        found.sort_by_key(|&(distance, _)| distance);
+        found.into_iter().map(|(_, word)| word).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn deletes() {
+        let mut deletes: Vec<String> = super::deletes("abc", 2).into_iter().collect();
+        deletes.sort();
+        assert_eq!(deletes, vec!["a", "ab", "abc", "ac", "b", "bc", "c"]);
+        assert_eq!(super::deletes("é", 3).len(), 2);
+    }
+
+    #[test]
+    fn symspell() {
+        let index = crate::SymSpellIndex::new(vec!["thin", "", "thing", "thin", "naïve"], 1);
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.suggest("thin", 0), vec!["thin", "thin"]);
+        assert_eq!(index.suggest("naive", 1), vec!["naïve"]);
+        assert_eq!(index.suggest("thinga", 2), vec!["thing", "thin", "thin"]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("spelling-symspell-{}", std::process::id()));
+        let index = crate::SymSpellIndex::new(["thin", "thing", "thin", "things", "hïnt"], 2);
+        index.save(&path).unwrap();
+        let loaded = crate::SymSpellIndex::load(&path).unwrap();
+        assert_eq!(loaded.max_distance(), 2);
+        assert_eq!(loaded.suggest("thinga", 2), index.suggest("thinga", 2));
+        assert_eq!(loaded.suggest("hint", 1), vec!["hïnt"]);
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.pop();
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(crate::SymSpellIndex::load(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "check")]
+    proptest::proptest! {
+        #[test]
+        fn symspell_matches_spellcheck(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            word in "[abc]{0,6}",
+            distance in 0usize..4,
+        ) {
+            let index = crate::SymSpellIndex::new(&words, 2);
+            let dictionary = words.join("\n");
+            proptest::prop_assert_eq!(
+                index.suggest(&word, distance),
+                crate::spellcheck(&dictionary, &word, distance)
+            );
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Splitting text into words.
+
+/// Whether `c` is an apostrophe, straight or curly.
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '\u{2019}'
+}
+
+/// Iterates over the words in `text` along with the byte offset each one
+/// starts at. A word is a run of alphanumeric characters, and apostrophes
+/// (`'` or `’`) between them, so "don't" and "O'Brien" stay whole. Anything
+/// else splits words, hyphens included, so each part of "well-known" is its
+/// own word, the way most dictionaries list them.
+/// ```
+/// use spelling::words;
+/// assert_eq!(
+///     words("Hello, world! Don't 'quote' well-known").collect::<Vec<_>>(),
+///     vec![(0, "Hello"), (7, "world"), (14, "Don't"), (21, "quote"), (28, "well"), (33, "known")]
+/// );
+/// ```
+pub fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, _) = rest.find(|(_, c)| c.is_alphanumeric())?;
+        let mut end = text.len();
+        while let Some(&(index, c)) = rest.peek() {
+            if c.is_alphanumeric() {
+                rest.next();
+                continue;
+            }
+            // an apostrophe only counts with a letter or digit on both sides
+            let next = text[index + c.len_utf8()..].chars().next();
+            if is_apostrophe(c) && next.is_some_and(char::is_alphanumeric) {
+                rest.next();
+                continue;
+            }
+            end = index;
+            break;
+        }
+        Some((start, &text[start..end]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn words() {
+        let text = "  thïs is\tsome—text 42 ";
+        let words: Vec<_> = crate::words(text).collect();
+        assert_eq!(words, vec![(2, "thïs"), (8, "is"), (11, "some"), (18, "text"), (23, "42")]);
+        for (start, word) in words {
+            assert_eq!(&text[start..start + word.len()], word);
+        }
+        assert_eq!(crate::words("").count(), 0);
+        assert_eq!(crate::words("!?").count(), 0);
+
+        let text = "rock 'n' roll, the dogs' bones aren\u{2019}t ''a''b";
+        let words: Vec<&str> = crate::words(text).map(|(_, word)| word).collect();
+        assert_eq!(words, vec!["rock", "n", "roll", "the", "dogs", "bones", "aren\u{2019}t", "a", "b"]);
+    }
+}
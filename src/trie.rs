@@ -0,0 +1,284 @@
+//! A [trie](https://en.wikipedia.org/wiki/Trie) of the dictionary, walked
+//! with a [`LevenshteinAutomaton`] to suggest corrections and complete partly
+//! typed words.
+
+use crate::automaton::LevenshteinAutomaton;
+use crate::dict::Dictionary;
+
+/// Words stored one `char` per edge, so words sharing a prefix share a path.
+/// Searches carry one row of the distance table per node, so a shared prefix
+/// is measured once for all the words under it, and a branch is dropped as
+/// soon as no row value is within the distance. Unlike a
+/// [`Dawg`](crate::Dawg) suffixes aren't shared, so each node is the end of
+/// at most one path and can complete to the words under it, and results can
+/// keep insertion order.
+///
+/// Repeated words are stored once, keeping their first position.
+/// ```
+/// use spelling::Trie;
+/// let trie: Trie = "restore\nrestaurant\nrust\nzebra".lines().collect();
+/// assert_eq!(trie.suggest("rost", 1), vec!["rust"]);
+/// assert_eq!(trie.suggest("restor", 3), vec!["restore", "rust"]);
+/// assert_eq!(trie.complete("resta", 0, 10), vec!["restaurant"]);
+/// assert_eq!(trie.complete("resta", 1, 10), vec!["restaurant", "restore"]);
+/// assert_eq!(trie.complete("resta", 1, 1), vec!["restaurant"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<TrieNode>,
+    words: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    /// Sorted by `char`.
+    children: Vec<(char, usize)>,
+    /// The index of the word ending here.
+    word: Option<usize>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie {
+            nodes: vec![TrieNode::default()],
+            words: Vec::new(),
+        }
+    }
+}
+
+impl Trie {
+    /// Builds a trie out of a [`Dictionary`].
+    pub fn from_dictionary(dictionary: &Dictionary) -> Self {
+        dictionary.iter().collect()
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The words, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// Adds `word`, returning whether it wasn't there already.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut current = 0;
+        for c in word.chars() {
+            current = match self.nodes[current].children.binary_search_by_key(&c, |&(c, _)| c) {
+                Ok(index) => self.nodes[current].children[index].1,
+                Err(index) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[current].children.insert(index, (c, child));
+                    child
+                }
+            };
+        }
+        if self.nodes[current].word.is_some() {
+            return false;
+        }
+        self.nodes[current].word = Some(self.words.len());
+        self.words.push(word.to_owned());
+        true
+    }
+
+    /// Whether `word` is in the trie.
+    pub fn contains(&self, word: &str) -> bool {
+        self.find(word).is_some_and(|node| self.nodes[node].word.is_some())
+    }
+
+    fn find(&self, word: &str) -> Option<usize> {
+        word.chars().try_fold(0, |current, c| {
+            let children = &self.nodes[current].children;
+            let index = children.binary_search_by_key(&c, |&(c, _)| c).ok()?;
+            Some(children[index].1)
+        })
+    }
+
+    /// Returns the words within `max_distance` Levenshtein edits of `word`,
+    /// sorted by distance with ties in insertion order.
+    pub fn suggest(&self, word: &str, max_distance: usize) -> Vec<&str> {
+        let mut found = self.search(word, max_distance, false);
+        found.sort_unstable();
+        found.into_iter().map(|(_, index)| self.words[index].as_str()).collect()
+    }
+
+    /// Returns up to `k` words with a prefix within `max_distance`
+    /// Levenshtein edits of `prefix`, sorted by the distance of their closest
+    /// prefix and then in insertion order.
+    pub fn complete(&self, prefix: &str, max_distance: usize, k: usize) -> Vec<&str> {
+        let mut found = self.completions(prefix, max_distance);
+        found.sort_unstable();
+        found.truncate(k);
+        found.into_iter().map(|(_, index)| self.words[index].as_str()).collect()
+    }
+
+    /// Every word with a prefix within `max_distance` of `prefix`, as the
+    /// distance of its closest prefix and its index, in no particular order.
+    pub(crate) fn completions(&self, prefix: &str, max_distance: usize) -> Vec<(usize, usize)> {
+        self.search(prefix, max_distance, true)
+    }
+
+    fn search(&self, query: &str, max_distance: usize, completing: bool) -> Vec<(usize, usize)> {
+        let automaton = LevenshteinAutomaton::new(query, max_distance);
+        let mut search = Search {
+            automaton: &automaton,
+            nodes: &self.nodes,
+            width: query.chars().count() + 1,
+            completing,
+            rows: automaton.start().0,
+            found: Vec::new(),
+        };
+        search.walk(0, 0, None);
+        search.found
+    }
+
+    /// The word with `index`, see [`completions`](Trie::completions).
+    #[cfg(feature = "check")]
+    pub(crate) fn word(&self, index: usize) -> &str {
+        &self.words[index]
+    }
+}
+
+/// Empty words are skipped, like [`Dictionary`] does.
+impl<'a> std::iter::FromIterator<&'a str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut trie = Trie::default();
+        for word in iter.into_iter().filter(|word| !word.is_empty()) {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+/// A depth first walk carrying one automaton row per depth. When completing,
+/// once some prefix on the path matches every word below it is a completion,
+/// but a longer prefix can still match more closely.
+struct Search<'a> {
+    automaton: &'a LevenshteinAutomaton,
+    nodes: &'a [TrieNode],
+    width: usize,
+    completing: bool,
+    /// The rows for the path to the current node, end to end.
+    rows: Vec<usize>,
+    found: Vec<(usize, usize)>,
+}
+
+impl Search<'_> {
+    fn walk(&mut self, current: usize, depth: usize, best: Option<usize>) {
+        let width = self.width;
+        let row = depth * width..(depth + 1) * width;
+        let distance = self.automaton.row_distance(&self.rows[row.clone()]);
+        let best = match (best, distance) {
+            (Some(best), Some(distance)) if self.completing => Some(best.min(distance)),
+            (best, distance) if self.completing => best.or(distance),
+            _ => distance,
+        };
+        if self.completing && best == Some(0) {
+            return self.collect(current, 0);
+        }
+        let node = &self.nodes[current];
+        if let (Some(distance), Some(index)) = (best, node.word) {
+            self.found.push((distance, index));
+        }
+        if self.rows.len() < row.end + width {
+            self.rows.resize(row.end + width, 0);
+        }
+        for &(c, child) in &node.children {
+            let (previous, next) = self.rows.split_at_mut(row.end);
+            self.automaton.step_into(&previous[row.clone()], c, &mut next[..width]);
+            if (self.completing && best.is_some()) || self.automaton.row_can_match(&next[..width]) {
+                self.walk(child, depth + 1, best);
+            }
+        }
+    }
+
+    /// Every word under `current`, which can't get any closer.
+    fn collect(&mut self, current: usize, distance: usize) {
+        let node = &self.nodes[current];
+        if let Some(index) = node.word {
+            self.found.push((distance, index));
+        }
+        for &(_, child) in &node.children {
+            self.collect(child, distance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn trie() {
+        let mut trie = crate::Trie::from_dictionary(&crate::Dictionary::from_newline_separated(
+            "thin\nthing\nthin\nnaïve\n\nthings",
+        ));
+        assert_eq!(trie.len(), 4);
+        assert!(trie.contains("naïve"));
+        assert!(!trie.contains("thi"));
+        assert!(!trie.contains(""));
+        assert!(!trie.insert("thing"));
+        assert_eq!(trie.iter().collect::<Vec<_>>(), vec!["thin", "thing", "naïve", "things"]);
+        assert_eq!(trie.complete("thin", 0, 10), vec!["thin", "thing", "things"]);
+        assert_eq!(trie.complete("nai", 0, 10), Vec::<&str>::new());
+        assert_eq!(trie.complete("nai", 1, 10), vec!["naïve"]);
+        // "thing" matches exactly further down
+        assert_eq!(trie.complete("thingx", 1, 10), vec!["thing", "things"]);
+        assert_eq!(trie.complete("", 0, 2), vec!["thin", "thing"]);
+        assert!(crate::Trie::default().complete("", 5, 5).is_empty());
+        assert_eq!(trie.suggest("thin", 0), vec!["thin"]);
+        assert_eq!(trie.suggest("naive", 1), vec!["naïve"]);
+        assert_eq!(trie.suggest("thinga", 2), vec!["thing", "things", "thin"]);
+        assert!(crate::Trie::default().suggest("a", 5).is_empty());
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn suggestions_match_a_scan(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            word in "[abc]{0,6}",
+            max in 0usize..4,
+        ) {
+            let trie: crate::Trie = words.iter().map(String::as_str).collect();
+            let mut expected: Vec<(usize, usize, &str)> = trie
+                .iter()
+                .enumerate()
+                .map(|(index, candidate)| (crate::levenshtein_distance(&word, candidate), index, candidate))
+                .filter(|&(distance, _, _)| distance <= max)
+                .collect();
+            expected.sort_unstable();
+            let expected: Vec<&str> = expected.into_iter().map(|(_, _, word)| word).collect();
+            proptest::prop_assert_eq!(trie.suggest(&word, max), expected);
+        }
+
+        #[test]
+        fn completions_match_a_scan(
+            words in proptest::collection::vec("[abc]{1,6}", 1..30),
+            prefix in "[abc]{0,5}",
+            max in 0usize..3,
+        ) {
+            let trie: crate::Trie = words.iter().map(String::as_str).collect();
+            let mut found = trie.completions(&prefix, max);
+            found.sort_unstable_by_key(|&(_, index)| index);
+            let expected: Vec<(usize, usize)> = trie
+                .iter()
+                .enumerate()
+                .filter_map(|(index, word)| {
+                    let chars: Vec<char> = word.chars().collect();
+                    (0..=chars.len())
+                        .map(|end| crate::levenshtein_distance(&prefix, &chars[..end].iter().collect::<String>()))
+                        .min()
+                        .filter(|&distance| distance <= max)
+                        .map(|distance| (distance, index))
+                })
+                .collect();
+            proptest::prop_assert_eq!(found, expected);
+        }
+    }
+}
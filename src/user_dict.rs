@@ -0,0 +1,135 @@
+//! Words the user added at runtime, kept apart from the main dictionary so
+//! they can be saved on their own, like an editor's "Add to dictionary".
+
+/// A mutable set of extra words, saved as a newline separated file. On a
+/// [`SpellChecker`](crate::SpellChecker) it's consulted before looking for
+/// corrections, and its words are suggested too.
+/// ```
+/// use spelling::UserDictionary;
+/// let mut user = UserDictionary::new();
+/// assert!(user.add_word("rustacean"));
+/// assert!(!user.add_word("rustacean"));
+/// assert!(user.contains("rustacean"));
+/// assert!(user.remove_word("rustacean"));
+/// assert!(user.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserDictionary {
+    words: std::collections::BTreeSet<String>,
+}
+
+impl UserDictionary {
+    /// An empty user dictionary.
+    pub fn new() -> Self {
+        UserDictionary::default()
+    }
+
+    /// Reads one word per line, ignoring blank lines and surrounding
+    /// whitespace.
+    pub fn from_newline_separated(string: &str) -> Self {
+        string.lines().collect()
+    }
+
+    /// Loads the file at `path`, see
+    /// [`from_newline_separated`](UserDictionary::from_newline_separated). A
+    /// missing file gives an empty dictionary, as nothing has been added yet.
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(UserDictionary::from_newline_separated(&contents)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(UserDictionary::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Writes the words to `path`, sorted, one per line. The file is replaced
+    /// in one step, so a crash while saving leaves the old one.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut temporary = path.to_owned().into_os_string();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, self.to_string())?;
+        std::fs::rename(&temporary, path)
+    }
+
+    /// Adds `word`, returning whether it wasn't there already. Surrounding
+    /// whitespace is trimmed; empty words and words with line breaks can't be
+    /// saved, so they aren't added.
+    pub fn add_word(&mut self, word: &str) -> bool {
+        let word = word.trim();
+        if word.is_empty() || word.contains(['\n', '\r']) {
+            return false;
+        }
+        self.words.insert(word.to_owned())
+    }
+
+    /// Removes `word`, returning whether it was there.
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        self.words.remove(word.trim())
+    }
+
+    /// Whether `word` was added, exactly.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// The stored copy of `word`, if it was added.
+    #[cfg(all(feature = "check", feature = "tokenize"))]
+    pub(crate) fn get(&self, word: &str) -> Option<&str> {
+        self.words.get(word).map(String::as_str)
+    }
+
+    /// The number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether no words were added.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The words, sorted.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.words.iter().map(String::as_str)
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a str> for UserDictionary {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut user = UserDictionary::new();
+        for word in iter {
+            user.add_word(word);
+        }
+        user
+    }
+}
+
+impl std::fmt::Display for UserDictionary {
+    /// The file format, see [`save`](UserDictionary::save).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for word in &self.words {
+            writeln!(f, "{}", word)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("spelling-user-{}", std::process::id()));
+        assert!(crate::UserDictionary::load(&path).unwrap().is_empty());
+
+        let mut user = crate::UserDictionary::from_newline_separated("  zebra\n\napple\nzebra\n");
+        assert_eq!(user.iter().collect::<Vec<_>>(), vec!["apple", "zebra"]);
+        assert!(!user.add_word(" "));
+        assert!(!user.add_word("two\nwords"));
+        assert!(user.add_word("mango "));
+        user.save(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "apple\nmango\nzebra\n");
+        assert_eq!(crate::UserDictionary::load(&path).unwrap(), user);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,43 @@
+//! [`wasm-bindgen`](https://rustwasm.github.io/docs/wasm-bindgen/) exports,
+//! so the crate can be used from JavaScript in the browser. Build a cdylib
+//! with `cargo rustc --release --target wasm32-unknown-unknown --features
+//! wasm --crate-type cdylib` and run `wasm-bindgen` on the `.wasm` file.
+//! ```js
+//! import { SpellChecker } from "spelling";
+//! const checker = new SpellChecker(await (await fetch("words.txt")).text());
+//! checker.suggest("restaraunt", 3);
+//! ```
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A [`SpellChecker`](crate::SpellChecker), exported to JavaScript as
+/// `SpellChecker`.
+#[wasm_bindgen(js_name = SpellChecker)]
+#[derive(Debug)]
+pub struct WasmSpellChecker(crate::SpellChecker);
+
+#[wasm_bindgen(js_class = SpellChecker)]
+impl WasmSpellChecker {
+    /// Creates a checker from a newline separated word list, see
+    /// [`SpellChecker::new`](crate::SpellChecker::new).
+    #[wasm_bindgen(constructor)]
+    pub fn new(dictionary_text: &str) -> WasmSpellChecker {
+        WasmSpellChecker(crate::SpellChecker::new(dictionary_text))
+    }
+
+    /// The words within `max_distance` of `word`, best first, see
+    /// [`SpellChecker::suggest`](crate::SpellChecker::suggest).
+    pub fn suggest(&self, word: &str, max_distance: usize) -> Vec<String> {
+        self.0.suggest(word, max_distance).into_iter().map(str::to_owned).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn wasm_spell_checker() {
+        let checker = crate::WasmSpellChecker::new("thin\nthing\nthings");
+        assert_eq!(checker.suggest("thng", 1), vec!["thing"]);
+        assert!(checker.suggest("xyz", 0).is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+//! Runs the `spelling` binary on files in a temporary directory.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Writes `files` into a fresh directory named after `test`.
+fn directory(test: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("spelling-cli-{}-{}", test, std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    for (name, contents) in files {
+        std::fs::write(directory.join(name), contents).unwrap();
+    }
+    directory
+}
+
+/// Runs the binary in `directory`, returning its stdout and exit code.
+fn run(directory: &PathBuf, args: &[&str]) -> (String, Option<i32>) {
+    let output = Command::new(env!("CARGO_BIN_EXE_spelling"))
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .unwrap();
+    (String::from_utf8(output.stdout).unwrap(), output.status.code())
+}
+
+#[test]
+fn skips_numbers_and_counts_display_columns() {
+    let directory = directory(
+        "columns",
+        &[("words.txt", "thin\nthing"), ("text.txt", "thin 42 thng 2024\n漢字 thng\n")],
+    );
+    let (stdout, code) = run(&directory, &["-d", "words.txt", "text.txt"]);
+    assert_eq!(
+        stdout,
+        "text.txt:1:9: thng (thing, thin)\ntext.txt:2:1: 漢字 ()\ntext.txt:2:6: thng (thing, thin)\n"
+    );
+    assert_eq!(code, Some(1));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn max_word_length() {
+    let directory = directory("max-word-length", &[("words.txt", "thin\nthing"), ("text.txt", "thng thinng\n")]);
+    let (stdout, _) = run(&directory, &["-d", "words.txt", "text.txt"]);
+    assert_eq!(stdout, "text.txt:1:1: thng (thing, thin)\ntext.txt:1:6: thinng (thing, thin)\n");
+    let (stdout, code) = run(&directory, &["-d", "words.txt", "--max-word-length", "4", "text.txt"]);
+    assert_eq!(stdout, "text.txt:1:1: thng (thing, thin)\ntext.txt:1:6: thinng ()\n");
+    assert_eq!(code, Some(1));
+    let (_, code) = run(&directory, &["-d", "words.txt", "--max-word-length", "four", "text.txt"]);
+    assert_eq!(code, Some(2));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn cache() {
+    let directory = directory("cache", &[("words.txt", "thin\nthing"), ("text.txt", "thng thin\n")]);
+    let args = ["-d", "words.txt", "--cache", "cache", "text.txt"];
+    let (stdout, code) = run(&directory, &args);
+    assert_eq!(stdout, "text.txt:1:1: thng (thing, thin)\n");
+    assert_eq!(code, Some(1));
+    // suggestions come from the file on the next run
+    let cache = std::fs::read_to_string(directory.join("cache")).unwrap();
+    assert!(cache.ends_with("\n2\tthng\tthing\tthin\n"));
+    std::fs::write(directory.join("cache"), cache.replace("\tthing\tthin", "\tthong")).unwrap();
+    let (stdout, _) = run(&directory, &args);
+    assert_eq!(stdout, "text.txt:1:1: thng (thong)\n");
+    let (_, code) = run(&directory, &["-a", "-d", "words.txt", "--cache", "cache"]);
+    assert_eq!(code, Some(2));
+    std::fs::remove_dir_all(&directory).unwrap();
+}